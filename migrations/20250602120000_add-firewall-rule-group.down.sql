@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE firewall_rule DROP CONSTRAINT firewall_rule_pkey;
+ALTER TABLE firewall_rule ADD CONSTRAINT firewall_rule_pkey PRIMARY KEY (project_id, id);
+ALTER TABLE firewall_rule DROP COLUMN IF EXISTS firewall_group_id;
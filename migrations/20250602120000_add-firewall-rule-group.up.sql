@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+-- Rule ids are only unique within their firewall group
+ALTER TABLE firewall_rule ADD COLUMN firewall_group_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000';
+ALTER TABLE firewall_rule ALTER COLUMN firewall_group_id DROP DEFAULT;
+ALTER TABLE firewall_rule DROP CONSTRAINT firewall_rule_pkey;
+ALTER TABLE firewall_rule ADD CONSTRAINT firewall_rule_pkey PRIMARY KEY (project_id, firewall_group_id, id);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE managed_database DROP COLUMN IF EXISTS vpc_id;
+ALTER TABLE compute DROP COLUMN IF EXISTS vpc_id;
+DROP TABLE IF EXISTS vpc;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'vpc';
+
+CREATE TABLE IF NOT EXISTS vpc (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    region VARCHAR(255) NOT NULL,
+    id UUID NOT NULL,
+    description TEXT NOT NULL,
+    v4_subnet VARCHAR(255) NOT NULL,
+    v4_subnet_mask BIGINT NOT NULL,
+    CONSTRAINT vpc_pkey PRIMARY KEY (project_id, id),
+    CONSTRAINT vpc_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
+
+-- Empty when the node is not in a VPC
+ALTER TABLE compute ADD COLUMN vpc_id VARCHAR(255) NOT NULL DEFAULT '';
+ALTER TABLE managed_database ADD COLUMN vpc_id VARCHAR(255) NOT NULL DEFAULT '';
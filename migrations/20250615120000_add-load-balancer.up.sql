@@ -0,0 +1,20 @@
+-- Add up migration script here
+
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'load_balancer';
+
+CREATE TABLE IF NOT EXISTS load_balancer (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    region VARCHAR(255) NOT NULL,
+    id UUID NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    status VARCHAR(255) NOT NULL,
+    ipv4 VARCHAR(255) NOT NULL,
+    has_ssl BOOLEAN NOT NULL,
+    forwarding_rules JSONB NOT NULL,
+    health_check JSONB NOT NULL,
+    instances UUID[] NOT NULL,
+    CONSTRAINT load_balancer_pkey PRIMARY KEY (project_id, id),
+    CONSTRAINT load_balancer_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
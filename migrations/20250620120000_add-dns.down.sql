@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS dns_record;
+DROP TABLE IF EXISTS dns_domain;
@@ -0,0 +1,31 @@
+-- Add up migration script here
+
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'dns_domain';
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'dns_record';
+
+CREATE TABLE IF NOT EXISTS dns_domain (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    id VARCHAR(255) NOT NULL, -- The domain name, Vultr has no other id for it
+    dns_sec VARCHAR(255) NOT NULL,
+    CONSTRAINT dns_domain_pkey PRIMARY KEY (project_id, id),
+    CONSTRAINT dns_domain_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS dns_record (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    domain VARCHAR(255) NOT NULL,
+    id UUID NOT NULL,
+    record_type VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    data TEXT NOT NULL,
+    priority BIGINT NOT NULL,
+    ttl BIGINT NOT NULL,
+    -- Compute or load balancer whose address the record holds
+    target_id UUID,
+    CONSTRAINT dns_record_pkey PRIMARY KEY (project_id, domain, id),
+    CONSTRAINT dns_record_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS startup_script;
+DROP TABLE IF EXISTS ssh_key;
@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS ssh_key (
+    project_id UUID NOT NULL,
+    id UUID NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    ssh_key TEXT NOT NULL,
+    update_dt TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT ssh_key_pkey PRIMARY KEY (project_id, id),
+    CONSTRAINT ssh_key_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS startup_script (
+    project_id UUID NOT NULL,
+    id UUID NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    script_type VARCHAR(255) NOT NULL,
+    script TEXT NOT NULL,
+    update_dt TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT startup_script_pkey PRIMARY KEY (project_id, id),
+    CONSTRAINT startup_script_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
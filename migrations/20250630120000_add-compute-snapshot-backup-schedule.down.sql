@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+ALTER TABLE compute DROP COLUMN IF EXISTS backup_schedule;
+DROP TABLE IF EXISTS compute_snapshot;
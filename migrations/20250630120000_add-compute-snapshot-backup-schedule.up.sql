@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'compute_snapshot';
+
+CREATE TABLE IF NOT EXISTS compute_snapshot (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    id UUID NOT NULL,
+    instance_id UUID NULL,
+    description VARCHAR(255) NOT NULL,
+    status VARCHAR(255) NOT NULL,
+    size BIGINT NOT NULL,
+    date_created VARCHAR(255) NOT NULL,
+    CONSTRAINT compute_snapshot_pkey PRIMARY KEY (project_id, id),
+    CONSTRAINT compute_snapshot_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
+
+ALTER TABLE compute ADD COLUMN IF NOT EXISTS backup_schedule JSONB NULL;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS connection_pool;
+DROP TABLE IF EXISTS logical_database;
+DROP TABLE IF EXISTS database_user;
+ALTER TABLE managed_database DROP COLUMN IF EXISTS trusted_ips;
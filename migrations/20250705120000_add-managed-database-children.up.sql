@@ -0,0 +1,43 @@
+-- Add up migration script here
+
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'database_user';
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'logical_database';
+ALTER TYPE resource_type ADD VALUE IF NOT EXISTS 'connection_pool';
+
+ALTER TABLE managed_database ADD COLUMN IF NOT EXISTS trusted_ips TEXT[] NOT NULL DEFAULT '{}';
+
+-- Passwords are never stored, Vultr keeps them
+CREATE TABLE IF NOT EXISTS database_user (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    database_id UUID NOT NULL,
+    id VARCHAR(255) NOT NULL, -- The user name, Vultr has no other id for it
+    encryption VARCHAR(255), -- MySQL only
+    CONSTRAINT database_user_pkey PRIMARY KEY (project_id, database_id, id),
+    CONSTRAINT database_user_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS logical_database (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    database_id UUID NOT NULL,
+    id VARCHAR(255) NOT NULL, -- The database name
+    CONSTRAINT logical_database_pkey PRIMARY KEY (project_id, database_id, id),
+    CONSTRAINT logical_database_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS connection_pool (
+    project_id UUID NOT NULL,
+    y BIGINT NOT NULL,
+    x BIGINT NOT NULL,
+    database_id UUID NOT NULL,
+    id VARCHAR(255) NOT NULL, -- The pool name
+    database VARCHAR(255) NOT NULL,
+    username VARCHAR(255) NOT NULL,
+    mode VARCHAR(255) NOT NULL,
+    size BIGINT NOT NULL,
+    CONSTRAINT connection_pool_pkey PRIMARY KEY (project_id, database_id, id),
+    CONSTRAINT connection_pool_project_id_fkey FOREIGN KEY (project_id) REFERENCES project(id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS object_storage_credential (
+    project_id UUID NOT NULL,
+    object_storage_id UUID NOT NULL,
+    s3_hostname VARCHAR(255) NOT NULL,
+    s3_access_key VARCHAR(255) NOT NULL,
+    s3_secret_key BYTEA NOT NULL, -- Encrypted, nonce followed by the sealed key
+    update_dt TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT object_storage_credential_pkey PRIMARY KEY (project_id, object_storage_id),
+    CONSTRAINT object_storage_credential_object_storage_fkey FOREIGN KEY (project_id, object_storage_id) REFERENCES object_storage(project_id, id) ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS refresh_token (
+    jti UUID PRIMARY KEY,
+    family_id UUID NOT NULL, -- Shared by the tokens rotated from one login
+    user_id UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_dt TIMESTAMPTZ NULL,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE,
+    create_dt TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT refresh_token_user_id_fkey FOREIGN KEY (user_id) REFERENCES account_user(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS refresh_token_family_id_idx ON refresh_token (family_id);
+CREATE INDEX IF NOT EXISTS refresh_token_user_id_idx ON refresh_token (user_id);
@@ -57,6 +57,30 @@ impl IntoResponse for ServiceError {
                 format!("Error while handling pem key: {}", err),
             )
                 .into_response(),
+            Self::InvalidCommand(err) => (StatusCode::BAD_REQUEST, err).into_response(),
+            Self::VultrApiError(err) => {
+                // Vultr rejecting the request is the caller's to fix, anything else is upstream
+                let status = if (400..500).contains(&err.status) {
+                    StatusCode::BAD_REQUEST
+                } else {
+                    StatusCode::BAD_GATEWAY
+                };
+                (status, Json(json!({ "error": err }))).into_response()
+            }
+            Self::DeployInProgress(job_id) => (
+                StatusCode::CONFLICT,
+                format!("Deploy job {} is still running", job_id),
+            )
+                .into_response(),
+            Self::DeploymentFailed(cause, compensation) => {
+                let error = format!("{:?}", cause);
+                let status = cause.into_response().status();
+                (
+                    status,
+                    Json(json!({ "error": error, "compensation": compensation })),
+                )
+                    .into_response()
+            }
         }
     }
 }
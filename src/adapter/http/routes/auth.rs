@@ -1,19 +1,29 @@
-use axum::{routing::post, Json, Router};
+use axum::{
+    routing::{post, put},
+    Extension, Json, Router,
+};
 use uuid::Uuid;
 
 use crate::{
     adapter::http::conversion::WebResponse,
     domain::auth::{
-        commands::{CheckVerification, CreateUserAccount, IssueTokens, RefreshTokens},
+        commands::{
+            ChangePassword, CheckVerification, CreateUserAccount, IssueTokens, Logout,
+            RefreshTokens, RequestPasswordReset, ResetPassword,
+        },
         AuthenticationTokens,
     },
     errors::ServiceError,
     service::auth::{
-        handle_check_verification_email, handle_create_user_account, handle_issue_tokens,
-        handle_refresh_tokens,
+        handle_change_password, handle_check_verification_email, handle_create_user_account,
+        handle_issue_tokens, handle_logout, handle_logout_all, handle_refresh_tokens,
+        handle_request_password_reset, handle_reset_password,
     },
+    CurrentUser,
 };
 
+use super::middleware::auth_middleware;
+
 /// Create User Account (Sign up)
 #[axum::debug_handler]
 #[utoipa::path(
@@ -67,6 +77,83 @@ pub async fn refresh_tokens(
     Ok(WebResponse(tokens))
 }
 
+/// Log out, the refresh token and the ones rotated with it stop working
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/auth/logout",
+    request_body(content = Logout, content_type = "application/json"),
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn logout(Json(cmd): Json<Logout>) -> Result<(), ServiceError> {
+    handle_logout(cmd).await
+}
+
+/// Log out of all sessions
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/auth/logout/all",
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn logout_all(
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), ServiceError> {
+    handle_logout_all(current_user).await
+}
+
+/// Forgot password, a reset code is emailed if the account exists
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/auth/password/forgot",
+    request_body(content = RequestPasswordReset, content_type = "application/json"),
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn request_password_reset(
+    Json(cmd): Json<RequestPasswordReset>,
+) -> Result<(), ServiceError> {
+    handle_request_password_reset(cmd).await
+}
+
+/// Reset password with the emailed code, every session is signed out
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/auth/password/reset",
+    request_body(content = ResetPassword, content_type = "application/json"),
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn reset_password(Json(cmd): Json<ResetPassword>) -> Result<(), ServiceError> {
+    handle_reset_password(cmd).await
+}
+
+/// Change password, other sessions are signed out
+#[axum::debug_handler]
+#[utoipa::path(
+    put,
+    path = "/external/auth/password",
+    request_body(content = ChangePassword, content_type = "application/json"),
+    responses(
+        (status = 200, body = AuthenticationTokens)
+    )
+)]
+pub async fn change_password(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<ChangePassword>,
+) -> Result<WebResponse<AuthenticationTokens>, ServiceError> {
+    let tokens = handle_change_password(cmd, current_user).await?;
+    Ok(WebResponse(tokens))
+}
+
 /// Check email verification
 #[axum::debug_handler]
 #[utoipa::path(
@@ -87,9 +174,21 @@ pub fn auth_router() -> Router {
     Router::new()
         .route("/external/auth/login", post(issue_tokens))
         .route("/external/auth/refresh", post(refresh_tokens))
+        .route("/external/auth/logout", post(logout))
+        .route(
+            "/external/auth/password/forgot",
+            post(request_password_reset),
+        )
+        .route("/external/auth/password/reset", post(reset_password))
         .route("/external/auth/account", post(create_user_account))
         .route(
             "/external/auth/verification/check",
             post(check_verification_email),
         )
+        .merge(
+            Router::new()
+                .route("/external/auth/logout/all", post(logout_all))
+                .route("/external/auth/password", put(change_password))
+                .route_layer(axum::middleware::from_fn(auth_middleware)),
+        )
 }
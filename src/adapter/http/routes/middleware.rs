@@ -21,8 +21,9 @@ pub async fn auth_middleware(
         .and_then(|header| header.to_str().ok())
         .ok_or(StatusCode::UNAUTHORIZED)?
         .trim_start_matches("Bearer ");
+    // Refresh tokens only buy new tokens, they don't authenticate requests
     let claims = jwt
-        .verify_token(token)
+        .verify_access_token(token)
         .map_err(|_| StatusCode::UNAUTHORIZED)?;
     let current_user = CurrentUser {
         email: claims.email,
@@ -15,13 +15,30 @@ use crate::{
             ArchitectureRecommendation, RequestArchitectureSuggestion,
         },
     },
-    domain::project::commands::{
-        AssignRole, CreateProject, DeleteProject, DeployProject, ExpelMember, RegisterVultApiKey,
+    domain::project::{
+        commands::{
+            AssignRole, CreateBucket, CreateProject, DeleteProject, DeployProject, EditSshKey,
+            EditStartupScript, ExpelMember, RegisterSshKey, RegisterStartupScript,
+            RegisterVultApiKey, ResourceResponse,
+        },
+        deploy_job::DeployJob,
+        drift::DriftReport,
+        import::{ImportCandidate, ImportResources},
+        object_storage::{Bucket, S3Credentials},
+        plan::DeployPlan,
+        provisioning::{SshKey, StartupScript},
     },
     errors::ServiceError,
     service::project::{
-        handle_assign_role, handle_create_project, handle_delete_project, handle_deploy_project,
-        handle_expel_member, handle_get_public_key, handle_register_vultr_api_key,
+        handle_assign_role, handle_create_bucket, handle_create_project, handle_delete_bucket,
+        handle_delete_project, handle_delete_ssh_key, handle_delete_startup_script,
+        handle_deploy_job_sse, handle_deploy_project, handle_detect_drift, handle_edit_ssh_key,
+        handle_edit_startup_script, handle_expel_member, handle_get_deploy_job,
+        handle_get_object_storage_credentials, handle_get_public_key, handle_import_resources,
+        handle_list_buckets, handle_list_import_candidates, handle_list_ssh_keys,
+        handle_list_startup_scripts, handle_plan_deploy_project, handle_reconcile_drift,
+        handle_regenerate_object_storage_keys, handle_register_ssh_key,
+        handle_register_startup_script, handle_register_vultr_api_key,
         handle_request_architecture_suggestion, handle_session_sse,
     },
     CurrentUser,
@@ -135,6 +152,234 @@ pub async fn register_vult_api_key(
     Ok(())
 }
 
+/// Register an SSH key at Vultr for the project's compute
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/ssh-key",
+    request_body(content = RegisterSshKey, content_type = "application/json"),
+    responses(
+        (status = 200, body = SshKey)
+    )
+)]
+pub async fn register_ssh_key(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<RegisterSshKey>,
+) -> Result<WebResponse<SshKey>, ServiceError> {
+    let ssh_key = handle_register_ssh_key(cmd, current_user).await?;
+    Ok(WebResponse(ssh_key))
+}
+
+/// List SSH keys of the project
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/ssh-key",
+    responses(
+        (status = 200, body = Vec<SshKey>)
+    )
+)]
+pub async fn list_ssh_keys(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(project_id): Path<Uuid>,
+) -> Result<WebResponse<Vec<SshKey>>, ServiceError> {
+    let ssh_keys = handle_list_ssh_keys(project_id, current_user).await?;
+    Ok(WebResponse(ssh_keys))
+}
+
+/// Rename or replace an SSH key
+#[axum::debug_handler]
+#[utoipa::path(
+    put,
+    path = "/external/project/ssh-key",
+    request_body(content = EditSshKey, content_type = "application/json"),
+    responses(
+        (status = 200, body = SshKey)
+    )
+)]
+pub async fn edit_ssh_key(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<EditSshKey>,
+) -> Result<WebResponse<SshKey>, ServiceError> {
+    let ssh_key = handle_edit_ssh_key(cmd, current_user).await?;
+    Ok(WebResponse(ssh_key))
+}
+
+/// Delete an SSH key at Vultr and from the project
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/external/project/{project_id}/ssh-key/{ssh_key_id}",
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn delete_ssh_key(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, ssh_key_id)): Path<(Uuid, Uuid)>,
+) -> Result<(), ServiceError> {
+    handle_delete_ssh_key(project_id, ssh_key_id, current_user).await?;
+    Ok(())
+}
+
+/// Register a startup script at Vultr for the project's compute
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/startup-script",
+    request_body(content = RegisterStartupScript, content_type = "application/json"),
+    responses(
+        (status = 200, body = StartupScript)
+    )
+)]
+pub async fn register_startup_script(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<RegisterStartupScript>,
+) -> Result<WebResponse<StartupScript>, ServiceError> {
+    let startup_script = handle_register_startup_script(cmd, current_user).await?;
+    Ok(WebResponse(startup_script))
+}
+
+/// List startup scripts of the project
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/startup-script",
+    responses(
+        (status = 200, body = Vec<StartupScript>)
+    )
+)]
+pub async fn list_startup_scripts(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(project_id): Path<Uuid>,
+) -> Result<WebResponse<Vec<StartupScript>>, ServiceError> {
+    let startup_scripts = handle_list_startup_scripts(project_id, current_user).await?;
+    Ok(WebResponse(startup_scripts))
+}
+
+/// Rename or rewrite a startup script
+#[axum::debug_handler]
+#[utoipa::path(
+    put,
+    path = "/external/project/startup-script",
+    request_body(content = EditStartupScript, content_type = "application/json"),
+    responses(
+        (status = 200, body = StartupScript)
+    )
+)]
+pub async fn edit_startup_script(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<EditStartupScript>,
+) -> Result<WebResponse<StartupScript>, ServiceError> {
+    let startup_script = handle_edit_startup_script(cmd, current_user).await?;
+    Ok(WebResponse(startup_script))
+}
+
+/// Delete a startup script at Vultr and from the project
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/external/project/{project_id}/startup-script/{script_id}",
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn delete_startup_script(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, script_id)): Path<(Uuid, Uuid)>,
+) -> Result<(), ServiceError> {
+    handle_delete_startup_script(project_id, script_id, current_user).await?;
+    Ok(())
+}
+
+/// S3 credentials of an object storage, for project admins only
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/object-storage/{object_storage_id}/credentials",
+    responses(
+        (status = 200, body = S3Credentials)
+    )
+)]
+pub async fn get_object_storage_credentials(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, object_storage_id)): Path<(Uuid, Uuid)>,
+) -> Result<WebResponse<S3Credentials>, ServiceError> {
+    let credentials =
+        handle_get_object_storage_credentials(project_id, object_storage_id, current_user).await?;
+    Ok(WebResponse(credentials))
+}
+
+/// Replace the S3 keys of an object storage, the previous ones stop working
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/{project_id}/object-storage/{object_storage_id}/credentials",
+    responses(
+        (status = 200, body = S3Credentials)
+    )
+)]
+pub async fn regenerate_object_storage_keys(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, object_storage_id)): Path<(Uuid, Uuid)>,
+) -> Result<WebResponse<S3Credentials>, ServiceError> {
+    let credentials =
+        handle_regenerate_object_storage_keys(project_id, object_storage_id, current_user).await?;
+    Ok(WebResponse(credentials))
+}
+
+/// List buckets of an object storage
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/object-storage/{object_storage_id}/bucket",
+    responses(
+        (status = 200, body = Vec<Bucket>)
+    )
+)]
+pub async fn list_buckets(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, object_storage_id)): Path<(Uuid, Uuid)>,
+) -> Result<WebResponse<Vec<Bucket>>, ServiceError> {
+    let buckets = handle_list_buckets(project_id, object_storage_id, current_user).await?;
+    Ok(WebResponse(buckets))
+}
+
+/// Create a bucket in an object storage
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/bucket",
+    request_body(content = CreateBucket, content_type = "application/json"),
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn create_bucket(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<CreateBucket>,
+) -> Result<(), ServiceError> {
+    handle_create_bucket(cmd, current_user).await?;
+    Ok(())
+}
+
+/// Delete an empty bucket of an object storage
+#[axum::debug_handler]
+#[utoipa::path(
+    delete,
+    path = "/external/project/{project_id}/object-storage/{object_storage_id}/bucket/{name}",
+    responses(
+        (status = 200, body = ())
+    )
+)]
+pub async fn delete_bucket(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, object_storage_id, name)): Path<(Uuid, Uuid, String)>,
+) -> Result<(), ServiceError> {
+    handle_delete_bucket(project_id, object_storage_id, name, current_user).await?;
+    Ok(())
+}
+
 use axum::response::sse::{Event, KeepAlive, Sse};
 use futures_util::stream::Stream;
 use std::convert::Infallible;
@@ -154,9 +399,13 @@ async fn session_sse(
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let stream = async_stream::try_stream! {
         let mut last_update_dt = Utc::with_ymd_and_hms(&Utc, 2000, 1, 1, 0, 0, 0).unwrap();
+        let mut last_job_update_dt = last_update_dt;
         loop {
             let message = handle_session_sse(&current_user, project_id, &mut last_update_dt).await.unwrap();
             yield Event::default().json_data(message).unwrap();
+            if let Some(job) = handle_deploy_job_sse(&current_user, project_id, &mut last_job_update_dt).await.unwrap() {
+                yield Event::default().event("deploy_job").json_data(job).unwrap();
+            }
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
     };
@@ -171,15 +420,120 @@ async fn session_sse(
     path = "/external/project/deploy",
     request_body(content = DeployProject, content_type = "application/json"),
     responses(
-        (status = 200, body = ())
+        (status = 200, body = Uuid),
+        (status = 409, description = "Another deploy of the project is still running")
     )
 )]
 pub async fn deploy_project(
     Extension(current_user): Extension<CurrentUser>,
     Json(cmd): Json<DeployProject>,
-) -> Result<(), ServiceError> {
-    handle_deploy_project(cmd, current_user).await?;
-    Ok(())
+) -> Result<WebResponse<Uuid>, ServiceError> {
+    let job_id = handle_deploy_project(cmd, current_user).await?;
+    Ok(WebResponse(job_id))
+}
+
+/// Get deploy job status
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/deploy/{job_id}",
+    responses(
+        (status = 200, body = DeployJob)
+    )
+)]
+pub async fn get_deploy_job(
+    Extension(current_user): Extension<CurrentUser>,
+    Path((project_id, job_id)): Path<(Uuid, Uuid)>,
+) -> Result<WebResponse<DeployJob>, ServiceError> {
+    let job = handle_get_deploy_job(project_id, job_id, current_user).await?;
+    Ok(WebResponse(job))
+}
+
+/// Plan project deployment without calling Vultr
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/deploy/plan",
+    request_body(content = DeployProject, content_type = "application/json"),
+    responses(
+        (status = 200, body = DeployPlan)
+    )
+)]
+pub async fn plan_deploy_project(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<DeployProject>,
+) -> Result<WebResponse<DeployPlan>, ServiceError> {
+    let plan = handle_plan_deploy_project(cmd, current_user).await?;
+    Ok(WebResponse(plan))
+}
+
+/// Compare the stored diagram with the live Vultr resources
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/drift",
+    responses(
+        (status = 200, body = DriftReport)
+    )
+)]
+pub async fn detect_drift(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(project_id): Path<Uuid>,
+) -> Result<WebResponse<DriftReport>, ServiceError> {
+    let report = handle_detect_drift(project_id, current_user).await?;
+    Ok(WebResponse(report))
+}
+
+/// Overwrite the stored diagram with the live Vultr resources
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/{project_id}/drift/apply",
+    responses(
+        (status = 200, body = DriftReport)
+    )
+)]
+pub async fn reconcile_drift(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(project_id): Path<Uuid>,
+) -> Result<WebResponse<DriftReport>, ServiceError> {
+    let report = handle_reconcile_drift(project_id, current_user).await?;
+    Ok(WebResponse(report))
+}
+
+/// List resources of the project's Vultr account that can be imported
+#[axum::debug_handler]
+#[utoipa::path(
+    get,
+    path = "/external/project/{project_id}/import",
+    responses(
+        (status = 200, body = Vec<ImportCandidate>)
+    )
+)]
+pub async fn list_import_candidates(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(project_id): Path<Uuid>,
+) -> Result<WebResponse<Vec<ImportCandidate>>, ServiceError> {
+    let candidates = handle_list_import_candidates(project_id, current_user).await?;
+    Ok(WebResponse(candidates))
+}
+
+/// Import selected Vultr resources into the project diagram
+#[axum::debug_handler]
+#[utoipa::path(
+    post,
+    path = "/external/project/import",
+    request_body(content = ImportResources, content_type = "application/json"),
+    responses(
+        (status = 200, body = Vec<ResourceResponse>)
+    )
+)]
+pub async fn import_resources(
+    Extension(current_user): Extension<CurrentUser>,
+    Json(cmd): Json<ImportResources>,
+) -> Result<WebResponse<Vec<ResourceResponse>>, ServiceError> {
+    let adopted = handle_import_resources(cmd, current_user).await?;
+    Ok(WebResponse(adopted))
 }
 
 /// Request architecture suggestion
@@ -209,6 +563,16 @@ pub fn project_router() -> Router {
         .route("/external/project/public-key", get(get_public_key))
         .route("/external/project/vult-api-key", put(register_vult_api_key))
         .route("/external/project/deploy", post(deploy_project))
+        .route("/external/project/deploy/plan", post(plan_deploy_project))
+        .route("/external/project/import", post(import_resources))
+        .route(
+            "/external/project/ssh-key",
+            post(register_ssh_key).put(edit_ssh_key),
+        )
+        .route(
+            "/external/project/startup-script",
+            post(register_startup_script).put(edit_startup_script),
+        )
         .route(
             "/external/project/{project_id}/member/{email}",
             delete(expel_member),
@@ -217,6 +581,45 @@ pub fn project_router() -> Router {
             "/external/project/{project_id}/architecture/suggestion",
             post(request_architecture_suggestion),
         )
+        .route(
+            "/external/project/{project_id}/deploy/{job_id}",
+            get(get_deploy_job),
+        )
+        .route("/external/project/{project_id}/drift", get(detect_drift))
+        .route(
+            "/external/project/{project_id}/import",
+            get(list_import_candidates),
+        )
+        .route(
+            "/external/project/{project_id}/drift/apply",
+            post(reconcile_drift),
+        )
+        .route("/external/project/{project_id}/ssh-key", get(list_ssh_keys))
+        .route(
+            "/external/project/{project_id}/ssh-key/{ssh_key_id}",
+            delete(delete_ssh_key),
+        )
+        .route(
+            "/external/project/{project_id}/startup-script",
+            get(list_startup_scripts),
+        )
+        .route(
+            "/external/project/{project_id}/startup-script/{script_id}",
+            delete(delete_startup_script),
+        )
+        .route("/external/project/bucket", post(create_bucket))
+        .route(
+            "/external/project/{project_id}/object-storage/{object_storage_id}/credentials",
+            get(get_object_storage_credentials).post(regenerate_object_storage_keys),
+        )
+        .route(
+            "/external/project/{project_id}/object-storage/{object_storage_id}/bucket",
+            get(list_buckets),
+        )
+        .route(
+            "/external/project/{project_id}/object-storage/{object_storage_id}/bucket/{name}",
+            delete(delete_bucket),
+        )
         .route("/external/project/{project_id}/session", get(session_sse))
         .route("/external/project/{project_id}", delete(delete_project))
         .route_layer(axum::middleware::from_fn(auth_middleware))
@@ -1,14 +1,26 @@
 use crate::adapter::request_dispensor::architector_server::RequestArchitectureSuggestion;
 use crate::domain::{
     auth::{
-        commands::{CheckVerification, CreateUserAccount, IssueTokens, RefreshTokens},
+        commands::{
+            ChangePassword, CheckVerification, CreateUserAccount, IssueTokens, Logout,
+            RefreshTokens, RequestPasswordReset, ResetPassword,
+        },
         AuthenticationTokens,
     },
     project::{
         commands::{
-            AssignRole, CreateProject, DeleteProject, DeployProject, ExpelMember,
+            AssignRole, CreateBucket, CreateProject, DeleteProject, DeployProject, EditSshKey,
+            EditStartupScript, ExpelMember, RegisterSshKey, RegisterStartupScript,
             RegisterVultApiKey,
         },
+        compensation::{CompensationReport, CompensationResult, CompletedStep},
+        deploy_job::{DeployCommandStatus, DeployJob, DeployStatus},
+        drift::{DriftReport, DriftStatus, FieldDrift, ResourceDrift},
+        import::{ImportCandidate, ImportResources, ImportSelection},
+        object_storage::{Bucket, S3Credentials},
+        plan::{DeployPlan, PlanValidationError, PlannedChange, UnresolvedReference},
+        provisioning::{SshKey, StartupScript},
+        vultr_command::VultrCommand,
         UserRole,
     },
 };
@@ -23,6 +35,11 @@ use utoipa::OpenApi;
         auth::issue_tokens,
         auth::check_verification_email,
         auth::refresh_tokens,
+        auth::logout,
+        auth::logout_all,
+        auth::request_password_reset,
+        auth::reset_password,
+        auth::change_password,
     ),
     components(
         schemas(
@@ -30,6 +47,10 @@ use utoipa::OpenApi;
             IssueTokens,
             CheckVerification,
             RefreshTokens,
+            Logout,
+            RequestPasswordReset,
+            ResetPassword,
+            ChangePassword,
             AuthenticationTokens,
         )
     ),
@@ -50,6 +71,25 @@ pub struct AuthDoc;
         project::register_vult_api_key,
         project::session_sse,
         project::deploy_project,
+        project::plan_deploy_project,
+        project::get_deploy_job,
+        project::detect_drift,
+        project::reconcile_drift,
+        project::list_import_candidates,
+        project::import_resources,
+        project::register_ssh_key,
+        project::list_ssh_keys,
+        project::edit_ssh_key,
+        project::delete_ssh_key,
+        project::register_startup_script,
+        project::list_startup_scripts,
+        project::edit_startup_script,
+        project::delete_startup_script,
+        project::get_object_storage_credentials,
+        project::regenerate_object_storage_keys,
+        project::list_buckets,
+        project::create_bucket,
+        project::delete_bucket,
         project::request_architecture_suggestion,
     ),
     components(
@@ -59,6 +99,33 @@ pub struct AuthDoc;
             CreateProject,
             DeleteProject,
             DeployProject,
+            VultrCommand,
+            DeployPlan,
+            PlannedChange,
+            UnresolvedReference,
+            PlanValidationError,
+            DeployJob,
+            DeployCommandStatus,
+            DeployStatus,
+            CompensationReport,
+            CompensationResult,
+            CompletedStep,
+            DriftReport,
+            ResourceDrift,
+            DriftStatus,
+            FieldDrift,
+            ImportCandidate,
+            ImportResources,
+            ImportSelection,
+            RegisterSshKey,
+            EditSshKey,
+            SshKey,
+            RegisterStartupScript,
+            EditStartupScript,
+            StartupScript,
+            CreateBucket,
+            S3Credentials,
+            Bucket,
             UserRole,
             RegisterVultApiKey,
             RequestArchitectureSuggestion,
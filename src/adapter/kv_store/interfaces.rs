@@ -1,4 +1,9 @@
-use crate::{domain::auth::private_key::PublicKey, errors::ServiceError};
+use uuid::Uuid;
+
+use crate::{
+    domain::{auth::private_key::PublicKey, project::deploy_job::DeployJob},
+    errors::ServiceError,
+};
 
 // TODO: Add a cleanup method to the trait
 pub(crate) trait KVStore {
@@ -14,6 +19,17 @@ pub(crate) trait VultrKeyPairStore: KVStore {
     async fn get_or_create_public_key(&self) -> Result<PublicKey, ServiceError>;
 }
 
+pub(crate) trait DeployJobStore: KVStore {
+    async fn save_deploy_job(&self, job: &DeployJob) -> Result<(), ServiceError>;
+    async fn get_deploy_job(
+        &self,
+        project_id: Uuid,
+        job_id: Uuid,
+    ) -> Result<DeployJob, ServiceError>;
+    async fn get_latest_deploy_job(&self, project_id: Uuid) -> Result<DeployJob, ServiceError>;
+    async fn list_latest_deploy_jobs(&self) -> Result<Vec<DeployJob>, ServiceError>;
+}
+
 // #[allow(unused)]
 // pub(crate) trait SessionStore: KVStore {
 //     const LOCK_NAME: &'static str = "lock";
@@ -1,14 +1,23 @@
 use rocksdb::DB;
-use std::sync::{Arc, LazyLock};
+use std::{
+    str::FromStr,
+    sync::{Arc, LazyLock},
+};
 use tokio::sync::Mutex;
+use uuid::Uuid;
 
 use crate::{
     config::get_config,
-    domain::auth::private_key::{PublicKey, VultrKeyPair},
+    domain::{
+        auth::private_key::{PublicKey, VultrKeyPair},
+        project::deploy_job::{
+            get_deploy_job_key, get_latest_deploy_job_key, DeployJob, LATEST_DEPLOY_JOB_KEY_PREFIX,
+        },
+    },
     errors::ServiceError,
 };
 
-use super::interfaces::{KVStore, VultrKeyPairStore};
+use super::interfaces::{DeployJobStore, KVStore, VultrKeyPairStore};
 
 pub(crate) struct RocksDB {
     pub(crate) db: Mutex<DB>,
@@ -80,6 +89,56 @@ impl VultrKeyPairStore for RocksDB {
     }
 }
 
+impl DeployJobStore for RocksDB {
+    async fn save_deploy_job(&self, job: &DeployJob) -> Result<(), ServiceError> {
+        let key = get_deploy_job_key(job.project_id, job.id);
+        self.insert(key.as_bytes(), &serde_json::to_vec(job)?)
+            .await?;
+        let key = get_latest_deploy_job_key(job.project_id);
+        self.insert(key.as_bytes(), job.id.as_bytes()).await
+    }
+
+    async fn get_deploy_job(
+        &self,
+        project_id: Uuid,
+        job_id: Uuid,
+    ) -> Result<DeployJob, ServiceError> {
+        let key = get_deploy_job_key(project_id, job_id);
+        let job = self.get(key.as_bytes()).await?;
+        Ok(serde_json::from_slice(&job)?)
+    }
+
+    async fn get_latest_deploy_job(&self, project_id: Uuid) -> Result<DeployJob, ServiceError> {
+        let key = get_latest_deploy_job_key(project_id);
+        let job_id = self.get(key.as_bytes()).await?;
+        let job_id =
+            Uuid::from_slice(&job_id).map_err(|err| ServiceError::ParsingError(Box::new(err)))?;
+        self.get_deploy_job(project_id, job_id).await
+    }
+
+    async fn list_latest_deploy_jobs(&self) -> Result<Vec<DeployJob>, ServiceError> {
+        let mut project_ids = Vec::new();
+        {
+            let db = self.db.lock().await;
+            for item in db.iterator(rocksdb::IteratorMode::Start) {
+                let (key, _) = item.map_err(|err| ServiceError::KVStoreError(Box::new(err)))?;
+                if let Some(project_id) = std::str::from_utf8(&key)
+                    .ok()
+                    .and_then(|key| key.strip_prefix(LATEST_DEPLOY_JOB_KEY_PREFIX))
+                    .and_then(|project_id| Uuid::from_str(project_id).ok())
+                {
+                    project_ids.push(project_id);
+                }
+            }
+        }
+        let mut jobs = Vec::new();
+        for project_id in project_ids {
+            jobs.push(self.get_latest_deploy_job(project_id).await?);
+        }
+        Ok(jobs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,8 +150,7 @@ mod tests {
         let db = rocks_db.db.lock().await;
 
         // Delete all key-value pairs using iterator
-        let mut iter = db.iterator(rocksdb::IteratorMode::Start);
-        while let Some(item) = iter.next() {
+        for item in db.iterator(rocksdb::IteratorMode::Start) {
             let (key, _) = item.unwrap();
             db.delete(key.as_ref()).expect("Failed to delete key");
         }
@@ -116,9 +174,8 @@ mod tests {
 
         // THEN
         let db = rocks_db.db.lock().await;
-        let mut iter = db.iterator(rocksdb::IteratorMode::Start);
         let mut found = false;
-        while let Some(item) = iter.next() {
+        for item in db.iterator(rocksdb::IteratorMode::Start) {
             let (k, v) = item.unwrap();
             if k.as_ref() == key {
                 found = true;
@@ -206,4 +263,52 @@ mod tests {
             rocks_db.get(RocksDB::PUBLIC_KEY_NAME).await.unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn test_save_and_get_deploy_job() {
+        // GIVEN
+        let rocks_db = get_rocks_db().await;
+        let project_id = Uuid::new_v4();
+        let first_job = DeployJob::new(project_id, "test@test.com".to_string(), &[], vec![]);
+        let second_job = DeployJob::new(project_id, "test@test.com".to_string(), &[], vec![]);
+
+        // WHEN
+        rocks_db.save_deploy_job(&first_job).await.unwrap();
+        rocks_db.save_deploy_job(&second_job).await.unwrap();
+
+        // THEN
+        let job = rocks_db
+            .get_deploy_job(project_id, first_job.id)
+            .await
+            .unwrap();
+        assert_eq!(job.id, first_job.id);
+        let latest_job = rocks_db.get_latest_deploy_job(project_id).await.unwrap();
+        assert_eq!(latest_job.id, second_job.id);
+        assert!(matches!(
+            rocks_db.get_deploy_job(Uuid::new_v4(), first_job.id).await,
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_latest_deploy_jobs() {
+        // GIVEN
+        let rocks_db = get_rocks_db().await;
+        let project_id = Uuid::new_v4();
+        let first_job = DeployJob::new(project_id, "test@test.com".to_string(), &[], vec![]);
+        let second_job = DeployJob::new(project_id, "test@test.com".to_string(), &[], vec![]);
+        rocks_db.save_deploy_job(&first_job).await.unwrap();
+        rocks_db.save_deploy_job(&second_job).await.unwrap();
+
+        // WHEN
+        let jobs = rocks_db.list_latest_deploy_jobs().await.unwrap();
+
+        // THEN
+        let job_ids: Vec<Uuid> = jobs
+            .iter()
+            .filter(|job| job.project_id == project_id)
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(job_ids, vec![second_job.id]);
+    }
 }
@@ -19,6 +19,8 @@ impl<'a> Email<'a> {
 pub enum EmailType<'a> {
     VerificationCode(&'a String),
     ProjectInvitation(&'a Uuid),
+    PasswordResetCode(&'a String),
+    PasswordChanged,
 }
 
 impl EmailType<'_> {
@@ -26,6 +28,8 @@ impl EmailType<'_> {
         match self {
             EmailType::VerificationCode(_) => "[AutCloud] Verification code has arrived",
             EmailType::ProjectInvitation(_) => "[AutCloud] You have been invited to join a project",
+            EmailType::PasswordResetCode(_) => "[AutCloud] Password reset code has arrived",
+            EmailType::PasswordChanged => "[AutCloud] Your password has been changed",
         }
     }
 
@@ -39,6 +43,11 @@ impl EmailType<'_> {
                 "You have been invited to join the project\nTo accept this invitation, please visit the following URL: https://autcloud-fe.vercel.app/project/{}\n\nNote: For security reasons, you must be a registered member to join the project.",
                 project_id
             ),
+            EmailType::PasswordResetCode(code) => format!(
+                "Your password reset code: {}\n\nPlease enter this code with your new password.\nThis code is valid for 5 minutes and can be tried only once.\n\nIf you did not ask to reset your password, you can ignore this email.",
+                code
+            ),
+            EmailType::PasswordChanged => "Your password has been changed and you have been signed out of every session.\n\nIf you did not change your password, please reset it right away.".to_string(),
         }
     }
 }
@@ -89,7 +98,54 @@ impl From<lettre::error::Error> for ServiceError {
 
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
+
+    #[test]
+    fn test_password_reset_code_email() {
+        // GIVEN
+        let code = "123456".to_string();
+
+        // WHEN
+        let email_type = EmailType::PasswordResetCode(&code);
+
+        // THEN
+        assert_eq!(
+            email_type.get_subject(),
+            "[AutCloud] Password reset code has arrived"
+        );
+        assert!(email_type.get_body().contains("123456"));
+    }
+
+    #[test]
+    fn test_password_changed_email() {
+        // WHEN
+        let email_type = EmailType::PasswordChanged;
+
+        // THEN
+        assert_eq!(
+            email_type.get_subject(),
+            "[AutCloud] Your password has been changed"
+        );
+        assert!(email_type
+            .get_body()
+            .contains("signed out of every session"));
+    }
+
+    #[tokio::test]
+    async fn test_send_password_reset_code_email() {
+        // GIVEN
+        let code = "123456".to_string();
+        let email = Email::new(
+            "test@test.com".to_string(),
+            EmailType::PasswordResetCode(&code),
+        );
+
+        // WHEN
+        let result = send_email(email).await;
+
+        // THEN
+        assert!(result.is_ok());
+    }
 
     // #[tokio::test]
     // async fn test_send_email() {
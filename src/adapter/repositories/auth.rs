@@ -1,6 +1,10 @@
 use sqlx::PgConnection;
+use uuid::Uuid;
 
-use crate::{domain::auth::UserAccountAggregate, errors::ServiceError};
+use crate::{
+    domain::auth::{RefreshTokenEntity, UserAccountAggregate},
+    errors::ServiceError,
+};
 
 pub async fn insert_user_account(
     input: &UserAccountAggregate,
@@ -69,6 +73,91 @@ pub async fn update_user_account(
     Ok(())
 }
 
+pub async fn insert_refresh_token(
+    input: &RefreshTokenEntity,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "INSERT INTO refresh_token (
+            jti,
+            family_id,
+            user_id,
+            expires_at,
+            used_dt,
+            revoked,
+            create_dt
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+         ",
+        input.jti,
+        input.family_id,
+        input.user_id,
+        input.expires_at,
+        input.used_dt,
+        input.revoked,
+        input.create_dt
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn get_refresh_token(
+    jti: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<RefreshTokenEntity, ServiceError> {
+    sqlx::query_as!(
+        RefreshTokenEntity,
+        "SELECT * FROM refresh_token WHERE jti = $1",
+        jti
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(Into::<ServiceError>::into)
+}
+
+/// Mark a refresh token used. Only one caller wins, `false` means it was used or revoked before.
+pub async fn use_refresh_token(jti: &Uuid, trx: &mut PgConnection) -> Result<bool, ServiceError> {
+    let res = sqlx::query!(
+        "UPDATE refresh_token
+            SET used_dt = NOW()
+        WHERE jti = $1 AND used_dt IS NULL AND NOT revoked",
+        jti
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(res.rows_affected() == 1)
+}
+
+pub async fn revoke_refresh_token_family(
+    family_id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE refresh_token SET revoked = TRUE WHERE family_id = $1",
+        family_id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn revoke_user_refresh_tokens(
+    user_id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE refresh_token SET revoked = TRUE WHERE user_id = $1",
+        user_id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,6 +324,6 @@ mod tests {
         .unwrap();
 
         assert_eq!(updated_user.phone_num, "0987654321");
-        assert_eq!(updated_user.verified, true);
+        assert!(updated_user.verified);
     }
 }
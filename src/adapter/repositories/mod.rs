@@ -94,7 +94,12 @@ pub fn connection_pool() -> &'static sqlx::PgPool {
             .min_connections(50);
 
         if cfg!(test) {
-            pool_options = pool_options.max_connections(1).test_before_acquire(true);
+            // Every test runs on its own runtime, so connections must not outlive the one that opened them
+            pool_options = pool_options
+                .max_connections(1)
+                .min_connections(0)
+                .test_before_acquire(true)
+                .after_release(|_, _| Box::pin(async { Ok(false) }));
         }
 
         pool_options.connect_lazy_with(opts)
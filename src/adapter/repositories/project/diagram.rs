@@ -3,7 +3,9 @@ use uuid::Uuid;
 
 use crate::{
     domain::project::diagrams::{
-        BlockStorage, Compute, FirewallGroup, FirewallRule, ManagedDatabase, ObjectStorage,
+        BlockStorage, Compute, ComputeSnapshot, ConnectionPool, DatabaseUser, DnsDomain, DnsRecord,
+        FirewallGroup, FirewallRule, LoadBalancer, LogicalDatabase, ManagedDatabase,
+        ObjectPosition, ObjectStorage, Vpc,
     },
     errors::ServiceError,
 };
@@ -75,6 +77,25 @@ pub async fn update_block_storage(
     Ok(())
 }
 
+pub async fn update_block_storage_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE block_storage SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 pub async fn delete_block_storage(
     project_id: &Uuid,
     id: &Uuid,
@@ -168,11 +189,39 @@ pub async fn update_firewall_group(
     Ok(())
 }
 
+pub async fn update_firewall_group_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE firewall_group SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 pub async fn delete_firewall_group(
     project_id: &Uuid,
     id: &Uuid,
     trx: &mut PgConnection,
 ) -> Result<(), ServiceError> {
+    // Vultr removes the rules along with their group
+    sqlx::query!(
+        "DELETE FROM firewall_rule WHERE project_id = $1 AND firewall_group_id = $2",
+        project_id,
+        id
+    )
+    .execute(&mut *trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
     sqlx::query!(
         "DELETE FROM firewall_group WHERE project_id = $1 AND id = $2",
         project_id,
@@ -215,6 +264,7 @@ pub async fn insert_firewall_rule(
         r#"
         INSERT INTO firewall_rule (
             project_id,
+            firewall_group_id,
             id,
             action,
             port,
@@ -225,9 +275,10 @@ pub async fn insert_firewall_rule(
             notes,
             x,
             y
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         "#,
         input.project_id,
+        input.firewall_group_id,
         input.id,
         input.action,
         input.port,
@@ -245,14 +296,78 @@ pub async fn insert_firewall_rule(
     Ok(())
 }
 
+/// Vultr replaces a rule instead of changing it, so the stored rule may take a new id.
+pub async fn update_firewall_rule(
+    previous_id: &i64,
+    input: &FirewallRule,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE firewall_rule
+        SET id = $1,
+            action = $2,
+            port = $3,
+            ip_type = $4,
+            protocol = $5,
+            subnet = $6,
+            subnet_size = $7,
+            notes = $8,
+            x = $9,
+            y = $10
+        WHERE project_id = $11 AND firewall_group_id = $12 AND id = $13
+        "#,
+        input.id,
+        input.action,
+        input.port,
+        &input.ip_type as _,
+        &input.protocol as _,
+        input.subnet,
+        input.subnet_size,
+        input.notes,
+        input.x,
+        input.y,
+        input.project_id,
+        input.firewall_group_id,
+        previous_id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_firewall_rule_position(
+    project_id: &Uuid,
+    firewall_group_id: &Uuid,
+    id: &i64,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE firewall_rule SET x = $1, y = $2 WHERE project_id = $3 AND id = $4 AND firewall_group_id = $5",
+        position.x,
+        position.y,
+        project_id,
+        id,
+        firewall_group_id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 pub async fn delete_firewall_rule(
     project_id: &Uuid,
+    firewall_group_id: &Uuid,
     id: &i64,
     trx: &mut PgConnection,
 ) -> Result<(), ServiceError> {
     sqlx::query!(
-        "DELETE FROM firewall_rule WHERE project_id = $1 AND id = $2",
+        "DELETE FROM firewall_rule WHERE project_id = $1 AND firewall_group_id = $2 AND id = $3",
         project_id,
+        firewall_group_id,
         id
     )
     .execute(trx)
@@ -270,6 +385,7 @@ pub async fn list_firewall_rule(
         r#"
         SELECT
             project_id,
+            firewall_group_id,
             id,
             action,
             port,
@@ -304,9 +420,11 @@ pub async fn insert_compute(input: &Compute, trx: &mut PgConnection) -> Result<(
             os_id,
             firewall_group_id,
             auto_backups,
+            vpc_id,
+            backup_schedule,
             x,
             y
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         "#,
         input.project_id,
         input.region,
@@ -318,6 +436,8 @@ pub async fn insert_compute(input: &Compute, trx: &mut PgConnection) -> Result<(
         input.os_id,
         input.firewall_group_id,
         &input.auto_backups as _,
+        input.vpc_id.as_deref().unwrap_or_default(),
+        &input.backup_schedule as _,
         input.x,
         input.y
     )
@@ -338,10 +458,12 @@ pub async fn update_compute(input: &Compute, trx: &mut PgConnection) -> Result<(
             label = $4,
             os_id = $5,
             firewall_group_id = $6,
-            auto_backups = $7,
-            x = $8,
-            y = $9
-        WHERE project_id = $10 AND id = $11
+            auto_backups = COALESCE($7, auto_backups),
+            vpc_id = COALESCE($8, vpc_id),
+            backup_schedule = COALESCE($9, backup_schedule),
+            x = $10,
+            y = $11
+        WHERE project_id = $12 AND id = $13
         "#,
         input.plan,
         input.status,
@@ -350,6 +472,8 @@ pub async fn update_compute(input: &Compute, trx: &mut PgConnection) -> Result<(
         input.os_id,
         input.firewall_group_id,
         &input.auto_backups as _,
+        input.vpc_id,
+        &input.backup_schedule as _,
         input.x,
         input.y,
         input.project_id,
@@ -361,6 +485,25 @@ pub async fn update_compute(input: &Compute, trx: &mut PgConnection) -> Result<(
     Ok(())
 }
 
+pub async fn update_compute_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE compute SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 pub async fn delete_compute(
     project_id: &Uuid,
     id: &Uuid,
@@ -395,6 +538,8 @@ pub async fn list_compute(
             os_id,
             firewall_group_id,
             auto_backups as "auto_backups:_",
+            vpc_id as "vpc_id?",
+            backup_schedule as "backup_schedule: _",
             x,
             y
         FROM compute 
@@ -423,9 +568,11 @@ pub async fn insert_managed_database(
             database_engine_version,
             latest_backup,
             label,
+            vpc_id,
+            trusted_ips,
             x,
             y
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
         "#,
         input.project_id,
         input.region,
@@ -436,6 +583,8 @@ pub async fn insert_managed_database(
         input.database_engine_version,
         input.latest_backup,
         input.label,
+        input.vpc_id,
+        &input.trusted_ips,
         input.x,
         input.y
     )
@@ -459,9 +608,11 @@ pub async fn update_managed_database(
             database_engine_version = $4,
             latest_backup = $5,
             label = $6,
-            x = $7,
-            y = $8
-        WHERE project_id = $9 AND id = $10
+            vpc_id = $7,
+            trusted_ips = $8,
+            x = $9,
+            y = $10
+        WHERE project_id = $11 AND id = $12
         "#,
         input.status,
         input.plan,
@@ -469,6 +620,8 @@ pub async fn update_managed_database(
         input.database_engine_version,
         input.latest_backup,
         input.label,
+        input.vpc_id,
+        &input.trusted_ips,
         input.x,
         input.y,
         input.project_id,
@@ -480,6 +633,25 @@ pub async fn update_managed_database(
     Ok(())
 }
 
+pub async fn update_managed_database_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE managed_database SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 pub async fn delete_managed_database(
     project_id: &Uuid,
     id: &Uuid,
@@ -513,6 +685,8 @@ pub async fn list_managed_database(
             database_engine_version,
             latest_backup,
             label,
+            vpc_id,
+            trusted_ips,
             x,
             y
         FROM managed_database 
@@ -584,6 +758,25 @@ pub async fn update_object_storage(
     Ok(())
 }
 
+pub async fn update_object_storage_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE object_storage SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
 pub async fn delete_object_storage(
     project_id: &Uuid,
     id: &Uuid,
@@ -625,104 +818,1179 @@ pub async fn list_object_storage(
     .map_err(Into::into)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        adapter::repositories::{
-            auth::insert_user_account, connection_pool, interfaces::TExecutor,
-            project::workspace::insert_project, tear_down, SqlExecutor,
-        },
-        domain::{
-            auth::UserAccountAggregate,
-            project::{
-                diagrams::{
-                    BlockStorage, Compute, FirewallGroup, FirewallRule, ManagedDatabase,
-                    ObjectStorage,
-                },
-                enums::{BackupStatus, DatabaseEngine, IpType, Protocol},
-                ProjectAggregate,
-            },
-        },
-    };
-    use chrono::Utc;
-    use uuid::Uuid;
+pub async fn insert_vpc(input: &Vpc, trx: &mut PgConnection) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO vpc (
+            project_id,
+            region,
+            id,
+            description,
+            v4_subnet,
+            v4_subnet_mask,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        input.project_id,
+        input.region,
+        input.id,
+        input.description,
+        input.v4_subnet,
+        input.v4_subnet_mask,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
 
-    async fn create_project_helper() -> (UserAccountAggregate, ProjectAggregate) {
-        let ext = SqlExecutor::new();
-        ext.write().await.begin().await.unwrap();
-        let user_account = UserAccountAggregate {
-            id: Uuid::new_v4(),
-            email: format!("test{}@test.com", Uuid::new_v4()),
-            name: "Test User".to_string(),
-            phone_num: "1234567890".to_string(),
-            password: format!("password{}", Uuid::new_v4()),
-            verified: true,
-            create_dt: Utc::now(),
-        };
-        let project = ProjectAggregate {
-            id: Uuid::new_v4(),
-            name: "Test Project".to_string(),
-            description: "Test Description".to_string(),
-            create_dt: Utc::now(),
-            update_dt: Utc::now(),
-            version: 1,
-        };
-        insert_user_account(&user_account, ext.write().await.transaction())
-            .await
-            .unwrap();
-        insert_project(&project, ext.write().await.transaction())
-            .await
-            .unwrap();
-        ext.write().await.commit().await.unwrap();
-        ext.write().await.close().await;
-        (user_account, project)
-    }
+pub async fn update_vpc(input: &Vpc, trx: &mut PgConnection) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE vpc
+        SET 
+            description = $1,
+            v4_subnet = $2,
+            v4_subnet_mask = $3,
+            x = $4,
+            y = $5
+        WHERE project_id = $6 AND id = $7
+        "#,
+        input.description,
+        input.v4_subnet,
+        input.v4_subnet_mask,
+        input.x,
+        input.y,
+        input.project_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
 
-    async fn get_block_storage(
-        input: &BlockStorage,
-        conn: &'static sqlx::PgPool,
-    ) -> Result<BlockStorage, ServiceError> {
-        sqlx::query_as!(
-            BlockStorage,
-            r#"
-            SELECT 
-                project_id,
-                region,
-                id,
-                mount_id,
-                attached_to_instance,
-                size_gb,
-                label,
-                x,
-                y
-            FROM block_storage 
-            WHERE project_id = $1 AND id = $2
-            "#,
-            input.project_id,
-            input.id
-        )
-        .fetch_one(conn)
-        .await
-        .map_err(Into::into)
-    }
-    async fn get_firewall_group(
-        input: &FirewallGroup,
-        conn: &'static sqlx::PgPool,
-    ) -> Result<FirewallGroup, ServiceError> {
-        sqlx::query_as!(
-            FirewallGroup,
-            r#"
-            SELECT 
-                project_id,
-                id,
-                description,
-                x,
-                y
-            FROM firewall_group 
-            WHERE project_id = $1 AND id = $2
-            "#,
-            input.project_id,
+pub async fn update_vpc_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE vpc SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_vpc(
+    project_id: &Uuid,
+    id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM vpc WHERE project_id = $1 AND id = $2",
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_vpc(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<Vpc>, ServiceError> {
+    sqlx::query_as!(
+        Vpc,
+        r#"
+        SELECT
+            project_id,
+            region,
+            id,
+            description,
+            v4_subnet,
+            v4_subnet_mask,
+            x,
+            y
+        FROM vpc
+        WHERE project_id = $1
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_load_balancer(
+    input: &LoadBalancer,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO load_balancer (
+            project_id,
+            region,
+            id,
+            label,
+            status,
+            ipv4,
+            has_ssl,
+            forwarding_rules,
+            health_check,
+            instances,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#,
+        input.project_id,
+        input.region,
+        input.id,
+        input.label,
+        input.status,
+        input.ipv4,
+        input.has_ssl,
+        &input.forwarding_rules as _,
+        &input.health_check as _,
+        &input.instances,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_load_balancer(
+    input: &LoadBalancer,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE load_balancer
+        SET 
+            label = $1,
+            status = $2,
+            ipv4 = $3,
+            has_ssl = $4,
+            forwarding_rules = $5,
+            health_check = $6,
+            instances = $7,
+            x = $8,
+            y = $9
+        WHERE project_id = $10 AND id = $11
+        "#,
+        input.label,
+        input.status,
+        input.ipv4,
+        input.has_ssl,
+        &input.forwarding_rules as _,
+        &input.health_check as _,
+        &input.instances,
+        input.x,
+        input.y,
+        input.project_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_load_balancer_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE load_balancer SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_load_balancer(
+    project_id: &Uuid,
+    id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM load_balancer WHERE project_id = $1 AND id = $2",
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_load_balancer(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<LoadBalancer>, ServiceError> {
+    sqlx::query_as!(
+        LoadBalancer,
+        r#"
+        SELECT
+            project_id,
+            region,
+            id,
+            label,
+            status,
+            ipv4,
+            has_ssl,
+            forwarding_rules as "forwarding_rules: _",
+            health_check as "health_check: _",
+            instances,
+            x,
+            y
+        FROM load_balancer
+        WHERE project_id = $1
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_dns_domain(
+    input: &DnsDomain,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO dns_domain (
+            project_id,
+            id,
+            dns_sec,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5)
+        "#,
+        input.project_id,
+        input.id,
+        input.dns_sec,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_dns_domain(
+    input: &DnsDomain,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE dns_domain
+        SET 
+            dns_sec = $1,
+            x = $2,
+            y = $3
+        WHERE project_id = $4 AND id = $5
+        "#,
+        input.dns_sec,
+        input.x,
+        input.y,
+        input.project_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_dns_domain_position(
+    project_id: &Uuid,
+    id: &str,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE dns_domain SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_dns_domain(
+    project_id: &Uuid,
+    id: &str,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    // Vultr removes the records along with their domain
+    sqlx::query!(
+        "DELETE FROM dns_record WHERE project_id = $1 AND domain = $2",
+        project_id,
+        id
+    )
+    .execute(&mut *trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    sqlx::query!(
+        "DELETE FROM dns_domain WHERE project_id = $1 AND id = $2",
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_dns_domain(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<DnsDomain>, ServiceError> {
+    sqlx::query_as!(
+        DnsDomain,
+        r#"
+        SELECT
+            project_id,
+            id,
+            dns_sec,
+            x,
+            y
+        FROM dns_domain
+        WHERE project_id = $1
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_dns_record(
+    input: &DnsRecord,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO dns_record (
+            project_id,
+            domain,
+            id,
+            record_type,
+            name,
+            data,
+            priority,
+            ttl,
+            target_id,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+        input.project_id,
+        input.domain,
+        input.id,
+        input.record_type,
+        input.name,
+        input.data,
+        input.priority,
+        input.ttl,
+        input.target_id,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_dns_record(
+    input: &DnsRecord,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE dns_record
+        SET 
+            name = $1,
+            data = $2,
+            priority = $3,
+            ttl = $4,
+            target_id = COALESCE($5, target_id),
+            x = $6,
+            y = $7
+        WHERE project_id = $8 AND domain = $9 AND id = $10
+        "#,
+        input.name,
+        input.data,
+        input.priority,
+        input.ttl,
+        input.target_id,
+        input.x,
+        input.y,
+        input.project_id,
+        input.domain,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_dns_record_position(
+    project_id: &Uuid,
+    domain: &str,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE dns_record SET x = $1, y = $2 WHERE project_id = $3 AND domain = $4 AND id = $5",
+        position.x,
+        position.y,
+        project_id,
+        domain,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_dns_record(
+    project_id: &Uuid,
+    domain: &str,
+    id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM dns_record WHERE project_id = $1 AND domain = $2 AND id = $3",
+        project_id,
+        domain,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_dns_record(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<DnsRecord>, ServiceError> {
+    sqlx::query_as!(
+        DnsRecord,
+        r#"
+        SELECT
+            project_id,
+            domain,
+            id,
+            record_type,
+            name,
+            data,
+            priority,
+            ttl,
+            target_id,
+            x,
+            y
+        FROM dns_record
+        WHERE project_id = $1
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_compute_snapshot(
+    input: &ComputeSnapshot,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO compute_snapshot (
+            project_id,
+            id,
+            instance_id,
+            description,
+            status,
+            size,
+            date_created,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        input.project_id,
+        input.id,
+        input.instance_id,
+        input.description,
+        input.status,
+        input.size,
+        input.date_created,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_compute_snapshot(
+    input: &ComputeSnapshot,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE compute_snapshot
+        SET 
+            instance_id = COALESCE($1, instance_id),
+            description = $2,
+            status = $3,
+            size = $4,
+            x = $5,
+            y = $6
+        WHERE project_id = $7 AND id = $8
+        "#,
+        input.instance_id,
+        input.description,
+        input.status,
+        input.size,
+        input.x,
+        input.y,
+        input.project_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_compute_snapshot_position(
+    project_id: &Uuid,
+    id: &Uuid,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE compute_snapshot SET x = $1, y = $2 WHERE project_id = $3 AND id = $4",
+        position.x,
+        position.y,
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_compute_snapshot(
+    project_id: &Uuid,
+    id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM compute_snapshot WHERE project_id = $1 AND id = $2",
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_compute_snapshot(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<ComputeSnapshot>, ServiceError> {
+    sqlx::query_as!(
+        ComputeSnapshot,
+        r#"
+        SELECT
+            project_id,
+            id,
+            instance_id,
+            description,
+            status,
+            size,
+            date_created,
+            x,
+            y
+        FROM compute_snapshot
+        WHERE project_id = $1
+        ORDER BY date_created
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_database_user(
+    input: &DatabaseUser,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO database_user (
+            project_id,
+            database_id,
+            id,
+            encryption,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        input.project_id,
+        input.database_id,
+        input.id,
+        input.encryption,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_database_user(
+    input: &DatabaseUser,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE database_user
+        SET
+            encryption = $1,
+            x = $2,
+            y = $3
+        WHERE project_id = $4 AND database_id = $5 AND id = $6
+        "#,
+        input.encryption,
+        input.x,
+        input.y,
+        input.project_id,
+        input.database_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_database_user_position(
+    project_id: &Uuid,
+    database_id: &Uuid,
+    id: &str,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE database_user SET x = $1, y = $2 WHERE project_id = $3 AND database_id = $4 AND id = $5",
+        position.x,
+        position.y,
+        project_id,
+        database_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_database_user(
+    project_id: &Uuid,
+    database_id: &Uuid,
+    id: &str,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM database_user WHERE project_id = $1 AND database_id = $2 AND id = $3",
+        project_id,
+        database_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_database_user(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<DatabaseUser>, ServiceError> {
+    sqlx::query_as!(
+        DatabaseUser,
+        r#"
+        SELECT
+            project_id,
+            database_id,
+            id,
+            encryption,
+            x,
+            y
+        FROM database_user
+        WHERE project_id = $1
+        ORDER BY database_id, id
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_logical_database(
+    input: &LogicalDatabase,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO logical_database (
+            project_id,
+            database_id,
+            id,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5)
+        "#,
+        input.project_id,
+        input.database_id,
+        input.id,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_logical_database(
+    input: &LogicalDatabase,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE logical_database
+        SET
+            x = $1,
+            y = $2
+        WHERE project_id = $3 AND database_id = $4 AND id = $5
+        "#,
+        input.x,
+        input.y,
+        input.project_id,
+        input.database_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_logical_database_position(
+    project_id: &Uuid,
+    database_id: &Uuid,
+    id: &str,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE logical_database SET x = $1, y = $2 WHERE project_id = $3 AND database_id = $4 AND id = $5",
+        position.x,
+        position.y,
+        project_id,
+        database_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_logical_database(
+    project_id: &Uuid,
+    database_id: &Uuid,
+    id: &str,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM logical_database WHERE project_id = $1 AND database_id = $2 AND id = $3",
+        project_id,
+        database_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_logical_database(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<LogicalDatabase>, ServiceError> {
+    sqlx::query_as!(
+        LogicalDatabase,
+        r#"
+        SELECT
+            project_id,
+            database_id,
+            id,
+            x,
+            y
+        FROM logical_database
+        WHERE project_id = $1
+        ORDER BY database_id, id
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_connection_pool(
+    input: &ConnectionPool,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO connection_pool (
+            project_id,
+            database_id,
+            id,
+            database,
+            username,
+            mode,
+            size,
+            x,
+            y
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        input.project_id,
+        input.database_id,
+        input.id,
+        input.database,
+        input.username,
+        input.mode,
+        input.size,
+        input.x,
+        input.y
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_connection_pool(
+    input: &ConnectionPool,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE connection_pool
+        SET
+            database = $1,
+            username = $2,
+            mode = $3,
+            size = $4,
+            x = $5,
+            y = $6
+        WHERE project_id = $7 AND database_id = $8 AND id = $9
+        "#,
+        input.database,
+        input.username,
+        input.mode,
+        input.size,
+        input.x,
+        input.y,
+        input.project_id,
+        input.database_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_connection_pool_position(
+    project_id: &Uuid,
+    database_id: &Uuid,
+    id: &str,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE connection_pool SET x = $1, y = $2 WHERE project_id = $3 AND database_id = $4 AND id = $5",
+        position.x,
+        position.y,
+        project_id,
+        database_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_connection_pool(
+    project_id: &Uuid,
+    database_id: &Uuid,
+    id: &str,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM connection_pool WHERE project_id = $1 AND database_id = $2 AND id = $3",
+        project_id,
+        database_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn list_connection_pool(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<ConnectionPool>, ServiceError> {
+    sqlx::query_as!(
+        ConnectionPool,
+        r#"
+        SELECT
+            project_id,
+            database_id,
+            id,
+            database,
+            username,
+            mode,
+            size,
+            x,
+            y
+        FROM connection_pool
+        WHERE project_id = $1
+        ORDER BY database_id, id
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapter::repositories::{
+            auth::insert_user_account, connection_pool, interfaces::TExecutor,
+            project::workspace::insert_project, tear_down, SqlExecutor,
+        },
+        domain::{
+            auth::UserAccountAggregate,
+            project::{
+                diagrams::{
+                    BackupSchedule, BlockStorage, Compute, ComputeSnapshot, ConnectionPool,
+                    DatabaseUser, DnsDomain, DnsRecord, FirewallGroup, FirewallRule,
+                    ForwardingRule, HealthCheck, LogicalDatabase, ManagedDatabase, ObjectStorage,
+                },
+                enums::{BackupScheduleType, BackupStatus, DatabaseEngine, IpType, Protocol},
+                ProjectAggregate,
+            },
+        },
+    };
+    use chrono::Utc;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    async fn create_project_helper() -> (UserAccountAggregate, ProjectAggregate) {
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        let user_account = UserAccountAggregate {
+            id: Uuid::new_v4(),
+            email: format!("test{}@test.com", Uuid::new_v4()),
+            name: "Test User".to_string(),
+            phone_num: "1234567890".to_string(),
+            password: format!("password{}", Uuid::new_v4()),
+            verified: true,
+            create_dt: Utc::now(),
+        };
+        let project = ProjectAggregate {
+            id: Uuid::new_v4(),
+            name: "Test Project".to_string(),
+            description: "Test Description".to_string(),
+            create_dt: Utc::now(),
+            update_dt: Utc::now(),
+            version: 1,
+        };
+        insert_user_account(&user_account, ext.write().await.transaction())
+            .await
+            .unwrap();
+        insert_project(&project, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        (user_account, project)
+    }
+
+    async fn get_block_storage(
+        input: &BlockStorage,
+        conn: &'static sqlx::PgPool,
+    ) -> Result<BlockStorage, ServiceError> {
+        sqlx::query_as!(
+            BlockStorage,
+            r#"
+            SELECT 
+                project_id,
+                region,
+                id,
+                mount_id,
+                attached_to_instance,
+                size_gb,
+                label,
+                x,
+                y
+            FROM block_storage 
+            WHERE project_id = $1 AND id = $2
+            "#,
+            input.project_id,
+            input.id
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(Into::into)
+    }
+    async fn get_firewall_group(
+        input: &FirewallGroup,
+        conn: &'static sqlx::PgPool,
+    ) -> Result<FirewallGroup, ServiceError> {
+        sqlx::query_as!(
+            FirewallGroup,
+            r#"
+            SELECT 
+                project_id,
+                id,
+                description,
+                x,
+                y
+            FROM firewall_group 
+            WHERE project_id = $1 AND id = $2
+            "#,
+            input.project_id,
+            input.id
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn get_firewall_rule(
+        input: &FirewallRule,
+        conn: &'static sqlx::PgPool,
+    ) -> Result<FirewallRule, ServiceError> {
+        sqlx::query_as!(
+            FirewallRule,
+            r#"
+            SELECT 
+                project_id,
+                firewall_group_id,
+                id,
+                action,
+                port,
+                ip_type as "ip_type:_",
+                protocol as "protocol:_",
+                subnet,
+                subnet_size,
+                notes,
+                x,
+                y
+            FROM firewall_rule 
+            WHERE project_id = $1 AND firewall_group_id = $2 AND id = $3
+            "#,
+            input.project_id,
+            input.firewall_group_id,
+            input.id
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn get_compute(
+        input: &Compute,
+        conn: &'static sqlx::PgPool,
+    ) -> Result<Compute, ServiceError> {
+        sqlx::query_as!(
+            Compute,
+            r#"
+            SELECT 
+                project_id,
+                region,
+                id,
+                plan,
+                status,
+                main_ip,
+                label,
+                os_id,
+                firewall_group_id,
+                auto_backups as "auto_backups:_",
+                vpc_id as "vpc_id?",
+                backup_schedule as "backup_schedule: _",
+                x,
+                y
+            FROM compute 
+            WHERE project_id = $1 AND id = $2
+            "#,
+            input.project_id,
             input.id
         )
         .fetch_one(conn)
@@ -730,26 +1998,28 @@ mod tests {
         .map_err(Into::into)
     }
 
-    pub async fn get_firewall_rule(
-        input: &FirewallRule,
+    pub async fn get_managed_database(
+        input: &ManagedDatabase,
         conn: &'static sqlx::PgPool,
-    ) -> Result<FirewallRule, ServiceError> {
+    ) -> Result<ManagedDatabase, ServiceError> {
         sqlx::query_as!(
-            FirewallRule,
+            ManagedDatabase,
             r#"
             SELECT 
                 project_id,
+                region,
                 id,
-                action,
-                port,
-                ip_type as "ip_type:_",
-                protocol as "protocol:_",
-                subnet,
-                subnet_size,
-                notes,
+                status,
+                plan,
+                database_engine as "database_engine:_",
+                database_engine_version,
+                latest_backup,
+                label,
+                vpc_id,
+                trusted_ips,
                 x,
                 y
-            FROM firewall_rule 
+            FROM managed_database 
             WHERE project_id = $1 AND id = $2
             "#,
             input.project_id,
@@ -760,27 +2030,77 @@ mod tests {
         .map_err(Into::into)
     }
 
-    pub async fn get_compute(
-        input: &Compute,
+    pub async fn get_object_storage(
+        input: &ObjectStorage,
         conn: &'static sqlx::PgPool,
-    ) -> Result<Compute, ServiceError> {
+    ) -> Result<ObjectStorage, ServiceError> {
         sqlx::query_as!(
-            Compute,
+            ObjectStorage,
+            r#"
+            SELECT 
+                project_id,
+                tier_id,
+                id,
+                cluster_id,
+                label,
+                x,
+                y
+            FROM object_storage 
+            WHERE project_id = $1 AND id = $2
+            "#,
+            input.project_id,
+            input.id
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn get_vpc(input: &Vpc, conn: &'static sqlx::PgPool) -> Result<Vpc, ServiceError> {
+        sqlx::query_as!(
+            Vpc,
+            r#"
+            SELECT 
+                project_id,
+                region,
+                id,
+                description,
+                v4_subnet,
+                v4_subnet_mask,
+                x,
+                y
+            FROM vpc 
+            WHERE project_id = $1 AND id = $2
+            "#,
+            input.project_id,
+            input.id
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn get_load_balancer(
+        input: &LoadBalancer,
+        conn: &'static sqlx::PgPool,
+    ) -> Result<LoadBalancer, ServiceError> {
+        sqlx::query_as!(
+            LoadBalancer,
             r#"
             SELECT 
                 project_id,
                 region,
                 id,
-                plan,
-                status,
-                main_ip,
                 label,
-                os_id,
-                firewall_group_id,
-                auto_backups as "auto_backups:_",
+                status,
+                ipv4,
+                has_ssl,
+                forwarding_rules as "forwarding_rules: _",
+                health_check as "health_check: _",
+                instances,
                 x,
                 y
-            FROM compute 
+            FROM load_balancer 
             WHERE project_id = $1 AND id = $2
             "#,
             input.project_id,
@@ -791,75 +2111,261 @@ mod tests {
         .map_err(Into::into)
     }
 
-    pub async fn get_managed_database(
-        input: &ManagedDatabase,
-        conn: &'static sqlx::PgPool,
-    ) -> Result<ManagedDatabase, ServiceError> {
-        sqlx::query_as!(
-            ManagedDatabase,
-            r#"
-            SELECT 
-                project_id,
-                region,
-                id,
-                status,
-                plan,
-                database_engine as "database_engine:_",
-                database_engine_version,
-                latest_backup,
-                label,
-                x,
-                y
-            FROM managed_database 
-            WHERE project_id = $1 AND id = $2
-            "#,
-            input.project_id,
-            input.id
+    #[tokio::test]
+    async fn test_block_storage_crud() {
+        // GIVEN
+        tear_down().await;
+        let (_, project) = create_project_helper().await;
+        let block_storage = BlockStorage {
+            project_id: project.id,
+            region: Some("region1".to_string()),
+            id: Uuid::new_v4(),
+            mount_id: "mount1".to_string(),
+            attached_to_instance: Uuid::new_v4(),
+            size_gb: 100,
+            label: "test-block-storage".to_string(),
+            x: 0,
+            y: 0,
+        };
+
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+
+        // WHEN (Insert)
+        insert_block_storage(&block_storage, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+
+        // THEN
+        let fetched_block_storage = get_block_storage(&block_storage, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_block_storage.project_id, block_storage.project_id);
+        assert_eq!(fetched_block_storage.id, block_storage.id);
+        assert_eq!(fetched_block_storage.mount_id, block_storage.mount_id);
+        assert_eq!(
+            fetched_block_storage.attached_to_instance,
+            block_storage.attached_to_instance
+        );
+        assert_eq!(fetched_block_storage.size_gb, block_storage.size_gb);
+        assert_eq!(fetched_block_storage.label, block_storage.label);
+
+        // WHEN (Update)
+        let mut updated_block_storage = block_storage.clone();
+        updated_block_storage.label = "updated-label".to_string();
+        ext.write().await.begin().await.unwrap();
+        update_block_storage(&updated_block_storage, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+
+        // THEN
+        let fetched_block_storage = get_block_storage(&updated_block_storage, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_block_storage.label, updated_block_storage.label);
+
+        // WHEN (Delete)
+        ext.write().await.begin().await.unwrap();
+        delete_block_storage(
+            &block_storage.project_id,
+            &block_storage.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        assert!(matches!(
+            get_block_storage(&block_storage, connection_pool()).await,
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_firewall_group_crud() {
+        // GIVEN
+        tear_down().await;
+        let (_, project) = create_project_helper().await;
+        let firewall_group = FirewallGroup {
+            project_id: project.id,
+            id: Uuid::new_v4(),
+            description: "test-firewall-group".to_string(),
+            x: 0,
+            y: 0,
+        };
+
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+
+        // WHEN (Insert)
+        insert_firewall_group(&firewall_group, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let fetched_firewall_group = get_firewall_group(&firewall_group, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_firewall_group.project_id, firewall_group.project_id);
+        assert_eq!(fetched_firewall_group.id, firewall_group.id);
+        assert_eq!(
+            fetched_firewall_group.description,
+            firewall_group.description
+        );
+
+        // WHEN (Update)
+        let mut updated_firewall_group = firewall_group.clone();
+        updated_firewall_group.description = "updated-description".to_string();
+        ext.write().await.begin().await.unwrap();
+        update_firewall_group(&updated_firewall_group, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let fetched_firewall_group = get_firewall_group(&updated_firewall_group, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(
+            fetched_firewall_group.description,
+            updated_firewall_group.description
+        );
+
+        // WHEN (Delete)
+        ext.write().await.begin().await.unwrap();
+        delete_firewall_group(
+            &firewall_group.project_id,
+            &firewall_group.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        assert!(matches!(
+            get_firewall_group(&firewall_group, connection_pool()).await,
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_firewall_rule_crud() {
+        // GIVEN
+        tear_down().await;
+        let (_, project) = create_project_helper().await;
+        let firewall_rule = FirewallRule {
+            project_id: project.id,
+            firewall_group_id: Uuid::new_v4(),
+            id: 1,
+            action: "allow".to_string(),
+            port: "80".to_string(),
+            ip_type: IpType::V4,
+            protocol: Protocol::Tcp,
+            subnet: "0.0.0.0".to_string(),
+            subnet_size: 24,
+            notes: "test-rule".to_string(),
+            x: 0,
+            y: 0,
+        };
+
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+
+        // WHEN (Insert)
+        insert_firewall_rule(&firewall_rule, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let fetched_firewall_rule = get_firewall_rule(&firewall_rule, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_firewall_rule.project_id, firewall_rule.project_id);
+        assert_eq!(fetched_firewall_rule.id, firewall_rule.id);
+        assert_eq!(fetched_firewall_rule.action, firewall_rule.action);
+        assert_eq!(fetched_firewall_rule.port, firewall_rule.port);
+        assert_eq!(fetched_firewall_rule.subnet, firewall_rule.subnet);
+        assert_eq!(fetched_firewall_rule.subnet_size, firewall_rule.subnet_size);
+        assert_eq!(fetched_firewall_rule.notes, firewall_rule.notes);
+        assert_eq!(
+            fetched_firewall_rule.firewall_group_id,
+            firewall_rule.firewall_group_id
+        );
+
+        // WHEN (Update)
+        let mut updated_firewall_rule = firewall_rule.clone();
+        updated_firewall_rule.id = 2;
+        updated_firewall_rule.port = "443".to_string();
+        ext.write().await.begin().await.unwrap();
+        update_firewall_rule(
+            &firewall_rule.id,
+            &updated_firewall_rule,
+            ext.write().await.transaction(),
         )
-        .fetch_one(conn)
         .await
-        .map_err(Into::into)
-    }
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
 
-    pub async fn get_object_storage(
-        input: &ObjectStorage,
-        conn: &'static sqlx::PgPool,
-    ) -> Result<ObjectStorage, ServiceError> {
-        sqlx::query_as!(
-            ObjectStorage,
-            r#"
-            SELECT 
-                project_id,
-                tier_id,
-                id,
-                cluster_id,
-                label,
-                x,
-                y
-            FROM object_storage 
-            WHERE project_id = $1 AND id = $2
-            "#,
-            input.project_id,
-            input.id
+        // THEN
+        let fetched_firewall_rule = get_firewall_rule(&updated_firewall_rule, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_firewall_rule.port, updated_firewall_rule.port);
+        assert!(matches!(
+            get_firewall_rule(&firewall_rule, connection_pool()).await,
+            Err(ServiceError::NotFound)
+        ));
+
+        // WHEN (Delete)
+        ext.write().await.begin().await.unwrap();
+        delete_firewall_rule(
+            &updated_firewall_rule.project_id,
+            &updated_firewall_rule.firewall_group_id,
+            &updated_firewall_rule.id,
+            ext.write().await.transaction(),
         )
-        .fetch_one(conn)
         .await
-        .map_err(Into::into)
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        assert!(matches!(
+            get_firewall_rule(&updated_firewall_rule, connection_pool()).await,
+            Err(ServiceError::NotFound)
+        ));
     }
 
     #[tokio::test]
-    async fn test_block_storage_crud() {
+    async fn test_compute_crud() {
         // GIVEN
         tear_down().await;
         let (_, project) = create_project_helper().await;
-        let block_storage = BlockStorage {
+        let compute = Compute {
             project_id: project.id,
             region: Some("region1".to_string()),
             id: Uuid::new_v4(),
-            mount_id: "mount1".to_string(),
-            attached_to_instance: Uuid::new_v4(),
-            size_gb: 100,
-            label: "test-block-storage".to_string(),
+            plan: "vc2-1c-1gb".to_string(),
+            status: "active".to_string(),
+            main_ip: "192.168.1.1".to_string(),
+            label: "test-compute".to_string(),
+            os_id: 1,
+            firewall_group_id: "default".to_string(),
+            auto_backups: Some(BackupStatus::Disabled),
+            vpc_id: None,
+            backup_schedule: None,
             x: 0,
             y: 0,
         };
@@ -868,45 +2374,58 @@ mod tests {
         ext.write().await.begin().await.unwrap();
 
         // WHEN (Insert)
-        insert_block_storage(&block_storage, ext.write().await.transaction())
+        insert_compute(&compute, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
 
         // THEN
-        let fetched_block_storage = get_block_storage(&block_storage, connection_pool())
-            .await
-            .unwrap();
-        assert_eq!(fetched_block_storage.project_id, block_storage.project_id);
-        assert_eq!(fetched_block_storage.id, block_storage.id);
-        assert_eq!(fetched_block_storage.mount_id, block_storage.mount_id);
-        assert_eq!(
-            fetched_block_storage.attached_to_instance,
-            block_storage.attached_to_instance
-        );
-        assert_eq!(fetched_block_storage.size_gb, block_storage.size_gb);
-        assert_eq!(fetched_block_storage.label, block_storage.label);
+        let fetched_compute = get_compute(&compute, connection_pool()).await.unwrap();
+        assert_eq!(fetched_compute.project_id, compute.project_id);
+        assert_eq!(fetched_compute.id, compute.id);
+        assert_eq!(fetched_compute.plan, compute.plan);
+        assert_eq!(fetched_compute.status, compute.status);
+        assert_eq!(fetched_compute.main_ip, compute.main_ip);
+        assert_eq!(fetched_compute.label, compute.label);
+        assert_eq!(fetched_compute.os_id, compute.os_id);
+        assert_eq!(fetched_compute.firewall_group_id, compute.firewall_group_id);
 
         // WHEN (Update)
-        let mut updated_block_storage = block_storage.clone();
-        updated_block_storage.label = "updated-label".to_string();
+        let mut updated_compute = compute.clone();
+        updated_compute.label = "updated-compute".to_string();
+        updated_compute.backup_schedule = Some(Json(BackupSchedule {
+            schedule_type: BackupScheduleType::Weekly,
+            hour: 3,
+            dow: Some(1),
+        }));
+        // Vultr does not report the schedule with the instance, the stored one is kept
+        let mut refreshed_compute = updated_compute.clone();
+        refreshed_compute.backup_schedule = None;
         ext.write().await.begin().await.unwrap();
-        update_block_storage(&updated_block_storage, ext.write().await.transaction())
+        update_compute(&updated_compute, ext.write().await.transaction())
+            .await
+            .unwrap();
+        update_compute(&refreshed_compute, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
 
         // THEN
-        let fetched_block_storage = get_block_storage(&updated_block_storage, connection_pool())
+        let fetched_compute = get_compute(&updated_compute, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_block_storage.label, updated_block_storage.label);
+        assert_eq!(fetched_compute.label, updated_compute.label);
+        let backup_schedule = fetched_compute.backup_schedule.unwrap();
+        assert!(backup_schedule.schedule_type == BackupScheduleType::Weekly);
+        assert_eq!(backup_schedule.dow, Some(1));
 
         // WHEN (Delete)
         ext.write().await.begin().await.unwrap();
-        delete_block_storage(
-            &block_storage.project_id,
-            &block_storage.id,
+        delete_compute(
+            &compute.project_id,
+            &compute.id,
             ext.write().await.transaction(),
         )
         .await
@@ -916,20 +2435,28 @@ mod tests {
 
         // THEN
         assert!(matches!(
-            get_block_storage(&block_storage, connection_pool()).await,
+            get_compute(&compute, connection_pool()).await,
             Err(ServiceError::NotFound)
         ));
     }
 
     #[tokio::test]
-    async fn test_firewall_group_crud() {
+    async fn test_managed_database_crud() {
         // GIVEN
         tear_down().await;
         let (_, project) = create_project_helper().await;
-        let firewall_group = FirewallGroup {
+        let managed_database = ManagedDatabase {
             project_id: project.id,
+            region: Some("region1".to_string()),
             id: Uuid::new_v4(),
-            description: "test-firewall-group".to_string(),
+            status: "active".to_string(),
+            plan: "db-s-1vcpu-1gb".to_string(),
+            database_engine: DatabaseEngine::Mysql,
+            database_engine_version: 8,
+            latest_backup: "2024-03-20".to_string(),
+            label: "test-database".to_string(),
+            vpc_id: String::new(),
+            trusted_ips: vec!["192.0.2.0/24".to_string()],
             x: 0,
             y: 0,
         };
@@ -938,47 +2465,56 @@ mod tests {
         ext.write().await.begin().await.unwrap();
 
         // WHEN (Insert)
-        insert_firewall_group(&firewall_group, ext.write().await.transaction())
+        insert_managed_database(&managed_database, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_firewall_group = get_firewall_group(&firewall_group, connection_pool())
+        let fetched_database = get_managed_database(&managed_database, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_firewall_group.project_id, firewall_group.project_id);
-        assert_eq!(fetched_firewall_group.id, firewall_group.id);
+        assert_eq!(fetched_database.project_id, managed_database.project_id);
+        assert_eq!(fetched_database.id, managed_database.id);
+        assert_eq!(fetched_database.status, managed_database.status);
+        assert_eq!(fetched_database.plan, managed_database.plan);
         assert_eq!(
-            fetched_firewall_group.description,
-            firewall_group.description
+            fetched_database.database_engine,
+            managed_database.database_engine
+        );
+        assert_eq!(
+            fetched_database.database_engine_version,
+            managed_database.database_engine_version
         );
+        assert_eq!(
+            fetched_database.latest_backup,
+            managed_database.latest_backup
+        );
+        assert_eq!(fetched_database.label, managed_database.label);
+        assert_eq!(fetched_database.trusted_ips, managed_database.trusted_ips);
 
         // WHEN (Update)
-        let mut updated_firewall_group = firewall_group.clone();
-        updated_firewall_group.description = "updated-description".to_string();
+        let mut updated_database = managed_database.clone();
+        updated_database.label = "updated-database".to_string();
         ext.write().await.begin().await.unwrap();
-        update_firewall_group(&updated_firewall_group, ext.write().await.transaction())
+        update_managed_database(&updated_database, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_firewall_group = get_firewall_group(&updated_firewall_group, connection_pool())
+        let fetched_database = get_managed_database(&updated_database, connection_pool())
             .await
             .unwrap();
-        assert_eq!(
-            fetched_firewall_group.description,
-            updated_firewall_group.description
-        );
+        assert_eq!(fetched_database.label, updated_database.label);
 
         // WHEN (Delete)
         ext.write().await.begin().await.unwrap();
-        delete_firewall_group(
-            &firewall_group.project_id,
-            &firewall_group.id,
+        delete_managed_database(
+            &managed_database.project_id,
+            &managed_database.id,
             ext.write().await.transaction(),
         )
         .await
@@ -988,26 +2524,22 @@ mod tests {
 
         // THEN
         assert!(matches!(
-            get_firewall_group(&firewall_group, connection_pool()).await,
+            get_managed_database(&managed_database, connection_pool()).await,
             Err(ServiceError::NotFound)
         ));
     }
 
     #[tokio::test]
-    async fn test_firewall_rule_crud() {
+    async fn test_object_storage_crud() {
         // GIVEN
         tear_down().await;
         let (_, project) = create_project_helper().await;
-        let firewall_rule = FirewallRule {
+        let object_storage = ObjectStorage {
             project_id: project.id,
-            id: 1,
-            action: "allow".to_string(),
-            port: "80".to_string(),
-            ip_type: IpType::V4,
-            protocol: Protocol::Tcp,
-            subnet: "0.0.0.0".to_string(),
-            subnet_size: 24,
-            notes: "test-rule".to_string(),
+            tier_id: Some(1),
+            id: Uuid::new_v4(),
+            cluster_id: 1,
+            label: "test-storage".to_string(),
             x: 0,
             y: 0,
         };
@@ -1016,29 +2548,43 @@ mod tests {
         ext.write().await.begin().await.unwrap();
 
         // WHEN (Insert)
-        insert_firewall_rule(&firewall_rule, ext.write().await.transaction())
+        insert_object_storage(&object_storage, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let fetched_storage = get_object_storage(&object_storage, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_storage.project_id, object_storage.project_id);
+        assert_eq!(fetched_storage.id, object_storage.id);
+        assert_eq!(fetched_storage.tier_id, object_storage.tier_id);
+        assert_eq!(fetched_storage.cluster_id, object_storage.cluster_id);
+        assert_eq!(fetched_storage.label, object_storage.label);
+
+        // WHEN (Update)
+        let mut updated_storage = object_storage.clone();
+        updated_storage.label = "updated-storage".to_string();
+        ext.write().await.begin().await.unwrap();
+        update_object_storage(&updated_storage, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_firewall_rule = get_firewall_rule(&firewall_rule, connection_pool())
+        let fetched_storage = get_object_storage(&updated_storage, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_firewall_rule.project_id, firewall_rule.project_id);
-        assert_eq!(fetched_firewall_rule.id, firewall_rule.id);
-        assert_eq!(fetched_firewall_rule.action, firewall_rule.action);
-        assert_eq!(fetched_firewall_rule.port, firewall_rule.port);
-        assert_eq!(fetched_firewall_rule.subnet, firewall_rule.subnet);
-        assert_eq!(fetched_firewall_rule.subnet_size, firewall_rule.subnet_size);
-        assert_eq!(fetched_firewall_rule.notes, firewall_rule.notes);
+        assert_eq!(fetched_storage.label, updated_storage.label);
 
         // WHEN (Delete)
         ext.write().await.begin().await.unwrap();
-        delete_firewall_rule(
-            &firewall_rule.project_id,
-            &firewall_rule.id,
+        delete_object_storage(
+            &object_storage.project_id,
+            &object_storage.id,
             ext.write().await.transaction(),
         )
         .await
@@ -1048,27 +2594,23 @@ mod tests {
 
         // THEN
         assert!(matches!(
-            get_firewall_rule(&firewall_rule, connection_pool()).await,
+            get_object_storage(&object_storage, connection_pool()).await,
             Err(ServiceError::NotFound)
         ));
     }
 
     #[tokio::test]
-    async fn test_compute_crud() {
+    async fn test_vpc_crud() {
         // GIVEN
         tear_down().await;
         let (_, project) = create_project_helper().await;
-        let compute = Compute {
+        let vpc = Vpc {
             project_id: project.id,
-            region: Some("region1".to_string()),
+            region: "region1".to_string(),
             id: Uuid::new_v4(),
-            plan: "vc2-1c-1gb".to_string(),
-            status: "active".to_string(),
-            main_ip: "192.168.1.1".to_string(),
-            label: "test-compute".to_string(),
-            os_id: 1,
-            firewall_group_id: "default".to_string(),
-            auto_backups: Some(BackupStatus::Disabled),
+            description: "test-vpc".to_string(),
+            v4_subnet: "10.1.0.0".to_string(),
+            v4_subnet_mask: 24,
             x: 0,
             y: 0,
         };
@@ -1077,73 +2619,86 @@ mod tests {
         ext.write().await.begin().await.unwrap();
 
         // WHEN (Insert)
-        insert_compute(&compute, ext.write().await.transaction())
+        insert_vpc(&vpc, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_compute = get_compute(&compute, connection_pool()).await.unwrap();
-        assert_eq!(fetched_compute.project_id, compute.project_id);
-        assert_eq!(fetched_compute.id, compute.id);
-        assert_eq!(fetched_compute.plan, compute.plan);
-        assert_eq!(fetched_compute.status, compute.status);
-        assert_eq!(fetched_compute.main_ip, compute.main_ip);
-        assert_eq!(fetched_compute.label, compute.label);
-        assert_eq!(fetched_compute.os_id, compute.os_id);
-        assert_eq!(fetched_compute.firewall_group_id, compute.firewall_group_id);
+        let fetched_vpc = get_vpc(&vpc, connection_pool()).await.unwrap();
+        assert_eq!(fetched_vpc.project_id, vpc.project_id);
+        assert_eq!(fetched_vpc.region, vpc.region);
+        assert_eq!(fetched_vpc.description, vpc.description);
+        assert_eq!(fetched_vpc.v4_subnet, vpc.v4_subnet);
+        assert_eq!(fetched_vpc.v4_subnet_mask, vpc.v4_subnet_mask);
 
         // WHEN (Update)
-        let mut updated_compute = compute.clone();
-        updated_compute.label = "updated-compute".to_string();
+        let mut updated_vpc = vpc.clone();
+        updated_vpc.description = "updated-vpc".to_string();
         ext.write().await.begin().await.unwrap();
-        update_compute(&updated_compute, ext.write().await.transaction())
+        update_vpc(&updated_vpc, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_compute = get_compute(&updated_compute, connection_pool())
-            .await
-            .unwrap();
-        assert_eq!(fetched_compute.label, updated_compute.label);
+        let fetched_vpc = get_vpc(&updated_vpc, connection_pool()).await.unwrap();
+        assert_eq!(fetched_vpc.description, updated_vpc.description);
+        assert_eq!(
+            list_vpc(&project.id, connection_pool())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
 
         // WHEN (Delete)
         ext.write().await.begin().await.unwrap();
-        delete_compute(
-            &compute.project_id,
-            &compute.id,
-            ext.write().await.transaction(),
-        )
-        .await
-        .unwrap();
+        delete_vpc(&vpc.project_id, &vpc.id, ext.write().await.transaction())
+            .await
+            .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
         assert!(matches!(
-            get_compute(&compute, connection_pool()).await,
+            get_vpc(&vpc, connection_pool()).await,
             Err(ServiceError::NotFound)
         ));
     }
 
     #[tokio::test]
-    async fn test_managed_database_crud() {
+    async fn test_load_balancer_crud() {
         // GIVEN
         tear_down().await;
         let (_, project) = create_project_helper().await;
-        let managed_database = ManagedDatabase {
+        let load_balancer = LoadBalancer {
             project_id: project.id,
-            region: Some("region1".to_string()),
+            region: "region1".to_string(),
             id: Uuid::new_v4(),
+            label: "test-lb".to_string(),
             status: "active".to_string(),
-            plan: "db-s-1vcpu-1gb".to_string(),
-            database_engine: DatabaseEngine::Mysql,
-            database_engine_version: 8,
-            latest_backup: "2024-03-20".to_string(),
-            label: "test-database".to_string(),
+            ipv4: "192.168.1.10".to_string(),
+            has_ssl: false,
+            forwarding_rules: Json(vec![ForwardingRule {
+                id: Some("rule-1".to_string()),
+                frontend_protocol: "http".to_string(),
+                frontend_port: 80,
+                backend_protocol: "http".to_string(),
+                backend_port: 8080,
+            }]),
+            health_check: Json(HealthCheck {
+                protocol: "http".to_string(),
+                port: 8080,
+                path: "/health".to_string(),
+                check_interval: 15,
+                response_timeout: 5,
+                unhealthy_threshold: 5,
+                healthy_threshold: 5,
+            }),
+            instances: vec![Uuid::new_v4()],
             x: 0,
             y: 0,
         };
@@ -1152,55 +2707,51 @@ mod tests {
         ext.write().await.begin().await.unwrap();
 
         // WHEN (Insert)
-        insert_managed_database(&managed_database, ext.write().await.transaction())
+        insert_load_balancer(&load_balancer, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_database = get_managed_database(&managed_database, connection_pool())
+        let fetched = get_load_balancer(&load_balancer, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_database.project_id, managed_database.project_id);
-        assert_eq!(fetched_database.id, managed_database.id);
-        assert_eq!(fetched_database.status, managed_database.status);
-        assert_eq!(fetched_database.plan, managed_database.plan);
-        assert_eq!(
-            fetched_database.database_engine,
-            managed_database.database_engine
-        );
-        assert_eq!(
-            fetched_database.database_engine_version,
-            managed_database.database_engine_version
-        );
-        assert_eq!(
-            fetched_database.latest_backup,
-            managed_database.latest_backup
-        );
-        assert_eq!(fetched_database.label, managed_database.label);
+        assert_eq!(fetched.label, load_balancer.label);
+        assert_eq!(fetched.instances, load_balancer.instances);
+        assert_eq!(fetched.forwarding_rules[0].backend_port, 8080);
+        assert_eq!(fetched.health_check.path, "/health");
 
         // WHEN (Update)
-        let mut updated_database = managed_database.clone();
-        updated_database.label = "updated-database".to_string();
+        let mut updated = load_balancer.clone();
+        updated.has_ssl = true;
+        updated.instances = Vec::new();
         ext.write().await.begin().await.unwrap();
-        update_managed_database(&updated_database, ext.write().await.transaction())
+        update_load_balancer(&updated, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_database = get_managed_database(&updated_database, connection_pool())
+        let fetched = get_load_balancer(&updated, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_database.label, updated_database.label);
+        assert!(fetched.has_ssl);
+        assert!(fetched.instances.is_empty());
+        assert_eq!(
+            list_load_balancer(&project.id, connection_pool())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
 
         // WHEN (Delete)
         ext.write().await.begin().await.unwrap();
-        delete_managed_database(
-            &managed_database.project_id,
-            &managed_database.id,
+        delete_load_balancer(
+            &load_balancer.project_id,
+            &load_balancer.id,
             ext.write().await.transaction(),
         )
         .await
@@ -1210,67 +2761,97 @@ mod tests {
 
         // THEN
         assert!(matches!(
-            get_managed_database(&managed_database, connection_pool()).await,
+            get_load_balancer(&load_balancer, connection_pool()).await,
             Err(ServiceError::NotFound)
         ));
     }
 
     #[tokio::test]
-    async fn test_object_storage_crud() {
+    async fn test_dns_domain_and_record_crud() {
         // GIVEN
         tear_down().await;
         let (_, project) = create_project_helper().await;
-        let object_storage = ObjectStorage {
+        let dns_domain = DnsDomain {
             project_id: project.id,
-            tier_id: Some(1),
-            id: Uuid::new_v4(),
-            cluster_id: 1,
-            label: "test-storage".to_string(),
+            id: "example.com".to_string(),
+            dns_sec: "disabled".to_string(),
             x: 0,
             y: 0,
         };
+        let target_id = Uuid::new_v4();
+        let dns_record = DnsRecord {
+            project_id: project.id,
+            domain: dns_domain.id.clone(),
+            id: Uuid::new_v4(),
+            record_type: "A".to_string(),
+            name: "www".to_string(),
+            data: "192.0.2.10".to_string(),
+            priority: 0,
+            ttl: 300,
+            target_id: Some(target_id),
+            x: 0,
+            y: 100,
+        };
 
         let ext = SqlExecutor::new();
         ext.write().await.begin().await.unwrap();
 
         // WHEN (Insert)
-        insert_object_storage(&object_storage, ext.write().await.transaction())
+        insert_dns_domain(&dns_domain, ext.write().await.transaction())
+            .await
+            .unwrap();
+        insert_dns_record(&dns_record, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_storage = get_object_storage(&object_storage, connection_pool())
+        let domains = list_dns_domain(&project.id, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_storage.project_id, object_storage.project_id);
-        assert_eq!(fetched_storage.id, object_storage.id);
-        assert_eq!(fetched_storage.tier_id, object_storage.tier_id);
-        assert_eq!(fetched_storage.cluster_id, object_storage.cluster_id);
-        assert_eq!(fetched_storage.label, object_storage.label);
+        assert_eq!(domains.len(), 1);
+        assert_eq!(domains[0].dns_sec, "disabled");
+        let records = list_dns_record(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(records[0].domain, "example.com");
+        assert_eq!(records[0].data, "192.0.2.10");
+        assert_eq!(records[0].target_id, Some(target_id));
 
         // WHEN (Update)
-        let mut updated_storage = object_storage.clone();
-        updated_storage.label = "updated-storage".to_string();
+        let mut updated_domain = dns_domain.clone();
+        updated_domain.dns_sec = "enabled".to_string();
+        // Vultr does not report the target, the stored one is kept
+        let mut updated_record = dns_record.clone();
+        updated_record.data = "192.0.2.11".to_string();
+        updated_record.target_id = None;
         ext.write().await.begin().await.unwrap();
-        update_object_storage(&updated_storage, ext.write().await.transaction())
+        update_dns_domain(&updated_domain, ext.write().await.transaction())
+            .await
+            .unwrap();
+        update_dns_record(&updated_record, ext.write().await.transaction())
             .await
             .unwrap();
         ext.write().await.commit().await.unwrap();
         ext.write().await.close().await;
 
         // THEN
-        let fetched_storage = get_object_storage(&updated_storage, connection_pool())
+        let domains = list_dns_domain(&project.id, connection_pool())
             .await
             .unwrap();
-        assert_eq!(fetched_storage.label, updated_storage.label);
+        assert_eq!(domains[0].dns_sec, "enabled");
+        let records = list_dns_record(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(records[0].data, "192.0.2.11");
+        assert_eq!(records[0].target_id, Some(target_id));
 
         // WHEN (Delete)
         ext.write().await.begin().await.unwrap();
-        delete_object_storage(
-            &object_storage.project_id,
-            &object_storage.id,
+        delete_dns_domain(
+            &dns_domain.project_id,
+            &dns_domain.id,
             ext.write().await.transaction(),
         )
         .await
@@ -1279,10 +2860,15 @@ mod tests {
         ext.write().await.close().await;
 
         // THEN
-        assert!(matches!(
-            get_object_storage(&object_storage, connection_pool()).await,
-            Err(ServiceError::NotFound)
-        ));
+        // The records go along with their domain
+        assert!(list_dns_domain(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(list_dns_record(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[tokio::test]
@@ -1306,6 +2892,8 @@ mod tests {
                 os_id: 1,
                 firewall_group_id: "default".to_string(),
                 auto_backups: Some(BackupStatus::Disabled),
+                vpc_id: None,
+                backup_schedule: None,
                 x: 0,
                 y: 0,
             },
@@ -1320,6 +2908,8 @@ mod tests {
                 os_id: 1,
                 firewall_group_id: "default".to_string(),
                 auto_backups: Some(BackupStatus::Disabled),
+                vpc_id: None,
+                backup_schedule: None,
                 x: 0,
                 y: 0,
             },
@@ -1362,6 +2952,8 @@ mod tests {
                 database_engine_version: 8,
                 latest_backup: "2024-03-20".to_string(),
                 label: "test-db-1".to_string(),
+                vpc_id: String::new(),
+                trusted_ips: Vec::new(),
                 x: 0,
                 y: 0,
             },
@@ -1375,6 +2967,8 @@ mod tests {
                 database_engine_version: 14,
                 latest_backup: "2024-03-20".to_string(),
                 label: "test-db-2".to_string(),
+                vpc_id: String::new(),
+                trusted_ips: Vec::new(),
                 x: 0,
                 y: 0,
             },
@@ -1555,4 +3149,216 @@ mod tests {
             .iter()
             .any(|s| s.label == "test-storage-2" && s.tier_id.unwrap() == 2));
     }
+
+    #[tokio::test]
+    async fn test_compute_snapshot_crud() {
+        // GIVEN
+        tear_down().await;
+        let (_, project) = create_project_helper().await;
+        let instance_id = Uuid::new_v4();
+        let compute_snapshot = ComputeSnapshot {
+            project_id: project.id,
+            id: Uuid::new_v4(),
+            instance_id: Some(instance_id),
+            description: "before upgrade".to_string(),
+            status: "pending".to_string(),
+            size: 0,
+            date_created: "2025-06-30T12:00:00+00:00".to_string(),
+            x: 0,
+            y: 0,
+        };
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+
+        // WHEN (Insert)
+        insert_compute_snapshot(&compute_snapshot, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let snapshots = list_compute_snapshot(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].instance_id, Some(instance_id));
+
+        // WHEN (Update)
+        // Vultr does not report the instance, the stored one is kept
+        let mut updated_snapshot = compute_snapshot.clone();
+        updated_snapshot.instance_id = None;
+        updated_snapshot.status = "complete".to_string();
+        updated_snapshot.size = 25;
+        ext.write().await.begin().await.unwrap();
+        update_compute_snapshot(&updated_snapshot, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let snapshots = list_compute_snapshot(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(snapshots[0].status, "complete");
+        assert_eq!(snapshots[0].size, 25);
+        assert_eq!(snapshots[0].instance_id, Some(instance_id));
+
+        // WHEN (Delete)
+        ext.write().await.begin().await.unwrap();
+        delete_compute_snapshot(
+            &project.id,
+            &compute_snapshot.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        assert!(list_compute_snapshot(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_managed_database_children_crud() {
+        // GIVEN
+        tear_down().await;
+        let (_, project) = create_project_helper().await;
+        let database_id = Uuid::new_v4();
+        let database_user = DatabaseUser {
+            project_id: project.id,
+            y: 0,
+            x: 0,
+            database_id,
+            id: "app".to_string(),
+            encryption: None,
+        };
+        let logical_database = LogicalDatabase {
+            project_id: project.id,
+            y: 0,
+            x: 100,
+            database_id,
+            id: "orders".to_string(),
+        };
+        let pool = ConnectionPool {
+            project_id: project.id,
+            y: 0,
+            x: 200,
+            database_id,
+            id: "orders-pool".to_string(),
+            database: "orders".to_string(),
+            username: "app".to_string(),
+            mode: "transaction".to_string(),
+            size: 10,
+        };
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+
+        // WHEN (Insert)
+        insert_database_user(&database_user, ext.write().await.transaction())
+            .await
+            .unwrap();
+        insert_logical_database(&logical_database, ext.write().await.transaction())
+            .await
+            .unwrap();
+        insert_connection_pool(&pool, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let database_users = list_database_user(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(database_users.len(), 1);
+        assert_eq!(database_users[0].database_id, database_id);
+        let logical_databases = list_logical_database(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(logical_databases[0].id, "orders");
+        let connection_pools = list_connection_pool(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(connection_pools[0].size, 10);
+
+        // WHEN (Update)
+        let mut updated_pool = pool.clone();
+        updated_pool.mode = "session".to_string();
+        updated_pool.size = 20;
+        ext.write().await.begin().await.unwrap();
+        update_connection_pool(&updated_pool, ext.write().await.transaction())
+            .await
+            .unwrap();
+        update_database_user_position(
+            &project.id,
+            &database_user.database_id,
+            &database_user.id,
+            &ObjectPosition { x: 40, y: 80 },
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let connection_pools = list_connection_pool(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(connection_pools[0].mode, "session");
+        assert_eq!(connection_pools[0].size, 20);
+        let database_users = list_database_user(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!((database_users[0].x, database_users[0].y), (40, 80));
+
+        // WHEN (Delete)
+        ext.write().await.begin().await.unwrap();
+        delete_database_user(
+            &project.id,
+            &database_id,
+            &database_user.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        delete_logical_database(
+            &project.id,
+            &database_id,
+            &logical_database.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        delete_connection_pool(
+            &project.id,
+            &database_id,
+            &pool.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        assert!(list_database_user(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(list_logical_database(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(list_connection_pool(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -1,2 +1,3 @@
 pub mod diagram;
+pub mod provisioning;
 pub mod workspace;
@@ -0,0 +1,493 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    domain::project::{
+        object_storage::ObjectStorageCredential,
+        provisioning::{SshKey, StartupScript},
+    },
+    errors::ServiceError,
+};
+
+pub async fn insert_ssh_key(input: &SshKey, trx: &mut PgConnection) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO ssh_key (
+            project_id,
+            id,
+            name,
+            ssh_key,
+            update_dt
+        ) VALUES ($1, $2, $3, $4, $5)
+        "#,
+        input.project_id,
+        input.id,
+        input.name,
+        input.ssh_key,
+        input.update_dt
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_ssh_key(input: &SshKey, trx: &mut PgConnection) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE ssh_key
+        SET
+            name = $1,
+            ssh_key = $2,
+            update_dt = $3
+        WHERE project_id = $4 AND id = $5
+        "#,
+        input.name,
+        input.ssh_key,
+        input.update_dt,
+        input.project_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_ssh_key(
+    project_id: &Uuid,
+    id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM ssh_key WHERE project_id = $1 AND id = $2",
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn get_ssh_key(
+    project_id: &Uuid,
+    id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<SshKey, ServiceError> {
+    sqlx::query_as!(
+        SshKey,
+        r#"
+        SELECT
+            project_id,
+            id,
+            name,
+            ssh_key,
+            update_dt
+        FROM ssh_key
+        WHERE project_id = $1 AND id = $2
+        "#,
+        project_id,
+        id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn list_ssh_key(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<SshKey>, ServiceError> {
+    sqlx::query_as!(
+        SshKey,
+        r#"
+        SELECT
+            project_id,
+            id,
+            name,
+            ssh_key,
+            update_dt
+        FROM ssh_key
+        WHERE project_id = $1
+        ORDER BY update_dt
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn insert_startup_script(
+    input: &StartupScript,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO startup_script (
+            project_id,
+            id,
+            name,
+            script_type,
+            script,
+            update_dt
+        ) VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        input.project_id,
+        input.id,
+        input.name,
+        input.script_type,
+        input.script,
+        input.update_dt
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn update_startup_script(
+    input: &StartupScript,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        UPDATE startup_script
+        SET
+            name = $1,
+            script = $2,
+            update_dt = $3
+        WHERE project_id = $4 AND id = $5
+        "#,
+        input.name,
+        input.script,
+        input.update_dt,
+        input.project_id,
+        input.id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn delete_startup_script(
+    project_id: &Uuid,
+    id: &Uuid,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "DELETE FROM startup_script WHERE project_id = $1 AND id = $2",
+        project_id,
+        id
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn get_startup_script(
+    project_id: &Uuid,
+    id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<StartupScript, ServiceError> {
+    sqlx::query_as!(
+        StartupScript,
+        r#"
+        SELECT
+            project_id,
+            id,
+            name,
+            script_type,
+            script,
+            update_dt
+        FROM startup_script
+        WHERE project_id = $1 AND id = $2
+        "#,
+        project_id,
+        id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(Into::into)
+}
+
+pub async fn list_startup_script(
+    project_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<Vec<StartupScript>, ServiceError> {
+    sqlx::query_as!(
+        StartupScript,
+        r#"
+        SELECT
+            project_id,
+            id,
+            name,
+            script_type,
+            script,
+            update_dt
+        FROM startup_script
+        WHERE project_id = $1
+        ORDER BY update_dt
+        "#,
+        project_id
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(Into::into)
+}
+
+/// Regenerated keys replace the stored ones.
+pub async fn upsert_object_storage_credential(
+    input: &ObjectStorageCredential,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO object_storage_credential (
+            project_id,
+            object_storage_id,
+            s3_hostname,
+            s3_access_key,
+            s3_secret_key,
+            update_dt
+        ) VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (project_id, object_storage_id) DO UPDATE
+        SET
+            s3_hostname = EXCLUDED.s3_hostname,
+            s3_access_key = EXCLUDED.s3_access_key,
+            s3_secret_key = EXCLUDED.s3_secret_key,
+            update_dt = EXCLUDED.update_dt
+        "#,
+        input.project_id,
+        input.object_storage_id,
+        input.s3_hostname,
+        input.s3_access_key,
+        input.s3_secret_key,
+        input.update_dt
+    )
+    .execute(trx)
+    .await
+    .map_err(Into::<ServiceError>::into)?;
+    Ok(())
+}
+
+pub async fn get_object_storage_credential(
+    project_id: &Uuid,
+    object_storage_id: &Uuid,
+    conn: &'static sqlx::PgPool,
+) -> Result<ObjectStorageCredential, ServiceError> {
+    sqlx::query_as!(
+        ObjectStorageCredential,
+        r#"
+        SELECT
+            project_id,
+            object_storage_id,
+            s3_hostname,
+            s3_access_key,
+            s3_secret_key,
+            update_dt
+        FROM object_storage_credential
+        WHERE project_id = $1 AND object_storage_id = $2
+        "#,
+        project_id,
+        object_storage_id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapter::repositories::{
+            auth::insert_user_account,
+            connection_pool,
+            interfaces::TExecutor,
+            project::{diagram::insert_object_storage, workspace::insert_project},
+            tear_down, SqlExecutor,
+        },
+        domain::{
+            auth::UserAccountAggregate,
+            project::{diagrams::ObjectStorage, object_storage::S3Credentials, ProjectAggregate},
+        },
+    };
+    use chrono::Utc;
+
+    async fn create_project_helper() -> ProjectAggregate {
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        let user_account = UserAccountAggregate {
+            id: Uuid::new_v4(),
+            email: format!("test{}@test.com", Uuid::new_v4()),
+            name: "Test User".to_string(),
+            phone_num: "1234567890".to_string(),
+            password: format!("password{}", Uuid::new_v4()),
+            verified: true,
+            create_dt: Utc::now(),
+        };
+        let project = ProjectAggregate {
+            id: Uuid::new_v4(),
+            name: "Test Project".to_string(),
+            description: "Test Description".to_string(),
+            create_dt: Utc::now(),
+            update_dt: Utc::now(),
+            version: 1,
+        };
+        insert_user_account(&user_account, ext.write().await.transaction())
+            .await
+            .unwrap();
+        insert_project(&project, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        project
+    }
+
+    #[tokio::test]
+    async fn test_ssh_key_and_startup_script_crud() {
+        // GIVEN
+        tear_down().await;
+        let project = create_project_helper().await;
+        let ssh_key = SshKey::new(
+            project.id,
+            Uuid::new_v4(),
+            "deploy".to_string(),
+            "ssh-ed25519 AAAA".to_string(),
+        );
+        let startup_script = StartupScript::new(
+            project.id,
+            Uuid::new_v4(),
+            "bootstrap".to_string(),
+            "boot".to_string(),
+            "IyEvYmluL3NoCg==".to_string(),
+        );
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+
+        // WHEN (Insert)
+        insert_ssh_key(&ssh_key, ext.write().await.transaction())
+            .await
+            .unwrap();
+        insert_startup_script(&startup_script, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let ssh_keys = list_ssh_key(&project.id, connection_pool()).await.unwrap();
+        assert_eq!(ssh_keys.len(), 1);
+        assert_eq!(ssh_keys[0].ssh_key, "ssh-ed25519 AAAA");
+        let startup_scripts = list_startup_script(&project.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(startup_scripts[0].script_type, "boot");
+
+        // WHEN (Update)
+        let mut updated_ssh_key = ssh_key.clone();
+        updated_ssh_key.name = "ci".to_string();
+        let mut updated_startup_script = startup_script.clone();
+        updated_startup_script.script = "IyEvYmluL2Jhc2gK".to_string();
+        ext.write().await.begin().await.unwrap();
+        update_ssh_key(&updated_ssh_key, ext.write().await.transaction())
+            .await
+            .unwrap();
+        update_startup_script(&updated_startup_script, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let fetched_ssh_key = get_ssh_key(&project.id, &ssh_key.id, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(fetched_ssh_key.name, "ci");
+        let fetched_startup_script =
+            get_startup_script(&project.id, &startup_script.id, connection_pool())
+                .await
+                .unwrap();
+        assert_eq!(fetched_startup_script.script, "IyEvYmluL2Jhc2gK");
+
+        // WHEN (Delete)
+        ext.write().await.begin().await.unwrap();
+        delete_ssh_key(&project.id, &ssh_key.id, ext.write().await.transaction())
+            .await
+            .unwrap();
+        delete_startup_script(
+            &project.id,
+            &startup_script.id,
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        assert!(list_ssh_key(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(list_startup_script(&project.id, connection_pool())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_object_storage_credential_upsert() {
+        // GIVEN
+        tear_down().await;
+        let project = create_project_helper().await;
+        let object_storage = ObjectStorage {
+            project_id: project.id,
+            tier_id: Some(1),
+            id: Uuid::new_v4(),
+            cluster_id: 2,
+            label: "assets".to_string(),
+            x: 0,
+            y: 0,
+        };
+        let credentials = |access_key: &str| S3Credentials {
+            s3_hostname: "ewr1.vultrobjects.com".to_string(),
+            s3_access_key: access_key.to_string(),
+            s3_secret_key: format!("{}-secret", access_key),
+        };
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        insert_object_storage(&object_storage, ext.write().await.transaction())
+            .await
+            .unwrap();
+
+        // WHEN
+        for access_key in ["first", "second"] {
+            let credential = ObjectStorageCredential::seal(
+                project.id,
+                object_storage.id,
+                credentials(access_key),
+            )
+            .unwrap();
+            upsert_object_storage_credential(&credential, ext.write().await.transaction())
+                .await
+                .unwrap();
+        }
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // THEN
+        let stored =
+            get_object_storage_credential(&project.id, &object_storage.id, connection_pool())
+                .await
+                .unwrap();
+        assert_eq!(stored.s3_access_key, "second");
+        assert_ne!(stored.s3_secret_key, b"second-secret");
+        assert_eq!(stored.open().unwrap().s3_secret_key, "second-secret");
+    }
+}
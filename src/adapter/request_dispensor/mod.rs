@@ -1,4 +1,5 @@
 pub mod architector_server;
+pub mod s3;
 pub mod vultr;
 
 use reqwest::Client;
@@ -0,0 +1,136 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Router,
+};
+use chrono::Utc;
+use reqwest::Method;
+
+use super::{S3Client, SignedRequest};
+
+pub(crate) const ACCESS_KEY: &str = "fake-access-key";
+pub(crate) const SECRET_KEY: &str = "fake-secret-key";
+
+// bucket name -> creation date
+type SharedState = Arc<Mutex<BTreeMap<String, String>>>;
+
+/// In-process stand-in for an S3-compatible endpoint. Requests must be signed with the fake keys.
+pub(crate) struct FakeS3Server {
+    endpoint: String,
+    state: SharedState,
+}
+
+impl FakeS3Server {
+    pub async fn start() -> Self {
+        let state = SharedState::default();
+        let app = Router::new()
+            .route("/", get(list_buckets))
+            .route("/{bucket}", put(create_bucket).delete(delete_bucket))
+            .layer(middleware::from_fn(verify_signature))
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { endpoint, state }
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint.clone()
+    }
+
+    pub fn client(&self) -> S3Client {
+        S3Client::new(
+            &self.endpoint,
+            ACCESS_KEY.to_string(),
+            SECRET_KEY.to_string(),
+        )
+        .unwrap()
+    }
+
+    pub fn buckets(&self) -> Vec<String> {
+        lock(&self.state).keys().cloned().collect()
+    }
+}
+
+fn lock(state: &SharedState) -> MutexGuard<'_, BTreeMap<String, String>> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn error(status: StatusCode, code: &str) -> Response {
+    (
+        status,
+        format!(
+            "<Error><Code>{}</Code><Message>{}</Message></Error>",
+            code, code
+        ),
+    )
+        .into_response()
+}
+
+async fn verify_signature(headers: HeaderMap, request: Request, next: Next) -> Response {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let payload_hash = header("x-amz-content-sha256");
+    let expected = SignedRequest::with_headers(
+        &Method::from_bytes(request.method().as_str().as_bytes()).unwrap(),
+        request.uri().path(),
+        request.uri().query().unwrap_or_default(),
+        &[
+            ("host", &header("host")),
+            ("x-amz-content-sha256", &payload_hash),
+            ("x-amz-date", &header("x-amz-date")),
+        ],
+        &payload_hash,
+    )
+    .authorization(ACCESS_KEY, SECRET_KEY);
+    if header("authorization") != expected {
+        return error(StatusCode::FORBIDDEN, "SignatureDoesNotMatch");
+    }
+    next.run(request).await
+}
+
+async fn list_buckets(State(state): State<SharedState>) -> Response {
+    let buckets: String = lock(&state)
+        .iter()
+        .map(|(name, creation_date)| {
+            format!(
+                "<Bucket><Name>{}</Name><CreationDate>{}</CreationDate></Bucket>",
+                name, creation_date
+            )
+        })
+        .collect();
+    format!(
+        "<ListAllMyBucketsResult><Owner><ID>fake</ID></Owner><Buckets>{}</Buckets></ListAllMyBucketsResult>",
+        buckets
+    )
+    .into_response()
+}
+
+async fn create_bucket(State(state): State<SharedState>, Path(bucket): Path<String>) -> Response {
+    let mut buckets = lock(&state);
+    if buckets.contains_key(&bucket) {
+        return error(StatusCode::CONFLICT, "BucketAlreadyOwnedByYou");
+    }
+    buckets.insert(bucket, Utc::now().to_rfc3339());
+    StatusCode::OK.into_response()
+}
+
+async fn delete_bucket(State(state): State<SharedState>, Path(bucket): Path<String>) -> Response {
+    match lock(&state).remove(&bucket) {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => error(StatusCode::NOT_FOUND, "NoSuchBucket"),
+    }
+}
@@ -0,0 +1,624 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use super::{testing::test_policy, VultrClient};
+
+pub(crate) const API_KEY: &str = "fake-api-key";
+
+/// Collections served by the fake, as (path, key of a single resource, key of a list).
+const COLLECTIONS: [(&str, &str, &str); 9] = [
+    ("instances", "instance", "instances"),
+    ("blocks", "block", "blocks"),
+    ("firewalls", "firewall_group", "firewall_groups"),
+    ("databases", "database", "databases"),
+    ("object-storage", "object_storage", "object_storages"),
+    ("vpcs", "vpc", "vpcs"),
+    ("load-balancers", "load_balancer", "load_balancers"),
+    ("domains", "domain", "domains"),
+    ("snapshots", "snapshot", "snapshots"),
+];
+
+/// Resources served under a parent, as (parent path, path, key of a single resource, key of a list).
+const CHILD_COLLECTIONS: [(&str, &str, &str, &str); 5] = [
+    ("firewalls", "rules", "firewall_rule", "firewall_rules"),
+    ("domains", "records", "record", "records"),
+    ("databases", "users", "user", "users"),
+    ("databases", "dbs", "db", "dbs"),
+    (
+        "databases",
+        "connection-pools",
+        "connection_pool",
+        "connection_pools",
+    ),
+];
+
+#[derive(Default)]
+struct FakeState {
+    // path -> id -> resource
+    resources: HashMap<String, HashMap<String, Value>>,
+    // (parent path, parent id, path) -> id -> resource
+    children: HashMap<(String, String, String), HashMap<String, Value>>,
+    next_rule_id: i64,
+    // instance id -> ids of the VPCs it is attached to
+    vpc_attachments: HashMap<String, BTreeSet<String>>,
+    // instance id -> backup schedule
+    backup_schedules: HashMap<String, Value>,
+    // instance id -> snapshot or backup ids it was restored from, oldest first
+    restores: HashMap<String, Vec<String>>,
+}
+
+type SharedState = Arc<Mutex<FakeState>>;
+
+/// In-process stand-in for the Vultr API that keeps the resources created through it.
+/// Instances, databases, load balancers and snapshots are provisioned when they are fetched for the first time.
+pub(crate) struct FakeVultrServer {
+    base_url: String,
+    state: SharedState,
+}
+
+impl FakeVultrServer {
+    pub async fn start() -> Self {
+        let state = SharedState::default();
+        let app = Router::new()
+            .route("/v2/{collection}", get(list).post(create))
+            .route(
+                "/v2/{collection}/{id}",
+                get(fetch).patch(update).put(update).delete(remove),
+            )
+            .route(
+                "/v2/{collection}/{id}/{action}",
+                get(list_children).post(act),
+            )
+            .route(
+                "/v2/{collection}/{id}/{action}/{child_id}",
+                get(fetch_child)
+                    .post(act_on_instance)
+                    .patch(update_child)
+                    .put(update_child)
+                    .delete(remove_child),
+            )
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v2", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { base_url, state }
+    }
+
+    pub fn client(&self) -> VultrClient {
+        VultrClient::with_policy(API_KEY, self.base_url.clone(), test_policy())
+    }
+
+    /// Resources currently stored under a collection path, e.g. `instances`.
+    pub fn resources(&self, collection: &str) -> Vec<Value> {
+        lock(&self.state)
+            .resources
+            .get(collection)
+            .map(|resources| resources.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn firewall_rules(&self, firewall_group_id: &str) -> Vec<Value> {
+        self.children("firewalls", firewall_group_id, "rules")
+    }
+
+    pub fn dns_records(&self, domain: &str) -> Vec<Value> {
+        self.children("domains", domain, "records")
+    }
+
+    /// Users, logical databases (`dbs`) or connection pools of a managed database.
+    pub fn database_children(&self, database_id: &str, child: &str) -> Vec<Value> {
+        self.children("databases", database_id, child)
+    }
+
+    fn children(&self, collection: &str, id: &str, child: &str) -> Vec<Value> {
+        lock(&self.state)
+            .children
+            .get(&child_key(collection, id, child))
+            .map(|children| children.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// VPCs an instance is attached to.
+    pub fn vpc_attachments(&self, instance_id: &str) -> Vec<String> {
+        lock(&self.state)
+            .vpc_attachments
+            .get(instance_id)
+            .map(|vpc_ids| vpc_ids.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn backup_schedule(&self, instance_id: &str) -> Option<Value> {
+        lock(&self.state).backup_schedules.get(instance_id).cloned()
+    }
+
+    /// Snapshot or backup ids an instance was restored from, oldest first.
+    pub fn restores(&self, instance_id: &str) -> Vec<String> {
+        lock(&self.state)
+            .restores
+            .get(instance_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+fn lock(state: &SharedState) -> MutexGuard<'_, FakeState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn keys(collection: &str) -> Option<(&'static str, &'static str)> {
+    COLLECTIONS
+        .iter()
+        .find(|(path, _, _)| *path == collection)
+        .map(|(_, single, plural)| (*single, *plural))
+}
+
+fn child_keys(collection: &str, child: &str) -> Option<(&'static str, &'static str)> {
+    CHILD_COLLECTIONS
+        .iter()
+        .find(|(parent, path, _, _)| *parent == collection && *path == child)
+        .map(|(_, _, single, plural)| (*single, *plural))
+}
+
+fn child_key(collection: &str, id: &str, child: &str) -> (String, String, String) {
+    (collection.to_string(), id.to_string(), child.to_string())
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "error": "Resource not found.", "status": 404 })),
+    )
+        .into_response()
+}
+
+/// Fields Vultr fills in itself when a resource is created.
+fn provision(collection: &str, id: &str, body: Value) -> Value {
+    let generated = match collection {
+        "instances" => json!({
+            "status": "pending",
+            "main_ip": "0.0.0.0",
+            "firewall_group_id": "",
+        }),
+        "blocks" => json!({
+            "mount_id": format!("ewr-{}", &id[..12]),
+            "attached_to_instance": Uuid::nil(),
+        }),
+        "databases" => json!({
+            "status": "Rebuilding",
+            "latest_backup": "",
+            "vpc_id": "",
+            "trusted_ips": [],
+        }),
+        "vpcs" => json!({ "v4_subnet": "10.1.96.0", "v4_subnet_mask": 20 }),
+        "load-balancers" => json!({
+            "status": "pending",
+            "ipv4": "",
+            "health_check": {
+                "protocol": "tcp",
+                "port": 80,
+                "check_interval": 15,
+                "response_timeout": 5,
+                "unhealthy_threshold": 5,
+                "healthy_threshold": 5,
+            },
+        }),
+        "domains" => json!({ "dns_sec": "disabled" }),
+        "snapshots" => json!({
+            "status": "pending",
+            "size": 0,
+            "date_created": "2025-06-30T12:00:00+00:00",
+        }),
+        _ => json!({}),
+    };
+    let mut resource = Map::new();
+    // A domain is addressed by its name, Vultr gives it no other id
+    if collection != "domains" {
+        resource.insert("id".to_string(), json!(id));
+    }
+    resource.extend(generated.as_object().cloned().unwrap_or_default());
+    resource.extend(body.as_object().cloned().unwrap_or_default());
+    if collection == "firewalls" {
+        resource.insert("rule_count".to_string(), json!(0));
+    }
+    // A snapshot does not tell which instance it was taken from
+    if collection == "snapshots" {
+        resource.remove("instance_id");
+    }
+    if collection == "load-balancers" {
+        let has_ssl = resource.remove("ssl").is_some();
+        resource.insert("has_ssl".to_string(), json!(has_ssl));
+        if let Some(Value::Array(rules)) = resource.get_mut("forwarding_rules") {
+            for rule in rules.iter_mut() {
+                rule["id"] = json!(Uuid::new_v4().simple().to_string());
+            }
+        }
+    }
+    Value::Object(resource)
+}
+
+fn progress(collection: &str, resource: &mut Value) {
+    match (collection, resource["status"].as_str()) {
+        ("instances", Some("pending")) => {
+            resource["status"] = json!("active");
+            resource["main_ip"] = json!("192.0.2.10");
+        }
+        ("load-balancers", Some("pending")) => {
+            resource["status"] = json!("active");
+            resource["ipv4"] = json!("192.0.2.20");
+        }
+        ("databases", Some("Rebuilding")) => {
+            resource["status"] = json!("Running");
+            resource["latest_backup"] = json!("2025-01-01 00:00:00");
+        }
+        ("snapshots", Some("pending")) => {
+            resource["status"] = json!("complete");
+            resource["size"] = json!(26843545600_i64);
+        }
+        _ => {}
+    }
+}
+
+async fn list(State(state): State<SharedState>, Path(collection): Path<String>) -> Response {
+    let Some((_, plural)) = keys(&collection) else {
+        return not_found();
+    };
+    let resources: Vec<Value> = lock(&state)
+        .resources
+        .get(&collection)
+        .map(|resources| resources.values().cloned().collect())
+        .unwrap_or_default();
+    Json(json!({
+        plural: resources,
+        "meta": { "total": resources.len(), "links": { "next": "", "prev": "" } }
+    }))
+    .into_response()
+}
+
+async fn create(
+    State(state): State<SharedState>,
+    Path(collection): Path<String>,
+    Json(body): Json<Value>,
+) -> Response {
+    let Some((single, _)) = keys(&collection) else {
+        return not_found();
+    };
+    let id = match collection.as_str() {
+        "domains" => body["domain"].as_str().unwrap_or_default().to_string(),
+        _ => Uuid::new_v4().to_string(),
+    };
+    let resource = provision(&collection, &id, body);
+    lock(&state)
+        .resources
+        .entry(collection)
+        .or_default()
+        .insert(id, resource.clone());
+    (StatusCode::ACCEPTED, Json(json!({ single: resource }))).into_response()
+}
+
+async fn fetch(
+    State(state): State<SharedState>,
+    Path((collection, id)): Path<(String, String)>,
+) -> Response {
+    let Some((single, _)) = keys(&collection) else {
+        return not_found();
+    };
+    let mut state = lock(&state);
+    let Some(resource) = state
+        .resources
+        .get_mut(&collection)
+        .and_then(|resources| resources.get_mut(&id))
+    else {
+        return not_found();
+    };
+    progress(&collection, resource);
+    Json(json!({ single: resource })).into_response()
+}
+
+async fn update(
+    State(state): State<SharedState>,
+    Path((collection, id)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> Response {
+    let Some((single, _)) = keys(&collection) else {
+        return not_found();
+    };
+    let mut state = lock(&state);
+    let Some(resource) = state
+        .resources
+        .get_mut(&collection)
+        .and_then(|resources| resources.get_mut(&id))
+    else {
+        return not_found();
+    };
+    if let (Some(fields), Some(changes)) = (resource.as_object_mut(), body.as_object()) {
+        fields.extend(changes.clone());
+    }
+    // Only instances and databases answer an update with the resource
+    match collection.as_str() {
+        "instances" | "databases" => {
+            (StatusCode::ACCEPTED, Json(json!({ single: resource }))).into_response()
+        }
+        _ => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+async fn remove(
+    State(state): State<SharedState>,
+    Path((collection, id)): Path<(String, String)>,
+) -> Response {
+    let removed = lock(&state)
+        .resources
+        .get_mut(&collection)
+        .and_then(|resources| resources.remove(&id));
+    match removed {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => not_found(),
+    }
+}
+
+async fn act(
+    State(state): State<SharedState>,
+    Path((collection, id, action)): Path<(String, String, String)>,
+    Json(body): Json<Value>,
+) -> Response {
+    match (collection.as_str(), action.as_str()) {
+        ("blocks", "attach") => set_block_instance(&state, &id, body["instance_id"].clone()),
+        ("blocks", "detach") => set_block_instance(&state, &id, json!(Uuid::nil())),
+        ("firewalls", "rules") => create_rule(&state, id, body),
+        ("domains", "records") => create_record(&state, id, body),
+        ("databases", child @ ("users" | "dbs" | "connection-pools")) => {
+            create_database_child(&state, id, child, body)
+        }
+        ("instances", "restore") => restore_instance(&state, id, body),
+        ("instances", "backup-schedule") => set_backup_schedule(&state, id, body),
+        _ => not_found(),
+    }
+}
+
+fn restore_instance(state: &SharedState, id: String, body: Value) -> Response {
+    let mut state = lock(state);
+    let exists = |collection: &str, id: &str| {
+        state
+            .resources
+            .get(collection)
+            .is_some_and(|resources| resources.contains_key(id))
+    };
+    if !exists("instances", &id) {
+        return not_found();
+    }
+    let restore_id = match (body["snapshot_id"].as_str(), body["backup_id"].as_str()) {
+        (Some(snapshot_id), None) if exists("snapshots", snapshot_id) => snapshot_id,
+        (None, Some(backup_id)) => backup_id,
+        _ => return StatusCode::BAD_REQUEST.into_response(),
+    }
+    .to_string();
+    state
+        .restores
+        .entry(id)
+        .or_default()
+        .push(restore_id.clone());
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({ "status": { "restore_id": restore_id, "status": "inprogress" } })),
+    )
+        .into_response()
+}
+
+fn set_backup_schedule(state: &SharedState, id: String, body: Value) -> Response {
+    let mut state = lock(state);
+    if !state
+        .resources
+        .get("instances")
+        .is_some_and(|instances| instances.contains_key(&id))
+    {
+        return not_found();
+    }
+    state.backup_schedules.insert(id, body);
+    StatusCode::OK.into_response()
+}
+
+fn set_block_instance(state: &SharedState, id: &str, instance_id: Value) -> Response {
+    let mut state = lock(state);
+    let Some(block) = state
+        .resources
+        .get_mut("blocks")
+        .and_then(|blocks| blocks.get_mut(id))
+    else {
+        return not_found();
+    };
+    block["attached_to_instance"] = instance_id;
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn act_on_instance(
+    State(state): State<SharedState>,
+    Path((collection, id, target, action)): Path<(String, String, String, String)>,
+    Json(body): Json<Value>,
+) -> Response {
+    if collection != "instances" || target != "vpcs" {
+        return not_found();
+    }
+    let mut state = lock(&state);
+    let Some(vpc_id) = body["vpc_id"].as_str().map(str::to_string) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let exists = |collection: &str, id: &str| {
+        state
+            .resources
+            .get(collection)
+            .is_some_and(|resources| resources.contains_key(id))
+    };
+    if !exists("instances", &id) || !exists("vpcs", &vpc_id) {
+        return not_found();
+    }
+    let vpc_ids = state.vpc_attachments.entry(id).or_default();
+    match action.as_str() {
+        "attach" => vpc_ids.insert(vpc_id),
+        "detach" => vpc_ids.remove(&vpc_id),
+        _ => return not_found(),
+    };
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn list_children(
+    State(state): State<SharedState>,
+    Path((collection, id, child)): Path<(String, String, String)>,
+) -> Response {
+    let Some((_, plural)) = child_keys(&collection, &child) else {
+        return not_found();
+    };
+    let children: Vec<Value> = lock(&state)
+        .children
+        .get(&child_key(&collection, &id, &child))
+        .map(|children| children.values().cloned().collect())
+        .unwrap_or_default();
+    Json(json!({
+        plural: children,
+        "meta": { "total": children.len(), "links": { "next": "", "prev": "" } }
+    }))
+    .into_response()
+}
+
+fn create_rule(state: &SharedState, id: String, body: Value) -> Response {
+    let mut state = lock(state);
+    let Some(group) = state
+        .resources
+        .get_mut("firewalls")
+        .and_then(|groups| groups.get_mut(&id))
+    else {
+        return not_found();
+    };
+    group["rule_count"] = json!(group["rule_count"].as_i64().unwrap_or_default() + 1);
+    state.next_rule_id += 1;
+    let rule_id = state.next_rule_id;
+    let mut rule = json!({ "id": rule_id, "action": "accept" });
+    if let (Some(fields), Some(body)) = (rule.as_object_mut(), body.as_object()) {
+        fields.extend(body.clone());
+    }
+    state
+        .children
+        .entry(child_key("firewalls", &id, "rules"))
+        .or_default()
+        .insert(rule_id.to_string(), rule.clone());
+    (StatusCode::CREATED, Json(json!({ "firewall_rule": rule }))).into_response()
+}
+
+fn create_record(state: &SharedState, domain: String, body: Value) -> Response {
+    let mut state = lock(state);
+    if !state
+        .resources
+        .get("domains")
+        .is_some_and(|domains| domains.contains_key(&domain))
+    {
+        return not_found();
+    }
+    let record_id = Uuid::new_v4().to_string();
+    let mut record = json!({ "id": record_id, "priority": 0, "ttl": 300 });
+    if let (Some(fields), Some(body)) = (record.as_object_mut(), body.as_object()) {
+        fields.extend(body.clone());
+    }
+    state
+        .children
+        .entry(child_key("domains", &domain, "records"))
+        .or_default()
+        .insert(record_id, record.clone());
+    (StatusCode::CREATED, Json(json!({ "record": record }))).into_response()
+}
+
+/// Users, logical databases and connection pools are addressed by their name.
+fn create_database_child(
+    state: &SharedState,
+    database_id: String,
+    child: &str,
+    body: Value,
+) -> Response {
+    let mut state = lock(state);
+    if !state
+        .resources
+        .get("databases")
+        .is_some_and(|databases| databases.contains_key(&database_id))
+    {
+        return not_found();
+    }
+    let (name_field, single) = match child {
+        "users" => ("username", "user"),
+        "dbs" => ("name", "db"),
+        _ => ("name", "connection_pool"),
+    };
+    let Some(name) = body[name_field].as_str().map(str::to_string) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let mut resource = body;
+    if child == "users" && resource["password"].is_null() {
+        resource["password"] = json!(Uuid::new_v4().simple().to_string());
+    }
+    state
+        .children
+        .entry(child_key("databases", &database_id, child))
+        .or_default()
+        .insert(name, resource.clone());
+    (StatusCode::ACCEPTED, Json(json!({ single: resource }))).into_response()
+}
+
+async fn fetch_child(
+    State(state): State<SharedState>,
+    Path((collection, id, child, child_id)): Path<(String, String, String, String)>,
+) -> Response {
+    let Some((single, _)) = child_keys(&collection, &child) else {
+        return not_found();
+    };
+    let resource = lock(&state)
+        .children
+        .get(&child_key(&collection, &id, &child))
+        .and_then(|children| children.get(&child_id))
+        .cloned();
+    match resource {
+        Some(resource) => Json(json!({ single: resource })).into_response(),
+        None => not_found(),
+    }
+}
+
+async fn update_child(
+    State(state): State<SharedState>,
+    Path((collection, id, child, child_id)): Path<(String, String, String, String)>,
+    Json(body): Json<Value>,
+) -> Response {
+    let mut state = lock(&state);
+    let Some(resource) = state
+        .children
+        .get_mut(&child_key(&collection, &id, &child))
+        .and_then(|children| children.get_mut(&child_id))
+    else {
+        return not_found();
+    };
+    if let (Some(fields), Some(changes)) = (resource.as_object_mut(), body.as_object()) {
+        fields.extend(changes.clone());
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn remove_child(
+    State(state): State<SharedState>,
+    Path((collection, id, child, child_id)): Path<(String, String, String, String)>,
+) -> Response {
+    let removed = lock(&state)
+        .children
+        .get_mut(&child_key(&collection, &id, &child))
+        .and_then(|children| children.remove(&child_id));
+    match removed {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => not_found(),
+    }
+}
@@ -23,3 +23,10 @@ pub trait ExecuteVultrGetCommand: Serialize {
         vultr_client: &VultrClient,
     ) -> Result<impl DeserializeOwned, ServiceError>;
 }
+
+pub trait ExecuteVultrListCommand: Serialize {
+    async fn execute(
+        self,
+        vultr_client: &VultrClient,
+    ) -> Result<Vec<impl DeserializeOwned>, ServiceError>;
+}
@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, PoisonError, Weak},
+    time::{Duration, Instant},
+};
+
+use openssl::sha::sha256;
+use reqwest::{header::RETRY_AFTER, Method, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+
+use super::VultrClient;
+use crate::{config::Config, errors::ServiceError};
+
+/// How every call to Vultr is timed out and retried.
+#[derive(Debug, Clone)]
+pub struct RequestPolicy {
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    // Requests per second per API key
+    pub rate_limit: u32,
+}
+
+impl RequestPolicy {
+    const MAX_DELAY: Duration = Duration::from_secs(30);
+
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            timeout: config.vultr_request_timeout,
+            max_retries: config.vultr_max_retries,
+            base_delay: config.vultr_retry_base_delay,
+            max_delay: Self::MAX_DELAY,
+            rate_limit: config.vultr_rate_limit,
+        }
+    }
+
+    /// Exponential backoff, unless Vultr said how long to wait.
+    fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.base_delay.saturating_mul(2u32.saturating_pow(attempt)))
+            .min(self.max_delay)
+    }
+}
+
+/// Token bucket holding at most one second worth of requests.
+pub struct TokenBucket {
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate_per_sec: u32) -> Self {
+        let rate = rate_per_sec.max(1) as f64;
+        Self {
+            rate,
+            state: Mutex::new(BucketState {
+                tokens: rate,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+                let now = Instant::now();
+                let refill = now.duration_since(state.refilled_at).as_secs_f64() * self.rate;
+                state.tokens = (state.tokens + refill).min(self.rate);
+                state.refilled_at = now;
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.rate)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Vultr limits requests per API key, so projects sharing a key share a bucket.
+/// Keys are only kept as digests, and a bucket lives as long as a client holding it.
+pub fn get_token_bucket(api_key: &str, rate_per_sec: u32) -> Arc<TokenBucket> {
+    static BUCKETS: OnceLock<Mutex<HashMap<[u8; 32], Weak<TokenBucket>>>> = OnceLock::new();
+    let mut buckets = BUCKETS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    buckets.retain(|_, bucket| bucket.strong_count() > 0);
+
+    let key = sha256(api_key.as_bytes());
+    if let Some(bucket) = buckets.get(&key).and_then(Weak::upgrade) {
+        return bucket;
+    }
+    let bucket = Arc::new(TokenBucket::new(rate_per_sec));
+    buckets.insert(key, Arc::downgrade(&bucket));
+    bucket
+}
+
+/// Request to Vultr that is throttled and retried according to the client's `RequestPolicy`.
+pub struct VultrRequest<'a> {
+    vultr_client: &'a VultrClient,
+    method: Method,
+    builder: RequestBuilder,
+}
+
+impl<'a> VultrRequest<'a> {
+    pub(super) fn new(
+        vultr_client: &'a VultrClient,
+        method: Method,
+        builder: RequestBuilder,
+    ) -> Self {
+        Self {
+            vultr_client,
+            method,
+            builder,
+        }
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        self.builder = self.builder.json(json);
+        self
+    }
+
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    /// Retry on 429 and connect errors. A POST creates a resource, so it is not retried
+    /// after a 5xx or a timeout as Vultr may have created it already.
+    pub async fn send(self) -> Result<Response, ServiceError> {
+        let policy = &self.vultr_client.policy;
+        let idempotent = self.method != Method::POST;
+        let request = self.builder.timeout(policy.timeout).build()?;
+        let mut attempt = 0;
+        loop {
+            // * Bodies are always JSON, so the request can be cloned
+            let retry = request
+                .try_clone()
+                .ok_or(ServiceError::_InternalServerError)?;
+            self.vultr_client.rate_limiter.acquire().await;
+            let (retry_after, reason) = match self.vultr_client.client.execute(retry).await {
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status == StatusCode::TOO_MANY_REQUESTS
+                        || (idempotent && status.is_server_error());
+                    if !retryable || attempt >= policy.max_retries {
+                        return Ok(response);
+                    }
+                    (retry_after(&response), status.to_string())
+                }
+                Err(err) => {
+                    let retryable = err.is_connect() || (idempotent && err.is_timeout());
+                    if !retryable || attempt >= policy.max_retries {
+                        return Err(err.into());
+                    }
+                    (None, err.to_string())
+                }
+            };
+            let delay = policy.backoff(attempt, retry_after);
+            tracing::warn!(
+                "Retrying {} {} in {:?} ({}), attempt {}",
+                request.method(),
+                request.url().path(),
+                delay,
+                reason,
+                attempt + 1
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::adapter::request_dispensor::vultr::testing::RecordingServer;
+
+    fn policy() -> RequestPolicy {
+        RequestPolicy {
+            timeout: Duration::from_secs(5),
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            rate_limit: 10,
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        // GIVEN
+        let policy = policy();
+
+        // WHEN
+        let delays: Vec<Duration> = (0..4)
+            .map(|attempt| policy.backoff(attempt, None))
+            .collect();
+        let told = policy.backoff(0, Some(Duration::from_millis(300)));
+
+        // THEN
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(400),
+                Duration::from_millis(500),
+            ]
+        );
+        assert_eq!(told, Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_throttles_burst() {
+        // GIVEN
+        let bucket = TokenBucket::new(20);
+        let started_at = Instant::now();
+
+        // WHEN
+        for _ in 0..25 {
+            bucket.acquire().await;
+        }
+
+        // THEN
+        // 20 requests go out at once, the remaining 5 wait 50ms each
+        assert!(started_at.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_get_is_retried_on_server_error() {
+        // GIVEN
+        let server =
+            RecordingServer::start_with_status(StatusCode::SERVICE_UNAVAILABLE, json!({})).await;
+        let client = server.client();
+
+        // WHEN
+        let response = client
+            .build_request(Method::GET, "instances".to_string())
+            .send()
+            .await
+            .unwrap();
+
+        // THEN
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_create_is_not_retried_on_server_error() {
+        // GIVEN
+        let server =
+            RecordingServer::start_with_status(StatusCode::INTERNAL_SERVER_ERROR, json!({})).await;
+        let client = server.client();
+
+        // WHEN
+        client
+            .build_request(Method::POST, "instances".to_string())
+            .json(&json!({ "label": "web" }))
+            .send()
+            .await
+            .unwrap();
+
+        // THEN
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_is_retried_when_rate_limited() {
+        // GIVEN
+        let server =
+            RecordingServer::start_with_status(StatusCode::TOO_MANY_REQUESTS, json!({})).await;
+        let client = server.client();
+
+        // WHEN
+        client
+            .build_request(Method::POST, "instances".to_string())
+            .json(&json!({ "label": "web" }))
+            .send()
+            .await
+            .unwrap();
+
+        // THEN
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|request| request.body == json!({ "label": "web" })));
+    }
+}
@@ -1,36 +1,229 @@
-use reqwest::{Client, Method, RequestBuilder};
-use schemas::BASE_URL;
-use std::sync::OnceLock;
+use middleware::{get_token_bucket, RequestPolicy, TokenBucket, VultrRequest};
+use reqwest::{Client, Method};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
 
 use super::get_client;
+use crate::config::get_config;
 
+#[cfg(test)]
+pub(crate) mod fake_server;
 pub mod interfaces;
+pub mod middleware;
+#[cfg(test)]
+mod request_tests;
 pub mod schemas;
+#[cfg(test)]
+pub(crate) mod testing;
 
 pub struct VultrClient {
     client: &'static Client,
     api_key: String,
+    base_url: String,
+    policy: RequestPolicy,
+    rate_limiter: Arc<TokenBucket>,
 }
 
 impl VultrClient {
     fn new(api_key: String) -> Self {
+        let policy = RequestPolicy::from_config(get_config());
         Self {
             client: get_client(),
+            rate_limiter: get_token_bucket(&api_key, policy.rate_limit),
             api_key,
+            base_url: get_config().vultr_base_url.clone(),
+            policy,
         }
     }
 
-    pub(crate) fn build_request(&self, method: Method, url: String) -> RequestBuilder {
-        // let prefix = if cfg!(test) { MOCKING_SERVER_URL } else { BASE_URL };
-        let prefix = BASE_URL;
-        let url = format!("{}/{}", prefix, url);
-        self.client
-            .request(method, url)
-            .bearer_auth(self.api_key.as_str())
+    #[cfg(test)]
+    pub(crate) fn with_policy(api_key: &str, base_url: String, policy: RequestPolicy) -> Self {
+        Self {
+            client: get_client(),
+            api_key: api_key.to_string(),
+            base_url,
+            rate_limiter: Arc::new(TokenBucket::new(policy.rate_limit)),
+            policy,
+        }
+    }
+
+    pub(crate) fn build_request(&self, method: Method, url: String) -> VultrRequest<'_> {
+        let url = format!("{}/{}", self.base_url, url);
+        let builder = self
+            .client
+            .request(method.clone(), url)
+            .bearer_auth(self.api_key.as_str());
+        VultrRequest::new(self, method, builder)
+    }
+}
+
+struct RegisteredVultrClient {
+    client: Arc<VultrClient>,
+    last_used: Instant,
+}
+
+/// Vultr clients per project. A client is reused only while the project's API key is unchanged.
+pub struct VultrClientRegistry {
+    clients: Mutex<HashMap<Uuid, RegisteredVultrClient>>,
+    idle_timeout: Duration,
+}
+
+impl VultrClientRegistry {
+    const IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 30);
+
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            idle_timeout,
+        }
     }
+
+    pub fn get_or_register(&self, project_id: Uuid, api_key: &str) -> Arc<VultrClient> {
+        let now = Instant::now();
+        let mut clients = self.clients.lock().unwrap_or_else(PoisonError::into_inner);
+        clients
+            .retain(|_, registered| now.duration_since(registered.last_used) < self.idle_timeout);
+
+        if let Some(registered) = clients.get_mut(&project_id) {
+            // * A rotated key must never be served by the client built for the previous one
+            if registered.client.api_key == api_key {
+                registered.last_used = now;
+                return registered.client.clone();
+            }
+        }
+        let client = Arc::new(VultrClient::new(api_key.to_string()));
+        clients.insert(
+            project_id,
+            RegisteredVultrClient {
+                client: client.clone(),
+                last_used: now,
+            },
+        );
+        client
+    }
+
+    pub fn evict(&self, project_id: &Uuid) {
+        self.clients
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(project_id);
+    }
+}
+
+pub fn get_vultr_client_registry() -> &'static VultrClientRegistry {
+    static REGISTRY: OnceLock<VultrClientRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| VultrClientRegistry::new(VultrClientRegistry::IDLE_TIMEOUT))
 }
 
-pub fn get_vultr_client(vultr_api_key: &str) -> &'static VultrClient {
-    static CLIENT: OnceLock<VultrClient> = OnceLock::new();
-    CLIENT.get_or_init(|| VultrClient::new(vultr_api_key.to_string()))
+pub fn get_vultr_client(project_id: Uuid, vultr_api_key: &str) -> Arc<VultrClient> {
+    get_vultr_client_registry().get_or_register(project_id, vultr_api_key)
+}
+
+pub fn evict_vultr_client(project_id: &Uuid) {
+    get_vultr_client_registry().evict(project_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_register_reuses_client_for_same_key() {
+        // GIVEN
+        let registry = VultrClientRegistry::new(Duration::from_secs(60));
+        let project_id = Uuid::new_v4();
+
+        // WHEN
+        let first = registry.get_or_register(project_id, "api_key");
+        let second = registry.get_or_register(project_id, "api_key");
+
+        // THEN
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_get_or_register_separates_projects() {
+        // GIVEN
+        let registry = VultrClientRegistry::new(Duration::from_secs(60));
+
+        // WHEN
+        let first = registry.get_or_register(Uuid::new_v4(), "api_key_1");
+        let second = registry.get_or_register(Uuid::new_v4(), "api_key_2");
+
+        // THEN
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(first.api_key, "api_key_1");
+        assert_eq!(second.api_key, "api_key_2");
+    }
+
+    #[test]
+    fn test_get_or_register_picks_up_rotated_key() {
+        // GIVEN
+        let registry = VultrClientRegistry::new(Duration::from_secs(60));
+        let project_id = Uuid::new_v4();
+        let old_client = registry.get_or_register(project_id, "old_api_key");
+
+        // WHEN
+        let new_client = registry.get_or_register(project_id, "new_api_key");
+
+        // THEN
+        assert!(!Arc::ptr_eq(&old_client, &new_client));
+        assert_eq!(new_client.api_key, "new_api_key");
+    }
+
+    #[test]
+    fn test_evict() {
+        // GIVEN
+        let registry = VultrClientRegistry::new(Duration::from_secs(60));
+        let project_id = Uuid::new_v4();
+        let first = registry.get_or_register(project_id, "api_key");
+
+        // WHEN
+        registry.evict(&project_id);
+
+        // THEN
+        let second = registry.get_or_register(project_id, "api_key");
+        assert!(!Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_idle_clients_are_evicted() {
+        // GIVEN
+        let registry = VultrClientRegistry::new(Duration::ZERO);
+        let project_id = Uuid::new_v4();
+        registry.get_or_register(project_id, "api_key");
+
+        // WHEN
+        registry.get_or_register(Uuid::new_v4(), "api_key");
+
+        // THEN
+        let clients = registry.clients.lock().unwrap();
+        assert_eq!(clients.len(), 1);
+        assert!(!clients.contains_key(&project_id));
+    }
+
+    #[test]
+    fn test_token_bucket_is_dropped_with_last_client() {
+        // GIVEN
+        let registry = VultrClientRegistry::new(Duration::from_secs(60));
+        let api_key = Uuid::new_v4().to_string();
+        let (first_project, second_project) = (Uuid::new_v4(), Uuid::new_v4());
+        let first = registry.get_or_register(first_project, &api_key);
+        let second = registry.get_or_register(second_project, &api_key);
+        assert!(Arc::ptr_eq(&first.rate_limiter, &second.rate_limiter));
+        let bucket = Arc::downgrade(&first.rate_limiter);
+        drop((first, second));
+
+        // WHEN
+        registry.evict(&first_project);
+
+        // THEN
+        assert!(bucket.upgrade().is_some());
+        registry.evict(&second_project);
+        assert!(bucket.upgrade().is_none());
+    }
 }
@@ -0,0 +1,1175 @@
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::{
+    interfaces::{
+        ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+        ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
+    },
+    schemas::{
+        block_storage::{
+            AttachBlockStorageToCompute, CreateBlockStorage, DeleteBlockStorage,
+            DetachBlockStorageFromCompute, GetBlockStorage, ListBlockStorage, UpdateBlockStorage,
+        },
+        dns::{
+            CreateDnsDomain, CreateDnsRecord, DeleteDnsDomain, DeleteDnsRecord, GetDnsDomain,
+            GetDnsRecord, ListDnsDomain, UpdateDnsDomain, UpdateDnsRecord,
+        },
+        firewall::{
+            CreateFirewallGroup, CreateFirewallRule, DeleteFirewallGroup, DeleteFirewallRule,
+            GetFirewallGroup, GetFirewallRule, ListFirewallGroup, ListFirewallRule,
+            UpdateFirewallGroup, UpdateFirewallRule,
+        },
+        instance::{
+            CreateCompute, DeleteCompute, GetCompute, ListCompute, RestoreCompute,
+            SetComputeBackupSchedule, UpdateCompute,
+        },
+        load_balancer::{
+            CreateLoadBalancer, DeleteLoadBalancer, GetLoadBalancer, ListLoadBalancer,
+            UpdateLoadBalancer,
+        },
+        managed_database::{
+            CreateConnectionPool, CreateDatabaseUser, CreateLogicalDatabase, CreateManagedDatabase,
+            DeleteConnectionPool, DeleteDatabaseUser, DeleteLogicalDatabase, DeleteManagedDatabase,
+            GetConnectionPool, GetDatabaseUser, GetLogicalDatabase, GetManagedDatabase,
+            ListManagedDatabase, UpdateConnectionPool, UpdateDatabaseUser, UpdateManagedDatabase,
+        },
+        object_storage::{
+            CreateObjectStorage, DeleteObjectStorage, GetObjectStorage,
+            GetObjectStorageCredentials, ListObjectStorage, RegenerateObjectStorageKeys,
+            UpdateObjectStorage,
+        },
+        snapshot::{
+            CreateComputeSnapshot, DeleteComputeSnapshot, GetComputeSnapshot, ListComputeSnapshot,
+            UpdateComputeSnapshot,
+        },
+        ssh_key::{CreateSshKey, DeleteSshKey, UpdateSshKey},
+        startup_script::{CreateStartupScript, DeleteStartupScript, UpdateStartupScript},
+        vpc::{
+            AttachVpcToCompute, CreateVpc, DeleteVpc, DetachVpcFromCompute, GetVpc, ListVpc,
+            UpdateVpc,
+        },
+    },
+    testing::{RecordedRequest, RecordingServer, API_KEY},
+};
+use crate::errors::ServiceError;
+
+fn command<T: DeserializeOwned>(data: Value) -> T {
+    serde_json::from_value(data).unwrap()
+}
+
+fn expected(method: &str, path: &str, body: Value) -> RecordedRequest {
+    RecordedRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        authorization: Some(format!("Bearer {}", API_KEY)),
+        body,
+    }
+}
+
+const LAST_PAGE: &str = r#"{ "total": 1, "links": { "next": "", "prev": "" } }"#;
+
+#[tokio::test]
+async fn test_block_storage_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let instance_id = Uuid::new_v4();
+    let block = json!({
+        "id": id,
+        "mount_id": "ewr-2f5d7a314fkb",
+        "attached_to_instance": instance_id,
+        "size_gb": 50,
+        "label": "data"
+    });
+    let server = RecordingServer::start(json!({
+        "block": block,
+        "blocks": [block],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    command::<CreateBlockStorage>(json!({ "region": "ewr", "size_gb": 50, "label": "data" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    GetBlockStorage::new(id).execute(&client).await.unwrap();
+    ListBlockStorage.execute(&client).await.unwrap();
+    command::<UpdateBlockStorage>(json!({ "id": id, "label": "logs", "size_gb": 60 }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<AttachBlockStorageToCompute>(
+        json!({ "id": id, "instance_id": instance_id, "live": true }),
+    )
+    .execute(&client)
+    .await
+    .unwrap();
+    command::<DetachBlockStorageFromCompute>(json!({ "id": id, "live": true }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteBlockStorage>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected(
+                "POST",
+                "/v2/blocks",
+                json!({ "region": "ewr", "size_gb": 50, "label": "data" })
+            ),
+            expected("GET", &format!("/v2/blocks/{}", id), Value::Null),
+            expected("GET", "/v2/blocks?per_page=500", Value::Null),
+            expected(
+                "PATCH",
+                &format!("/v2/blocks/{}", id),
+                json!({ "label": "logs", "size_gb": 60 })
+            ),
+            expected(
+                "POST",
+                &format!("/v2/blocks/{}/attach", id),
+                json!({ "instance_id": instance_id, "live": true })
+            ),
+            expected(
+                "POST",
+                &format!("/v2/blocks/{}/detach", id),
+                json!({ "live": true })
+            ),
+            expected("DELETE", &format!("/v2/blocks/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_compute_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let firewall_group_id = Uuid::new_v4();
+    let instance = json!({
+        "id": id,
+        "plan": "vc2-1c-1gb",
+        "status": "active",
+        "main_ip": "192.0.2.10",
+        "label": "web",
+        "os_id": 1743,
+        "firewall_group_id": firewall_group_id
+    });
+    let server = RecordingServer::start(json!({
+        "instance": instance,
+        "instances": [instance],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+    let create = json!({
+        "region": "ewr",
+        "plan": "vc2-1c-1gb",
+        "label": "web",
+        "os_id": 1743,
+        "backups": "disabled",
+        "hostname": "web",
+        "sshkey_id": [Uuid::new_v4()],
+        "script_id": Uuid::new_v4(),
+        "user_data": "I2Nsb3VkLWNvbmZpZwo="
+    });
+    let update = json!({
+        "backups": "enabled",
+        "firewall_group_id": firewall_group_id,
+        "os_id": 1743,
+        "plan": "vc2-2c-4gb",
+        "ddos_protection": false,
+        "label": "web",
+        "user_data": "I2Nsb3VkLWNvbmZpZwo="
+    });
+
+    // WHEN
+    command::<CreateCompute>(create.clone())
+        .execute(&client)
+        .await
+        .unwrap();
+    GetCompute::new(id).execute(&client).await.unwrap();
+    ListCompute.execute(&client).await.unwrap();
+    let mut update_with_id = update.clone();
+    update_with_id["id"] = json!(id);
+    command::<UpdateCompute>(update_with_id)
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteCompute>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected("POST", "/v2/instances", create),
+            expected("GET", &format!("/v2/instances/{}", id), Value::Null),
+            expected("GET", "/v2/instances?per_page=500", Value::Null),
+            expected("PATCH", &format!("/v2/instances/{}", id), update),
+            expected("DELETE", &format!("/v2/instances/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_compute_snapshot_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let instance_id = Uuid::new_v4();
+    let snapshot = json!({
+        "id": id,
+        "description": "before upgrade",
+        "status": "complete",
+        "size": 26843545600_i64,
+        "date_created": "2025-06-30T12:00:00+00:00"
+    });
+    let server = RecordingServer::start(json!({
+        "snapshot": snapshot,
+        "snapshots": [snapshot],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    command::<CreateComputeSnapshot>(json!({
+        "instance_id": instance_id,
+        "description": "before upgrade"
+    }))
+    .execute(&client)
+    .await
+    .unwrap();
+    GetComputeSnapshot::new(id).execute(&client).await.unwrap();
+    ListComputeSnapshot.execute(&client).await.unwrap();
+    command::<UpdateComputeSnapshot>(json!({ "id": id, "description": "release 1.2" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<RestoreCompute>(json!({ "id": instance_id, "snapshot_id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+    let ambiguous_restore = command::<RestoreCompute>(json!({
+        "id": instance_id,
+        "snapshot_id": id,
+        "backup_id": "cb676a46-66fd-4dfb-b839-443f2e6c0b60"
+    }))
+    .execute(&client)
+    .await;
+    command::<SetComputeBackupSchedule>(json!({
+        "id": instance_id,
+        "type": "weekly",
+        "hour": 3,
+        "dow": 1
+    }))
+    .execute(&client)
+    .await
+    .unwrap();
+    let weekly_without_day = command::<SetComputeBackupSchedule>(json!({
+        "id": instance_id,
+        "type": "weekly",
+        "hour": 3
+    }))
+    .execute(&client)
+    .await;
+    command::<DeleteComputeSnapshot>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert!(matches!(
+        ambiguous_restore,
+        Err(ServiceError::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        weekly_without_day,
+        Err(ServiceError::InvalidCommand(_))
+    ));
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected(
+                "POST",
+                "/v2/snapshots",
+                json!({ "instance_id": instance_id, "description": "before upgrade" })
+            ),
+            expected("GET", &format!("/v2/snapshots/{}", id), Value::Null),
+            expected("GET", "/v2/snapshots?per_page=500", Value::Null),
+            expected(
+                "PUT",
+                &format!("/v2/snapshots/{}", id),
+                json!({ "description": "release 1.2" })
+            ),
+            expected(
+                "POST",
+                &format!("/v2/instances/{}/restore", instance_id),
+                json!({ "snapshot_id": id })
+            ),
+            expected(
+                "POST",
+                &format!("/v2/instances/{}/backup-schedule", instance_id),
+                json!({ "type": "weekly", "hour": 3, "dow": 1 })
+            ),
+            expected("DELETE", &format!("/v2/snapshots/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_dns_requests() {
+    // GIVEN
+    let record_id = Uuid::new_v4();
+    let instance_id = Uuid::new_v4();
+    let domain = json!({ "domain": "example.com", "dns_sec": "disabled" });
+    let record = json!({
+        "id": record_id,
+        "type": "A",
+        "name": "www",
+        "data": "192.0.2.10",
+        "priority": 0,
+        "ttl": 300
+    });
+    let server = RecordingServer::start(json!({
+        "domain": domain,
+        "domains": [domain],
+        "record": record,
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    let created = command::<CreateDnsDomain>(json!({ "domain": "example.com" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    let listed = ListDnsDomain.execute(&client).await.unwrap();
+    command::<UpdateDnsDomain>(json!({ "id": "example.com", "dns_sec": "enabled" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    let missing_data = command::<CreateDnsRecord>(json!({
+        "domain": "example.com",
+        "name": "www",
+        "type": "A",
+        "instance_id": instance_id
+    }))
+    .execute(&client)
+    .await;
+    command::<CreateDnsRecord>(json!({
+        "domain": "example.com",
+        "name": "www",
+        "type": "A",
+        "data": "192.0.2.10",
+        "instance_id": instance_id
+    }))
+    .execute(&client)
+    .await
+    .unwrap();
+    let fetched = GetDnsRecord::new("example.com".to_string(), record_id)
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<UpdateDnsRecord>(json!({
+        "domain": "example.com",
+        "id": record_id,
+        "data": "192.0.2.11"
+    }))
+    .execute(&client)
+    .await
+    .unwrap();
+    command::<DeleteDnsRecord>(json!({ "domain": "example.com", "id": record_id }))
+        .execute(&client)
+        .await
+        .unwrap();
+    GetDnsDomain::new("example.com".to_string())
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteDnsDomain>(json!({ "id": "example.com" }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    // The domain name is the id
+    assert_eq!(created["id"], "example.com");
+    assert_eq!(listed[0].id, "example.com");
+    assert!(matches!(missing_data, Err(ServiceError::InvalidCommand(_))));
+    assert_eq!(fetched.domain, "example.com");
+    assert_eq!(fetched.data, "192.0.2.10");
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected("POST", "/v2/domains", json!({ "domain": "example.com" })),
+            expected("GET", "/v2/domains?per_page=500", Value::Null),
+            expected(
+                "PUT",
+                "/v2/domains/example.com",
+                json!({ "dns_sec": "enabled" })
+            ),
+            // The target is resolved by the executor, it is never sent
+            expected(
+                "POST",
+                "/v2/domains/example.com/records",
+                json!({ "name": "www", "type": "A", "data": "192.0.2.10" })
+            ),
+            expected(
+                "GET",
+                &format!("/v2/domains/example.com/records/{}", record_id),
+                Value::Null
+            ),
+            expected(
+                "PATCH",
+                &format!("/v2/domains/example.com/records/{}", record_id),
+                json!({ "data": "192.0.2.11" })
+            ),
+            expected(
+                "DELETE",
+                &format!("/v2/domains/example.com/records/{}", record_id),
+                Value::Null
+            ),
+            expected("GET", "/v2/domains/example.com", Value::Null),
+            expected("DELETE", "/v2/domains/example.com", Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_firewall_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let firewall_group = json!({ "id": id, "description": "web" });
+    let server = RecordingServer::start(json!({
+        "firewall_group": firewall_group,
+        "firewall_groups": [firewall_group],
+        "firewall_rule": { "id": 1 },
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+    let rule = json!({
+        "ip_type": "v4",
+        "protocol": "tcp",
+        "port": "443",
+        "subnet": "0.0.0.0",
+        "subnet_size": 0,
+        "notes": "https"
+    });
+
+    // WHEN
+    command::<CreateFirewallGroup>(json!({ "description": "web" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    GetFirewallGroup::new(id).execute(&client).await.unwrap();
+    ListFirewallGroup.execute(&client).await.unwrap();
+    command::<UpdateFirewallGroup>(json!({ "id": id, "description": "api" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    let mut rule_with_group = rule.clone();
+    rule_with_group["firewall_group_id"] = json!(id);
+    command::<CreateFirewallRule>(rule_with_group)
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteFirewallRule>(json!({ "firewall_group_id": id, "firewall_rule_id": 1 }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteFirewallGroup>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected("POST", "/v2/firewalls", json!({ "description": "web" })),
+            expected("GET", &format!("/v2/firewalls/{}", id), Value::Null),
+            expected("GET", "/v2/firewalls?per_page=500", Value::Null),
+            expected(
+                "PUT",
+                &format!("/v2/firewalls/{}", id),
+                json!({ "description": "api" })
+            ),
+            expected("POST", &format!("/v2/firewalls/{}/rules", id), rule),
+            expected(
+                "DELETE",
+                &format!("/v2/firewalls/{}/rules/1", id),
+                Value::Null
+            ),
+            expected("DELETE", &format!("/v2/firewalls/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_firewall_rule_requests() {
+    // GIVEN
+    let group_id = Uuid::new_v4();
+    let firewall_rule = json!({
+        "id": 2,
+        "action": "accept",
+        "ip_type": "v4",
+        "protocol": "tcp",
+        "port": "8443",
+        "subnet": "0.0.0.0",
+        "subnet_size": 0,
+        "notes": "https"
+    });
+    let server = RecordingServer::start(json!({
+        "firewall_rule": firewall_rule,
+        "firewall_rules": [firewall_rule],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+    let rule = json!({
+        "ip_type": "v4",
+        "protocol": "tcp",
+        "port": "8443",
+        "subnet": "0.0.0.0",
+        "subnet_size": 0,
+        "notes": "https"
+    });
+
+    // WHEN
+    let fetched = GetFirewallRule::new(group_id, 2)
+        .execute(&client)
+        .await
+        .unwrap();
+    let listed = ListFirewallRule::new(group_id)
+        .execute(&client)
+        .await
+        .unwrap();
+    let mut update = rule.clone();
+    update["firewall_group_id"] = json!(group_id);
+    update["firewall_rule_id"] = json!(1);
+    let replacement = command::<UpdateFirewallRule>(update)
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(fetched.firewall_group_id, group_id);
+    assert_eq!(listed[0].firewall_group_id, group_id);
+    assert_eq!(replacement, Some(firewall_rule));
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected(
+                "GET",
+                &format!("/v2/firewalls/{}/rules/2", group_id),
+                Value::Null
+            ),
+            expected(
+                "GET",
+                &format!("/v2/firewalls/{}/rules?per_page=500", group_id),
+                Value::Null
+            ),
+            // The replacement is created before the old rule is removed
+            expected("POST", &format!("/v2/firewalls/{}/rules", group_id), rule),
+            expected(
+                "DELETE",
+                &format!("/v2/firewalls/{}/rules/1", group_id),
+                Value::Null
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_load_balancer_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let instance_id = Uuid::new_v4();
+    let forwarding_rule = json!({
+        "frontend_protocol": "https",
+        "frontend_port": 443,
+        "backend_protocol": "http",
+        "backend_port": 8080
+    });
+    let load_balancer = json!({
+        "id": id,
+        "region": "ewr",
+        "label": "web",
+        "status": "active",
+        "ipv4": "192.0.2.20",
+        "has_ssl": true,
+        "forwarding_rules": [forwarding_rule],
+        "health_check": {
+            "protocol": "http",
+            "port": 8080,
+            "path": "/health",
+            "check_interval": 15,
+            "response_timeout": 5,
+            "unhealthy_threshold": 5,
+            "healthy_threshold": 5
+        },
+        "instances": [instance_id]
+    });
+    let server = RecordingServer::start(json!({
+        "load_balancer": load_balancer,
+        "load_balancers": [load_balancer],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+    let ssl = json!({ "private_key": "key", "certificate": "cert" });
+
+    // WHEN
+    command::<CreateLoadBalancer>(json!({
+        "region": "ewr",
+        "label": "web",
+        "forwarding_rules": [forwarding_rule],
+        "instances": [instance_id],
+        "ssl": ssl
+    }))
+    .execute(&client)
+    .await
+    .unwrap();
+    let fetched = GetLoadBalancer::new(id).execute(&client).await.unwrap();
+    ListLoadBalancer.execute(&client).await.unwrap();
+    command::<UpdateLoadBalancer>(json!({ "id": id, "instances": [], "remove_ssl": true }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteLoadBalancer>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(fetched.instances, vec![instance_id]);
+    assert_eq!(fetched.health_check.path, "/health");
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected(
+                "POST",
+                "/v2/load-balancers",
+                json!({
+                    "region": "ewr",
+                    "label": "web",
+                    "forwarding_rules": [forwarding_rule],
+                    "instances": [instance_id],
+                    "ssl": ssl
+                })
+            ),
+            expected("GET", &format!("/v2/load-balancers/{}", id), Value::Null),
+            expected("GET", "/v2/load-balancers?per_page=500", Value::Null),
+            // Only the given fields are changed
+            expected(
+                "PATCH",
+                &format!("/v2/load-balancers/{}", id),
+                json!({ "instances": [] })
+            ),
+            expected(
+                "DELETE",
+                &format!("/v2/load-balancers/{}/ssl", id),
+                Value::Null
+            ),
+            expected("DELETE", &format!("/v2/load-balancers/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_managed_database_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let database = json!({
+        "id": id,
+        "status": "Running",
+        "plan": "vultr-dbaas-startup-cc-1-55-2",
+        "database_engine": "pg",
+        "database_engine_version": 15,
+        "latest_backup": "",
+        "label": "orders"
+    });
+    let server = RecordingServer::start(json!({
+        "database": database,
+        "databases": [database],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+    let create = json!({
+        "database_engine": "pg",
+        "database_engine_version": 15,
+        "region": "ewr",
+        "plan": "vultr-dbaas-startup-cc-1-55-2",
+        "label": "orders"
+    });
+
+    // WHEN
+    command::<CreateManagedDatabase>(create.clone())
+        .execute(&client)
+        .await
+        .unwrap();
+    GetManagedDatabase::new(id).execute(&client).await.unwrap();
+    ListManagedDatabase.execute(&client).await.unwrap();
+    command::<UpdateManagedDatabase>(
+        json!({ "id": id, "plan": "vultr-dbaas-business-cc-1-55-2", "label": "orders" }),
+    )
+    .execute(&client)
+    .await
+    .unwrap();
+    command::<DeleteManagedDatabase>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected("POST", "/v2/databases", create),
+            expected("GET", &format!("/v2/databases/{}", id), Value::Null),
+            expected("GET", "/v2/databases?per_page=500", Value::Null),
+            expected(
+                "PUT",
+                &format!("/v2/databases/{}", id),
+                json!({ "plan": "vultr-dbaas-business-cc-1-55-2", "label": "orders" })
+            ),
+            expected("DELETE", &format!("/v2/databases/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_database_children_requests() {
+    // GIVEN
+    let database_id = Uuid::new_v4();
+    let server = RecordingServer::start(json!({
+        "user": { "username": "app", "password": "s3cret", "encryption": "" },
+        "db": { "name": "orders" },
+        "connection_pool": {
+            "name": "orders-pool",
+            "database": "orders",
+            "username": "app",
+            "mode": "transaction",
+            "size": 10
+        }
+    }))
+    .await;
+    let client = server.client();
+    let user = json!({ "username": "app" });
+    let pool = json!({
+        "name": "orders-pool",
+        "database": "orders",
+        "username": "app",
+        "mode": "transaction",
+        "size": 10
+    });
+    let with_database = |data: &Value| {
+        let mut data = data.clone();
+        data["database_id"] = json!(database_id);
+        data
+    };
+
+    // WHEN
+    let created_user = command::<CreateDatabaseUser>(with_database(&user))
+        .execute(&client)
+        .await
+        .unwrap();
+    let fetched_user = GetDatabaseUser::new(database_id, "app".to_string())
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<UpdateDatabaseUser>(
+        json!({ "database_id": database_id, "id": "app", "password": "n3w" }),
+    )
+    .execute(&client)
+    .await
+    .unwrap();
+    command::<CreateLogicalDatabase>(with_database(&json!({ "name": "orders" })))
+        .execute(&client)
+        .await
+        .unwrap();
+    GetLogicalDatabase::new(database_id, "orders".to_string())
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<CreateConnectionPool>(with_database(&pool))
+        .execute(&client)
+        .await
+        .unwrap();
+    let fetched_pool = GetConnectionPool::new(database_id, "orders-pool".to_string())
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<UpdateConnectionPool>(
+        json!({ "database_id": database_id, "id": "orders-pool", "size": 20 }),
+    )
+    .execute(&client)
+    .await
+    .unwrap();
+    command::<DeleteConnectionPool>(json!({ "database_id": database_id, "id": "orders-pool" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteLogicalDatabase>(json!({ "database_id": database_id, "id": "orders" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteDatabaseUser>(json!({ "database_id": database_id, "id": "app" }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert!(created_user.get("password").is_none());
+    assert_eq!(created_user["id"], "app");
+    assert_eq!(fetched_user.database_id, database_id);
+    assert_eq!(fetched_pool.size, 10);
+    let path = |rest: &str| format!("/v2/databases/{}/{}", database_id, rest);
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected("POST", &path("users"), user),
+            expected("GET", &path("users/app"), Value::Null),
+            expected("PUT", &path("users/app"), json!({ "password": "n3w" })),
+            expected("POST", &path("dbs"), json!({ "name": "orders" })),
+            expected("GET", &path("dbs/orders"), Value::Null),
+            expected("POST", &path("connection-pools"), pool),
+            expected("GET", &path("connection-pools/orders-pool"), Value::Null),
+            expected(
+                "PUT",
+                &path("connection-pools/orders-pool"),
+                json!({ "size": 20 })
+            ),
+            expected("DELETE", &path("connection-pools/orders-pool"), Value::Null),
+            expected("DELETE", &path("dbs/orders"), Value::Null),
+            expected("DELETE", &path("users/app"), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_object_storage_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let object_storage = json!({ "id": id, "cluster_id": 2, "label": "assets" });
+    let server = RecordingServer::start(json!({
+        "object_storage": object_storage,
+        "object_storages": [object_storage],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    command::<CreateObjectStorage>(json!({ "cluster_id": 2, "tier_id": 1, "label": "assets" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    GetObjectStorage::new(id).execute(&client).await.unwrap();
+    ListObjectStorage.execute(&client).await.unwrap();
+    command::<UpdateObjectStorage>(json!({ "id": id, "label": "media" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteObjectStorage>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected(
+                "POST",
+                "/v2/object-storage",
+                json!({ "cluster_id": 2, "tier_id": 1, "label": "assets" })
+            ),
+            expected("GET", &format!("/v2/object-storage/{}", id), Value::Null),
+            expected("GET", "/v2/object-storage?per_page=500", Value::Null),
+            expected(
+                "PUT",
+                &format!("/v2/object-storage/{}", id),
+                json!({ "label": "media" })
+            ),
+            expected("DELETE", &format!("/v2/object-storage/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_object_storage_credential_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let credentials = json!({
+        "s3_hostname": "ewr1.vultrobjects.com",
+        "s3_access_key": "access",
+        "s3_secret_key": "secret"
+    });
+    let mut object_storage = credentials.clone();
+    object_storage["id"] = json!(id);
+    object_storage["label"] = json!("assets");
+    let server = RecordingServer::start(json!({
+        "object_storage": object_storage,
+        "s3_credentials": credentials
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    let fetched = GetObjectStorageCredentials::new(id)
+        .execute(&client)
+        .await
+        .unwrap();
+    let regenerated = RegenerateObjectStorageKeys::new(id)
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(fetched.s3_secret_key, "secret");
+    assert_eq!(regenerated, Some(credentials));
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected("GET", &format!("/v2/object-storage/{}", id), Value::Null),
+            expected(
+                "POST",
+                &format!("/v2/object-storage/{}/regenerate-keys", id),
+                Value::Null
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_ssh_key_and_startup_script_requests() {
+    // GIVEN
+    let ssh_key_id = Uuid::new_v4();
+    let script_id = Uuid::new_v4();
+    let server = RecordingServer::start(json!({
+        "ssh_key": { "id": ssh_key_id, "name": "deploy", "ssh_key": "ssh-ed25519 AAAA" },
+        "startup_script": { "id": script_id, "name": "bootstrap", "type": "boot" }
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    let ssh_key = CreateSshKey::new("deploy".to_string(), "ssh-ed25519 AAAA".to_string())
+        .execute(&client)
+        .await
+        .unwrap();
+    UpdateSshKey::new(ssh_key_id, Some("ci".to_string()), None)
+        .execute(&client)
+        .await
+        .unwrap();
+    DeleteSshKey {
+        id: Some(ssh_key_id),
+    }
+    .execute(&client)
+    .await
+    .unwrap();
+    let startup_script = CreateStartupScript::new(
+        "bootstrap".to_string(),
+        "boot".to_string(),
+        "IyEvYmluL3NoCg==".to_string(),
+    )
+    .execute(&client)
+    .await
+    .unwrap();
+    UpdateStartupScript::new(script_id, None, Some("IyEvYmluL2Jhc2gK".to_string()))
+        .execute(&client)
+        .await
+        .unwrap();
+    DeleteStartupScript {
+        id: Some(script_id),
+    }
+    .execute(&client)
+    .await
+    .unwrap();
+
+    // THEN
+    assert_eq!(ssh_key["id"], json!(ssh_key_id));
+    assert_eq!(startup_script["id"], json!(script_id));
+    assert_eq!(
+        server.requests(),
+        vec![
+            expected(
+                "POST",
+                "/v2/ssh-keys",
+                json!({ "name": "deploy", "ssh_key": "ssh-ed25519 AAAA" })
+            ),
+            // Only the given fields are changed
+            expected(
+                "PATCH",
+                &format!("/v2/ssh-keys/{}", ssh_key_id),
+                json!({ "name": "ci" })
+            ),
+            expected(
+                "DELETE",
+                &format!("/v2/ssh-keys/{}", ssh_key_id),
+                Value::Null
+            ),
+            expected(
+                "POST",
+                "/v2/startup-scripts",
+                json!({ "name": "bootstrap", "type": "boot", "script": "IyEvYmluL3NoCg==" })
+            ),
+            expected(
+                "PATCH",
+                &format!("/v2/startup-scripts/{}", script_id),
+                json!({ "script": "IyEvYmluL2Jhc2gK" })
+            ),
+            expected(
+                "DELETE",
+                &format!("/v2/startup-scripts/{}", script_id),
+                Value::Null
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_vpc_requests() {
+    // GIVEN
+    let id = Uuid::new_v4();
+    let instance_id = Uuid::new_v4();
+    let vpc = json!({
+        "id": id,
+        "region": "ewr",
+        "description": "private",
+        "v4_subnet": "10.99.0.0",
+        "v4_subnet_mask": 24
+    });
+    let server = RecordingServer::start(json!({
+        "vpc": vpc,
+        "vpcs": [vpc],
+        "meta": serde_json::from_str::<Value>(LAST_PAGE).unwrap()
+    }))
+    .await;
+    let client = server.client();
+
+    // WHEN
+    command::<CreateVpc>(json!({ "region": "ewr", "description": "private" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    GetVpc::new(id).execute(&client).await.unwrap();
+    ListVpc.execute(&client).await.unwrap();
+    command::<UpdateVpc>(json!({ "id": id, "description": "backend" }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<AttachVpcToCompute>(json!({ "id": instance_id, "vpc_id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DetachVpcFromCompute>(json!({ "id": instance_id, "vpc_id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+    command::<DeleteVpc>(json!({ "id": id }))
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // THEN
+    assert_eq!(
+        server.requests(),
+        vec![
+            // The subnet is left to Vultr when it is not given
+            expected(
+                "POST",
+                "/v2/vpcs",
+                json!({ "region": "ewr", "description": "private" })
+            ),
+            expected("GET", &format!("/v2/vpcs/{}", id), Value::Null),
+            expected("GET", "/v2/vpcs?per_page=500", Value::Null),
+            expected(
+                "PUT",
+                &format!("/v2/vpcs/{}", id),
+                json!({ "description": "backend" })
+            ),
+            expected(
+                "POST",
+                &format!("/v2/instances/{}/vpcs/attach", instance_id),
+                json!({ "vpc_id": id })
+            ),
+            expected(
+                "POST",
+                &format!("/v2/instances/{}/vpcs/detach", instance_id),
+                json!({ "vpc_id": id })
+            ),
+            expected("DELETE", &format!("/v2/vpcs/{}", id), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_error_response_is_decoded() {
+    // GIVEN
+    let server = RecordingServer::start_with_status(
+        StatusCode::BAD_REQUEST,
+        json!({ "error": "Invalid plan chosen.", "status": 400 }),
+    )
+    .await;
+
+    // WHEN
+    let result = command::<CreateFirewallGroup>(json!({ "description": "web" }))
+        .execute(&server.client())
+        .await;
+
+    // THEN
+    let Err(ServiceError::VultrApiError(err)) = result else {
+        panic!("expected a Vultr API error");
+    };
+    assert_eq!(err.status, 400);
+    assert_eq!(err.message, "Invalid plan chosen.");
+    assert_eq!(err.request_id.as_deref(), Some("test-request-id"));
+}
+
+#[tokio::test]
+async fn test_error_response_without_body_is_decoded() {
+    // GIVEN
+    let server =
+        RecordingServer::start_with_status(StatusCode::INTERNAL_SERVER_ERROR, Value::Null).await;
+
+    // WHEN
+    let result = command::<DeleteFirewallGroup>(json!({ "id": Uuid::new_v4() }))
+        .execute(&server.client())
+        .await;
+
+    // THEN
+    assert!(matches!(result, Err(ServiceError::VultrApiError(err)) if err.status == 500));
+}
+
+#[tokio::test]
+async fn test_not_found_response() {
+    // GIVEN
+    let server = RecordingServer::start_with_status(
+        StatusCode::NOT_FOUND,
+        json!({ "error": "Block storage not found.", "status": 404 }),
+    )
+    .await;
+
+    // WHEN
+    let result = GetBlockStorage::new(Uuid::new_v4())
+        .execute(&server.client())
+        .await;
+
+    // THEN
+    assert!(matches!(result, Err(ServiceError::NotFound)));
+}
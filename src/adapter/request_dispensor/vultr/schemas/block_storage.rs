@@ -1,14 +1,15 @@
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::extract_schema_from_response;
+use super::{check_response, extract_schema_from_response, list_all_pages};
 use crate::{
     adapter::request_dispensor::vultr::{
         interfaces::{
             ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
-            ExecuteVultrUpdateCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
         },
         VultrClient,
     },
@@ -19,7 +20,7 @@ use crate::{
 #[derive(Serialize)]
 pub struct ListBlockStorage;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateBlockStorage {
     region: String,
     size_gb: i64, // New size of the Block Storage in GB. Size may range between 10 and 40000 depending on the block_type
@@ -37,19 +38,19 @@ impl GetBlockStorage {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteBlockStorage {
     // This id can be None if the id is not assigned yet
     pub id: Option<Uuid>,
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateBlockStorage {
     #[serde(skip_serializing)]
     pub id: Option<Uuid>,
     label: String,
     size_gb: i64, // New size of the Block Storage in GB. Size may range between 10 and 40000 depending on the block_type
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct AttachBlockStorageToCompute {
     #[serde(skip_serializing)]
     pub id: Option<Uuid>,
@@ -57,27 +58,18 @@ pub struct AttachBlockStorageToCompute {
     live: bool, // true: do not restart the instance
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DetachBlockStorageFromCompute {
     #[serde(skip_serializing)]
     pub id: Option<Uuid>,
     live: bool, // true: do not restart the instance
 }
-// #[allow(refining_impl_trait)]
-// impl ExecuteVultrCommand for ListBlockStorage {
-//     async fn execute<'a>(self, vultr_client: &'a VultrClient, id_store: &'a mut HashMap<i64, String>) -> Result<Vec<BlockStorage>, ServiceError> {
-//         let response = vultr_client
-//             .build_request(Method::GET, "blocks".to_string())
-//             .send()
-//             .await?;
-//         extract_schema_from_response::<Vec<BlockStorage>>(response, "blocks").await
-//     }
-// }
 #[allow(refining_impl_trait)]
 impl ExecuteVultrCreateCommand for CreateBlockStorage {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
         let response = vultr_client
             .build_request(Method::POST, "blocks".to_string())
+            .json(&self)
             .send()
             .await?;
         extract_schema_from_response::<Value>(response, "block").await
@@ -94,13 +86,20 @@ impl ExecuteVultrGetCommand for GetBlockStorage {
     }
 }
 #[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListBlockStorage {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<BlockStorage>, ServiceError> {
+        list_all_pages::<BlockStorage>(vultr_client, "blocks", "blocks").await
+    }
+}
+#[allow(refining_impl_trait)]
 impl ExecuteVultrDeleteCommand for DeleteBlockStorage {
     async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::DELETE, format!("blocks/{}", id))
             .send()
             .await?;
+        check_response(response).await?;
         Ok(())
     }
 }
@@ -108,10 +107,12 @@ impl ExecuteVultrDeleteCommand for DeleteBlockStorage {
 impl ExecuteVultrUpdateCommand for UpdateBlockStorage {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
-            .build_request(Method::PUT, format!("blocks/{}", id))
+        let response = vultr_client
+            .build_request(Method::PATCH, format!("blocks/{}", id))
+            .json(&self)
             .send()
             .await?;
+        check_response(response).await?;
         Ok(None)
     }
 }
@@ -119,10 +120,12 @@ impl ExecuteVultrUpdateCommand for UpdateBlockStorage {
 impl ExecuteVultrUpdateCommand for AttachBlockStorageToCompute {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::POST, format!("blocks/{}/attach", id))
+            .json(&self)
             .send()
             .await?;
+        check_response(response).await?;
         Ok(None)
     }
 }
@@ -130,10 +133,12 @@ impl ExecuteVultrUpdateCommand for AttachBlockStorageToCompute {
 impl ExecuteVultrUpdateCommand for DetachBlockStorageFromCompute {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::POST, format!("blocks/{}/detach", id))
+            .json(&self)
             .send()
             .await?;
+        check_response(response).await?;
         Ok(None)
     }
 }
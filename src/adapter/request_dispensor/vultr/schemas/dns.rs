@@ -0,0 +1,258 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{check_response, extract_schema_from_response, list_all_pages};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::{
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
+        },
+        VultrClient,
+    },
+    domain::project::{
+        diagrams::{DnsDomain, DnsRecord},
+        enums::ResourceType,
+    },
+    errors::ServiceError,
+};
+
+#[derive(Serialize)]
+pub struct ListDnsDomain;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateDnsDomain {
+    domain: String, // e.g. "example.com"
+    // Vultr adds default records pointing at this address when it is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dns_sec: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GetDnsDomain {
+    domain: String,
+}
+
+impl GetDnsDomain {
+    pub fn new(domain: String) -> Self {
+        Self { domain }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateDnsDomain {
+    #[serde(skip_serializing)]
+    pub id: Option<String>, // The domain name
+    dns_sec: String, // "enabled" or "disabled"
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteDnsDomain {
+    // This id can be None if the domain is not created yet
+    pub id: Option<String>,
+}
+
+/// Either `data` or a target is given. With a target, the executor fills `data` in
+/// with the address of the compute or load balancer once Vultr reports it as ready.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateDnsRecord {
+    #[serde(skip_serializing)]
+    pub domain: String,
+    name: String,
+    #[serde(rename = "type")]
+    record_type: String, // e.g. "A", "CNAME"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<i64>,
+    #[serde(default, skip_serializing)]
+    instance_id: Option<Uuid>,
+    #[serde(default, skip_serializing)]
+    load_balancer_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+pub struct GetDnsRecord {
+    domain: String,
+    id: Uuid,
+}
+
+impl GetDnsRecord {
+    pub fn new(domain: String, id: Uuid) -> Self {
+        Self { domain, id }
+    }
+}
+
+/// Fields left out are kept as they are at Vultr.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateDnsRecord {
+    #[serde(skip_serializing)]
+    pub domain: String,
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<i64>,
+    #[serde(default, skip_serializing)]
+    instance_id: Option<Uuid>,
+    #[serde(default, skip_serializing)]
+    load_balancer_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteDnsRecord {
+    pub domain: String,
+    // This id can be None if the id is not assigned yet
+    pub id: Option<Uuid>,
+}
+
+impl CreateDnsRecord {
+    pub fn target(&self) -> Option<(ResourceType, Uuid)> {
+        target(self.instance_id, self.load_balancer_id)
+    }
+}
+
+impl UpdateDnsRecord {
+    pub fn target(&self) -> Option<(ResourceType, Uuid)> {
+        target(self.instance_id, self.load_balancer_id)
+    }
+}
+
+fn target(
+    instance_id: Option<Uuid>,
+    load_balancer_id: Option<Uuid>,
+) -> Option<(ResourceType, Uuid)> {
+    instance_id
+        .map(|id| (ResourceType::Compute, id))
+        .or(load_balancer_id.map(|id| (ResourceType::LoadBalancer, id)))
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateDnsDomain {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, "domains".to_string())
+            .json(&self)
+            .send()
+            .await?;
+        // Vultr names the domain `domain`, read it into `id` like every other resource
+        let dns_domain = extract_schema_from_response::<DnsDomain>(response, "domain").await?;
+        Ok(json!(dns_domain))
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetDnsDomain {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<DnsDomain, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("domains/{}", self.domain))
+            .send()
+            .await?;
+        extract_schema_from_response::<DnsDomain>(response, "domain").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListDnsDomain {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<DnsDomain>, ServiceError> {
+        list_all_pages::<DnsDomain>(vultr_client, "domains", "domains").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateDnsDomain {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.as_ref().ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::PUT, format!("domains/{}", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteDnsDomain {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::DELETE, format!("domains/{}", id))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateDnsRecord {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        if self.data.is_none() {
+            return Err(ServiceError::InvalidCommand(
+                "a record needs `data` or a target to take it from".to_string(),
+            ));
+        }
+        let response = vultr_client
+            .build_request(Method::POST, format!("domains/{}/records", self.domain))
+            .json(&self)
+            .send()
+            .await?;
+        extract_schema_from_response::<Value>(response, "record").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetDnsRecord {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<DnsRecord, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::GET,
+                format!("domains/{}/records/{}", self.domain, self.id),
+            )
+            .send()
+            .await?;
+        let mut dns_record = extract_schema_from_response::<DnsRecord>(response, "record").await?;
+        dns_record.domain = self.domain;
+        Ok(dns_record)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateDnsRecord {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::PATCH,
+                format!("domains/{}/records/{}", self.domain, id),
+            )
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteDnsRecord {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::DELETE,
+                format!("domains/{}/records/{}", self.domain, id),
+            )
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
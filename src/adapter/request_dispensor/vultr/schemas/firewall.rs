@@ -1,25 +1,26 @@
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::extract_schema_from_response;
+use super::{check_response, extract_schema_from_response, list_all_pages};
 use crate::{
     adapter::request_dispensor::vultr::{
         interfaces::{
             ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
-            ExecuteVultrUpdateCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
         },
         VultrClient,
     },
     domain::project::{
-        diagrams::FirewallGroup,
+        diagrams::{FirewallGroup, FirewallRule},
         enums::{IpType, Protocol},
     },
     errors::ServiceError,
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateFirewallGroup {
     description: String,
 }
@@ -35,20 +36,37 @@ impl GetFirewallGroup {
         Self { id }
     }
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateFirewallGroup {
-    pub id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>, // Use id as path parameter
     description: String,
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteFirewallGroup {
     // This id can be None if the id is not assigned yet
     pub id: Option<Uuid>,
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateFirewallRule {
     #[serde(skip_serializing)]
-    firewall_group_id: Uuid, // Use id as path parameter
+    pub firewall_group_id: Uuid, // Use id as path parameter
+    ip_type: IpType,
+    protocol: Protocol,
+    port: String,
+    subnet: String,
+    subnet_size: i64,
+    notes: String,
+}
+
+/// Vultr can't change a rule in place, it is replaced by a new one with another id.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateFirewallRule {
+    #[serde(skip_serializing)]
+    pub firewall_group_id: Uuid, // Use id as path parameter
+    // This id can be None if the id is not assigned yet
+    #[serde(skip_serializing)]
+    pub firewall_rule_id: Option<i64>,
     ip_type: IpType,
     protocol: Protocol,
     port: String,
@@ -61,10 +79,15 @@ pub struct CreateFirewallRule {
 pub struct ListFirewallRule {
     firewall_group_id: Uuid,
 }
+impl ListFirewallRule {
+    pub fn new(firewall_group_id: Uuid) -> Self {
+        Self { firewall_group_id }
+    }
+}
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteFirewallRule {
-    firewall_group_id: Uuid,
+    pub firewall_group_id: Uuid,
     // This id can be None if the id is not assigned yet
     pub firewall_rule_id: Option<i64>,
 }
@@ -74,13 +97,21 @@ pub struct GetFirewallRule {
     firewall_group_id: Uuid,
     firewall_rule_id: i64,
 }
+impl GetFirewallRule {
+    pub fn new(firewall_group_id: Uuid, firewall_rule_id: i64) -> Self {
+        Self {
+            firewall_group_id,
+            firewall_rule_id,
+        }
+    }
+}
 
 #[allow(refining_impl_trait)]
 impl ExecuteVultrCreateCommand for CreateFirewallGroup {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
         let response = vultr_client
             .build_request(Method::POST, "firewalls".to_string())
-            .json(&serde_json::json!(self))
+            .json(&self)
             .send()
             .await?;
         extract_schema_from_response::<Value>(response, "firewall_group").await
@@ -97,16 +128,23 @@ impl ExecuteVultrGetCommand for GetFirewallGroup {
         extract_schema_from_response::<FirewallGroup>(response, "firewall_group").await
     }
 }
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListFirewallGroup {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<FirewallGroup>, ServiceError> {
+        list_all_pages::<FirewallGroup>(vultr_client, "firewalls", "firewall_groups").await
+    }
+}
 
 #[allow(refining_impl_trait)]
 impl ExecuteVultrUpdateCommand for UpdateFirewallGroup {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::PUT, format!("firewalls/{}", id))
-            .json(&serde_json::json!(self))
+            .json(&self)
             .send()
             .await?;
+        check_response(response).await?;
 
         Ok(None)
     }
@@ -116,10 +154,11 @@ impl ExecuteVultrUpdateCommand for UpdateFirewallGroup {
 impl ExecuteVultrDeleteCommand for DeleteFirewallGroup {
     async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::DELETE, format!("firewalls/{}", id))
             .send()
             .await?;
+        check_response(response).await?;
 
         Ok(())
     }
@@ -133,7 +172,7 @@ impl ExecuteVultrCreateCommand for CreateFirewallRule {
                 Method::POST,
                 format!("firewalls/{}/rules", self.firewall_group_id),
             )
-            .json(&serde_json::json!(self))
+            .json(&self)
             .send()
             .await?;
 
@@ -141,13 +180,94 @@ impl ExecuteVultrCreateCommand for CreateFirewallRule {
     }
 }
 
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetFirewallRule {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<FirewallRule, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::GET,
+                format!(
+                    "firewalls/{}/rules/{}",
+                    self.firewall_group_id, self.firewall_rule_id
+                ),
+            )
+            .send()
+            .await?;
+        let mut firewall_rule =
+            extract_schema_from_response::<FirewallRule>(response, "firewall_rule").await?;
+        firewall_rule.firewall_group_id = self.firewall_group_id;
+        Ok(firewall_rule)
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListFirewallRule {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<FirewallRule>, ServiceError> {
+        let mut firewall_rules = list_all_pages::<FirewallRule>(
+            vultr_client,
+            &format!("firewalls/{}/rules", self.firewall_group_id),
+            "firewall_rules",
+        )
+        .await?;
+        for firewall_rule in firewall_rules.iter_mut() {
+            firewall_rule.firewall_group_id = self.firewall_group_id;
+        }
+        Ok(firewall_rules)
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateFirewallRule {
+    /// Create the replacement first so the group never goes without the rule.
+    /// If the old rule can't be removed, the replacement is removed again.
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let firewall_rule_id = self
+            .firewall_rule_id
+            .ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::POST,
+                format!("firewalls/{}/rules", self.firewall_group_id),
+            )
+            .json(&self)
+            .send()
+            .await?;
+        let replacement = extract_schema_from_response::<Value>(response, "firewall_rule").await?;
+
+        let removed = DeleteFirewallRule {
+            firewall_group_id: self.firewall_group_id,
+            firewall_rule_id: Some(firewall_rule_id),
+        }
+        .execute(vultr_client)
+        .await;
+        if let Err(err) = removed {
+            let rollback = DeleteFirewallRule {
+                firewall_group_id: self.firewall_group_id,
+                firewall_rule_id: replacement["id"].as_i64(),
+            }
+            .execute(vultr_client)
+            .await;
+            if let Err(rollback_err) = rollback {
+                tracing::error!(
+                    "Failed to remove replacement of firewall rule {} in group {}: {:?}",
+                    firewall_rule_id,
+                    self.firewall_group_id,
+                    rollback_err
+                );
+            }
+            return Err(err);
+        }
+        Ok(Some(replacement))
+    }
+}
+
 #[allow(refining_impl_trait)]
 impl ExecuteVultrDeleteCommand for DeleteFirewallRule {
     async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
         let firewall_rule_id = self
             .firewall_rule_id
             .ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(
                 Method::DELETE,
                 format!(
@@ -157,6 +277,7 @@ impl ExecuteVultrDeleteCommand for DeleteFirewallRule {
             )
             .send()
             .await?;
+        check_response(response).await?;
 
         Ok(())
     }
@@ -1,24 +1,29 @@
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
     adapter::request_dispensor::vultr::{
         interfaces::{
-            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrUpdateCommand,
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
         },
         VultrClient,
     },
-    domain::project::enums::BackupStatus,
+    domain::project::{
+        diagrams::{BackupSchedule, Compute},
+        enums::{BackupScheduleType, BackupStatus},
+    },
     errors::ServiceError,
 };
 
-use super::extract_schema_from_response;
+use super::{check_response, extract_schema_from_response, list_all_pages};
 
 #[derive(Serialize, Deserialize)]
 pub struct ListCompute;
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateCompute {
     pub region: String,
     pub plan: String,
@@ -26,12 +31,25 @@ pub struct CreateCompute {
     pub os_id: i64,
     pub backups: BackupStatus,
     pub hostname: String,
+    // Registered through the project, see `SshKey` and `StartupScript`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sshkey_id: Option<Vec<Uuid>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_id: Option<Uuid>,
+    // Base64 encoded cloud-init data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_data: Option<String>,
 }
 #[derive(Serialize, Deserialize)]
 pub struct GetCompute {
     id: Uuid,
 }
-#[derive(Serialize, Deserialize)]
+impl GetCompute {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateCompute {
     #[serde(skip_serializing)]
     pub id: Option<Uuid>, // Use id as path parameter
@@ -41,12 +59,32 @@ pub struct UpdateCompute {
     plan: String,
     ddos_protection: bool,
     label: String,
+    // Vultr takes SSH keys and startup scripts only when an instance is created, user data can change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_data: Option<String>,
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteCompute {
     // This id can be None if the id is not assigned yet
     pub id: Option<Uuid>,
 }
+/// Restore an instance from one of its backups or from a snapshot, exactly one of them is given.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RestoreCompute {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>, // Compute id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_id: Option<String>,
+}
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SetComputeBackupSchedule {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>, // Compute id
+    #[serde(flatten)]
+    pub schedule: BackupSchedule,
+}
 
 // pub struct VultrCreateCommand {
 //     pub command_type: String,  // 명령어 타입
@@ -58,18 +96,37 @@ impl ExecuteVultrCreateCommand for CreateCompute {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
         let response = vultr_client
             .build_request(Method::POST, "instances".to_string())
+            .json(&self)
             .send()
             .await?;
         extract_schema_from_response::<Value>(response, "instance").await
     }
 }
 
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetCompute {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Compute, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("instances/{}", self.id))
+            .send()
+            .await?;
+        extract_schema_from_response::<Compute>(response, "instance").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListCompute {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<Compute>, ServiceError> {
+        list_all_pages::<Compute>(vultr_client, "instances", "instances").await
+    }
+}
+
 #[allow(refining_impl_trait)]
 impl ExecuteVultrUpdateCommand for UpdateCompute {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
         let response = vultr_client
-            .build_request(Method::PUT, format!("instances/{}", id))
+            .build_request(Method::PATCH, format!("instances/{}", id))
+            .json(&self)
             .send()
             .await?;
         Ok(Some(
@@ -82,10 +139,58 @@ impl ExecuteVultrUpdateCommand for UpdateCompute {
 impl ExecuteVultrDeleteCommand for DeleteCompute {
     async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::DELETE, format!("instances/{}", id))
             .send()
             .await?;
+        check_response(response).await?;
         Ok(())
     }
 }
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for RestoreCompute {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        if self.snapshot_id.is_some() == self.backup_id.is_some() {
+            return Err(ServiceError::InvalidCommand(
+                "restore needs either `snapshot_id` or `backup_id`".to_string(),
+            ));
+        }
+        let response = vultr_client
+            .build_request(Method::POST, format!("instances/{}/restore", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for SetComputeBackupSchedule {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        if !(0..24).contains(&self.schedule.hour) {
+            return Err(ServiceError::InvalidCommand(
+                "backup `hour` must be between 0 and 23".to_string(),
+            ));
+        }
+        match (self.schedule.schedule_type, self.schedule.dow) {
+            (BackupScheduleType::Weekly, Some(dow)) if (1..=7).contains(&dow) => {}
+            (BackupScheduleType::Weekly, _) => {
+                return Err(ServiceError::InvalidCommand(
+                    "weekly backups need `dow` between 1 (Sunday) and 7 (Saturday)".to_string(),
+                ))
+            }
+            (BackupScheduleType::Daily, _) => {}
+        }
+        let response = vultr_client
+            .build_request(Method::POST, format!("instances/{}/backup-schedule", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
@@ -0,0 +1,145 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{check_response, extract_schema_from_response, list_all_pages};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::{
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
+        },
+        VultrClient,
+    },
+    domain::project::diagrams::{ForwardingRule, HealthCheck, LoadBalancer},
+    errors::ServiceError,
+};
+
+#[derive(Serialize)]
+pub struct ListLoadBalancer;
+
+/// Certificate that turns SSL on for the https forwarding rules.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LoadBalancerSsl {
+    private_key: String,
+    certificate: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateLoadBalancer {
+    region: String,
+    label: String,
+    forwarding_rules: Vec<ForwardingRule>,
+    // Vultr checks tcp on the first backend port when it is left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    health_check: Option<HealthCheck>,
+    #[serde(default)]
+    instances: Vec<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssl: Option<LoadBalancerSsl>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssl_redirect: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct GetLoadBalancer {
+    id: Uuid,
+}
+
+impl GetLoadBalancer {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+
+/// Fields left out are kept as they are at Vultr.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateLoadBalancer {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    forwarding_rules: Option<Vec<ForwardingRule>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    health_check: Option<HealthCheck>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    instances: Option<Vec<Uuid>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssl: Option<LoadBalancerSsl>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssl_redirect: Option<bool>,
+    // Remove the certificate, i.e. turn SSL off
+    #[serde(default, skip_serializing)]
+    remove_ssl: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteLoadBalancer {
+    // This id can be None if the id is not assigned yet
+    pub id: Option<Uuid>,
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateLoadBalancer {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, "load-balancers".to_string())
+            .json(&self)
+            .send()
+            .await?;
+        extract_schema_from_response::<Value>(response, "load_balancer").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetLoadBalancer {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<LoadBalancer, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("load-balancers/{}", self.id))
+            .send()
+            .await?;
+        extract_schema_from_response::<LoadBalancer>(response, "load_balancer").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListLoadBalancer {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<LoadBalancer>, ServiceError> {
+        list_all_pages::<LoadBalancer>(vultr_client, "load-balancers", "load_balancers").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateLoadBalancer {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::PATCH, format!("load-balancers/{}", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        if self.remove_ssl {
+            let response = vultr_client
+                .build_request(Method::DELETE, format!("load-balancers/{}/ssl", id))
+                .send()
+                .await?;
+            check_response(response).await?;
+        }
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteLoadBalancer {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::DELETE, format!("load-balancers/{}", id))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
@@ -1,64 +1,210 @@
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::extract_schema_from_response;
+use super::{check_response, extract_schema_from_response, list_all_pages};
 use crate::{
     adapter::request_dispensor::vultr::{
         interfaces::{
-            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrUpdateCommand,
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
         },
         VultrClient,
     },
-    domain::project::enums::DatabaseEngine,
+    domain::project::{
+        diagrams::{ConnectionPool, DatabaseUser, LogicalDatabase, ManagedDatabase},
+        enums::DatabaseEngine,
+    },
     errors::ServiceError,
 };
 #[derive(Serialize)]
-pub struct ListManagedDatabasePlans;
-#[derive(Serialize)]
 pub struct ListManagedDatabase;
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateManagedDatabase {
     database_engine: DatabaseEngine,
     database_engine_version: i64,
     region: String,
     plan: String,
     label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vpc_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trusted_ips: Option<Vec<String>>,
 }
 #[derive(Serialize)]
 pub struct GetManagedDatabase {
     pub id: Uuid, // Use id as path parameter
 }
-#[derive(Serialize, Deserialize)]
+impl GetManagedDatabase {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateManagedDatabase {
     #[serde(skip_serializing)]
     pub id: Option<Uuid>, // Use id as path parameter
     plan: String,
     label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vpc_id: Option<Uuid>,
+    // Replaces the whole list, an empty one lets everyone connect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trusted_ips: Option<Vec<String>>,
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteManagedDatabase {
     // This id can be None if the id is not assigned yet
     pub id: Option<Uuid>,
 }
+
+/// Vultr generates the password when none is given.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateDatabaseUser {
+    #[serde(skip_serializing)]
+    pub database_id: Uuid,
+    username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    // MySQL only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<String>,
+}
+#[derive(Serialize)]
+pub struct GetDatabaseUser {
+    database_id: Uuid,
+    username: String,
+}
+impl GetDatabaseUser {
+    pub fn new(database_id: Uuid, username: String) -> Self {
+        Self {
+            database_id,
+            username,
+        }
+    }
+}
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateDatabaseUser {
+    #[serde(skip_serializing)]
+    pub database_id: Uuid,
+    #[serde(skip_serializing)]
+    pub id: Option<String>, // The user name
+    password: String,
+}
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteDatabaseUser {
+    pub database_id: Uuid,
+    // This id can be None if the user is not created yet
+    pub id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateLogicalDatabase {
+    #[serde(skip_serializing)]
+    pub database_id: Uuid,
+    name: String,
+}
+#[derive(Serialize)]
+pub struct GetLogicalDatabase {
+    database_id: Uuid,
+    name: String,
+}
+impl GetLogicalDatabase {
+    pub fn new(database_id: Uuid, name: String) -> Self {
+        Self { database_id, name }
+    }
+}
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteLogicalDatabase {
+    pub database_id: Uuid,
+    // This id can be None if the database is not created yet
+    pub id: Option<String>,
+}
+
+/// PostgreSQL only.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateConnectionPool {
+    #[serde(skip_serializing)]
+    pub database_id: Uuid,
+    name: String,
+    database: String,
+    username: String,
+    mode: String, // "session", "transaction" or "statement"
+    size: i64,
+}
+#[derive(Serialize)]
+pub struct GetConnectionPool {
+    database_id: Uuid,
+    name: String,
+}
+impl GetConnectionPool {
+    pub fn new(database_id: Uuid, name: String) -> Self {
+        Self { database_id, name }
+    }
+}
+/// Fields left out are kept as they are at Vultr.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateConnectionPool {
+    #[serde(skip_serializing)]
+    pub database_id: Uuid,
+    #[serde(skip_serializing)]
+    pub id: Option<String>, // The pool name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    database: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<i64>,
+}
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteConnectionPool {
+    pub database_id: Uuid,
+    // This id can be None if the pool is not created yet
+    pub id: Option<String>,
+}
 #[allow(refining_impl_trait)]
 impl ExecuteVultrCreateCommand for CreateManagedDatabase {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
         let response = vultr_client
             .build_request(Method::POST, "databases".to_string())
+            .json(&self)
             .send()
             .await?;
         extract_schema_from_response::<Value>(response, "database").await
     }
 }
 
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetManagedDatabase {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<ManagedDatabase, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("databases/{}", self.id))
+            .send()
+            .await?;
+        extract_schema_from_response::<ManagedDatabase>(response, "database").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListManagedDatabase {
+    async fn execute(
+        self,
+        vultr_client: &VultrClient,
+    ) -> Result<Vec<ManagedDatabase>, ServiceError> {
+        list_all_pages::<ManagedDatabase>(vultr_client, "databases", "databases").await
+    }
+}
+
 #[allow(refining_impl_trait)]
 impl ExecuteVultrUpdateCommand for UpdateManagedDatabase {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
         let response = vultr_client
             .build_request(Method::PUT, format!("databases/{}", id))
+            .json(&self)
             .send()
             .await?;
         Ok(Some(
@@ -71,10 +217,189 @@ impl ExecuteVultrUpdateCommand for UpdateManagedDatabase {
 impl ExecuteVultrDeleteCommand for DeleteManagedDatabase {
     async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::DELETE, format!("databases/{}", id))
             .send()
             .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateDatabaseUser {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::POST,
+                format!("databases/{}/users", self.database_id),
+            )
+            .json(&self)
+            .send()
+            .await?;
+        // Read through `DatabaseUser` so the password Vultr answers with goes no further
+        let database_user = extract_schema_from_response::<DatabaseUser>(response, "user").await?;
+        Ok(json!(database_user))
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetDatabaseUser {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<DatabaseUser, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::GET,
+                format!("databases/{}/users/{}", self.database_id, self.username),
+            )
+            .send()
+            .await?;
+        let mut database_user =
+            extract_schema_from_response::<DatabaseUser>(response, "user").await?;
+        database_user.database_id = self.database_id;
+        Ok(database_user)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateDatabaseUser {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.as_ref().ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::PUT,
+                format!("databases/{}/users/{}", self.database_id, id),
+            )
+            .json(&self)
+            .send()
+            .await?;
+        // The answer holds the new password, the user is fetched again instead
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteDatabaseUser {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::DELETE,
+                format!("databases/{}/users/{}", self.database_id, id),
+            )
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateLogicalDatabase {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, format!("databases/{}/dbs", self.database_id))
+            .json(&self)
+            .send()
+            .await?;
+        // Vultr names the database `name`, read it into `id` like every other resource
+        let logical_database =
+            extract_schema_from_response::<LogicalDatabase>(response, "db").await?;
+        Ok(json!(logical_database))
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetLogicalDatabase {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<LogicalDatabase, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::GET,
+                format!("databases/{}/dbs/{}", self.database_id, self.name),
+            )
+            .send()
+            .await?;
+        let mut logical_database =
+            extract_schema_from_response::<LogicalDatabase>(response, "db").await?;
+        logical_database.database_id = self.database_id;
+        Ok(logical_database)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteLogicalDatabase {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::DELETE,
+                format!("databases/{}/dbs/{}", self.database_id, id),
+            )
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateConnectionPool {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::POST,
+                format!("databases/{}/connection-pools", self.database_id),
+            )
+            .json(&self)
+            .send()
+            .await?;
+        let connection_pool =
+            extract_schema_from_response::<ConnectionPool>(response, "connection_pool").await?;
+        Ok(json!(connection_pool))
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetConnectionPool {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<ConnectionPool, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::GET,
+                format!(
+                    "databases/{}/connection-pools/{}",
+                    self.database_id, self.name
+                ),
+            )
+            .send()
+            .await?;
+        let mut connection_pool =
+            extract_schema_from_response::<ConnectionPool>(response, "connection_pool").await?;
+        connection_pool.database_id = self.database_id;
+        Ok(connection_pool)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateConnectionPool {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.as_ref().ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::PUT,
+                format!("databases/{}/connection-pools/{}", self.database_id, id),
+            )
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteConnectionPool {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(
+                Method::DELETE,
+                format!("databases/{}/connection-pools/{}", self.database_id, id),
+            )
+            .send()
+            .await?;
+        check_response(response).await?;
         Ok(())
     }
 }
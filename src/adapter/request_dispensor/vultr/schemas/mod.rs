@@ -1,20 +1,69 @@
-use serde::de::DeserializeOwned;
+use reqwest::{Method, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 
+use super::VultrClient;
 use crate::errors::ServiceError;
 
 pub mod block_storage;
 pub mod conversions;
+pub mod dns;
 pub mod firewall;
 pub mod instance;
+pub mod load_balancer;
 pub mod managed_database;
 pub mod object_storage;
+pub mod snapshot;
+pub mod ssh_key;
+pub mod startup_script;
+pub mod vpc;
 
 pub const BASE_URL: &str = "https://api.vultr.com/v2";
+// Largest page Vultr serves for list requests
+pub const PER_PAGE: u32 = 500;
+
+/// Error answered by Vultr, e.g. `{"error": "Invalid plan", "status": 400}`.
+#[derive(Debug, Serialize)]
+pub struct VultrApiError {
+    pub status: u16,
+    pub message: String,
+    // Quote this to Vultr support
+    pub request_id: Option<String>,
+}
+
+/// Pass 2xx responses through. A 404 becomes `NotFound` so callers can tell a deleted resource apart.
+pub async fn check_response(
+    response: reqwest::Response,
+) -> Result<reqwest::Response, ServiceError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status == StatusCode::NOT_FOUND {
+        return Err(ServiceError::NotFound);
+    }
+    let request_id = response
+        .headers()
+        .get("x-request-id")
+        .and_then(|request_id| request_id.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await?;
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|body| body.get("error")?.as_str().map(str::to_string))
+        .unwrap_or(body);
+    Err(ServiceError::VultrApiError(VultrApiError {
+        status: status.as_u16(),
+        message,
+        request_id,
+    }))
+}
 
 pub async fn extract_schema_from_response<T: DeserializeOwned>(
     response: reqwest::Response,
     field_name: &str,
 ) -> Result<T, ServiceError> {
+    let response = check_response(response).await?;
     let response_json: serde_json::Value = response.json().await?;
     let schema_json = response_json
         .get(field_name)
@@ -22,3 +71,66 @@ pub async fn extract_schema_from_response<T: DeserializeOwned>(
     let schema: T = serde_json::from_value(schema_json.clone())?;
     Ok(schema)
 }
+
+/// Collect every item of a list endpoint, following `meta.links.next` until the last page.
+pub async fn list_all_pages<T: DeserializeOwned>(
+    vultr_client: &VultrClient,
+    path: &str,
+    field_name: &str,
+) -> Result<Vec<T>, ServiceError> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = vultr_client
+            .build_request(Method::GET, path.to_string())
+            .query(&[("per_page", PER_PAGE)]);
+        if let Some(cursor) = cursor.as_ref() {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        let response_json: Value = check_response(request.send().await?).await?.json().await?;
+        let page = response_json
+            .get(field_name)
+            .ok_or(ServiceError::ParseError)?;
+        items.extend(serde_json::from_value::<Vec<T>>(page.clone())?);
+
+        cursor = next_cursor(&response_json);
+        if cursor.is_none() {
+            return Ok(items);
+        }
+    }
+}
+
+// Vultr sends an empty string instead of omitting the link on the last page
+fn next_cursor(response_json: &Value) -> Option<String> {
+    response_json
+        .pointer("/meta/links/next")
+        .and_then(Value::as_str)
+        .filter(|cursor| !cursor.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_next_cursor() {
+        // GIVEN
+        let middle_page = json!({
+            "blocks": [],
+            "meta": { "total": 3, "links": { "next": "bmV4dF9fMg==", "prev": "" } }
+        });
+        let last_page = json!({
+            "blocks": [],
+            "meta": { "total": 3, "links": { "next": "", "prev": "cHJldl9fMQ==" } }
+        });
+
+        // WHEN
+        let cursors = (next_cursor(&middle_page), next_cursor(&last_page));
+
+        // THEN
+        assert_eq!(cursors, (Some("bmV4dF9fMg==".to_string()), None));
+    }
+}
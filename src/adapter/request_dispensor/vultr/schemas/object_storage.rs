@@ -1,25 +1,26 @@
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::extract_schema_from_response;
+use super::{check_response, extract_schema_from_response, list_all_pages};
 use crate::{
     adapter::request_dispensor::vultr::{
         interfaces::{
             ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
-            ExecuteVultrUpdateCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
         },
         VultrClient,
     },
-    domain::project::diagrams::ObjectStorage,
+    domain::project::{diagrams::ObjectStorage, object_storage::S3Credentials},
     errors::ServiceError,
 };
 
 #[derive(Serialize)]
 pub struct ListObjectStorage;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateObjectStorage {
     cluster_id: i64,
     tier_id: i64,
@@ -31,12 +32,12 @@ pub struct GetObjectStorage {
     id: Uuid, // Use id as path parameter
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeleteObjectStorage {
     // This id can be None if the id is not assigned yet
     pub id: Option<Uuid>,
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UpdateObjectStorage {
     #[serde(skip_serializing)]
     pub id: Option<Uuid>, // Use id as path parameter
@@ -47,11 +48,35 @@ impl GetObjectStorage {
         Self { id }
     }
 }
+
+/// Reads only the S3 credentials of an object storage, they never reach the diagram.
+#[derive(Serialize)]
+pub struct GetObjectStorageCredentials {
+    id: Uuid,
+}
+impl GetObjectStorageCredentials {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+
+/// The previous keys stop working once Vultr answers.
+#[derive(Serialize)]
+pub struct RegenerateObjectStorageKeys {
+    #[serde(skip_serializing)]
+    id: Uuid,
+}
+impl RegenerateObjectStorageKeys {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
 #[allow(refining_impl_trait)]
 impl ExecuteVultrCreateCommand for CreateObjectStorage {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
         let response = vultr_client
             .build_request(Method::POST, "object-storage".to_string())
+            .json(&self)
             .send()
             .await?;
         extract_schema_from_response::<Value>(response, "object_storage").await
@@ -68,13 +93,20 @@ impl ExecuteVultrGetCommand for GetObjectStorage {
     }
 }
 #[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListObjectStorage {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<ObjectStorage>, ServiceError> {
+        list_all_pages::<ObjectStorage>(vultr_client, "object-storage", "object_storages").await
+    }
+}
+#[allow(refining_impl_trait)]
 impl ExecuteVultrDeleteCommand for DeleteObjectStorage {
     async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::DELETE, format!("object-storage/{}", id))
             .send()
             .await?;
+        check_response(response).await?;
         Ok(())
     }
 }
@@ -82,10 +114,37 @@ impl ExecuteVultrDeleteCommand for DeleteObjectStorage {
 impl ExecuteVultrUpdateCommand for UpdateObjectStorage {
     async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
         let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
-        vultr_client
+        let response = vultr_client
             .build_request(Method::PUT, format!("object-storage/{}", id))
+            .json(&self)
             .send()
             .await?;
+        check_response(response).await?;
         Ok(None)
     }
 }
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetObjectStorageCredentials {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<S3Credentials, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("object-storage/{}", self.id))
+            .send()
+            .await?;
+        extract_schema_from_response::<S3Credentials>(response, "object_storage").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for RegenerateObjectStorageKeys {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let response = vultr_client
+            .build_request(
+                Method::POST,
+                format!("object-storage/{}/regenerate-keys", self.id),
+            )
+            .send()
+            .await?;
+        Ok(Some(
+            extract_schema_from_response::<Value>(response, "s3_credentials").await?,
+        ))
+    }
+}
@@ -0,0 +1,107 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{check_response, extract_schema_from_response, list_all_pages};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::{
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
+        },
+        VultrClient,
+    },
+    domain::project::diagrams::ComputeSnapshot,
+    errors::ServiceError,
+};
+
+#[derive(Serialize)]
+pub struct ListComputeSnapshot;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateComputeSnapshot {
+    pub instance_id: Uuid,
+    description: String,
+}
+
+#[derive(Serialize)]
+pub struct GetComputeSnapshot {
+    id: Uuid,
+}
+
+impl GetComputeSnapshot {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateComputeSnapshot {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>,
+    description: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteComputeSnapshot {
+    // This id can be None if the id is not assigned yet
+    pub id: Option<Uuid>,
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateComputeSnapshot {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, "snapshots".to_string())
+            .json(&self)
+            .send()
+            .await?;
+        extract_schema_from_response::<Value>(response, "snapshot").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetComputeSnapshot {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<ComputeSnapshot, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("snapshots/{}", self.id))
+            .send()
+            .await?;
+        extract_schema_from_response::<ComputeSnapshot>(response, "snapshot").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListComputeSnapshot {
+    async fn execute(
+        self,
+        vultr_client: &VultrClient,
+    ) -> Result<Vec<ComputeSnapshot>, ServiceError> {
+        list_all_pages::<ComputeSnapshot>(vultr_client, "snapshots", "snapshots").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateComputeSnapshot {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::PUT, format!("snapshots/{}", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteComputeSnapshot {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::DELETE, format!("snapshots/{}", id))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{check_response, extract_schema_from_response};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::{
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrUpdateCommand,
+        },
+        VultrClient,
+    },
+    errors::ServiceError,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateSshKey {
+    name: String,
+    ssh_key: String,
+}
+
+impl CreateSshKey {
+    pub fn new(name: String, ssh_key: String) -> Self {
+        Self { name, ssh_key }
+    }
+}
+
+/// Fields left out are kept as they are at Vultr.
+#[derive(Serialize, Deserialize)]
+pub struct UpdateSshKey {
+    #[serde(skip_serializing)]
+    id: Uuid, // Use id as path parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssh_key: Option<String>,
+}
+
+impl UpdateSshKey {
+    pub fn new(id: Uuid, name: Option<String>, ssh_key: Option<String>) -> Self {
+        Self { id, name, ssh_key }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteSshKey {
+    pub id: Option<Uuid>,
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateSshKey {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, "ssh-keys".to_string())
+            .json(&self)
+            .send()
+            .await?;
+        extract_schema_from_response::<Value>(response, "ssh_key").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateSshKey {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::PATCH, format!("ssh-keys/{}", self.id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteSshKey {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::DELETE, format!("ssh-keys/{}", id))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{check_response, extract_schema_from_response};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::{
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrUpdateCommand,
+        },
+        VultrClient,
+    },
+    errors::ServiceError,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateStartupScript {
+    name: String,
+    #[serde(rename = "type")]
+    script_type: String, // "boot" or "pxe"
+    script: String, // Base64 encoded
+}
+
+impl CreateStartupScript {
+    pub fn new(name: String, script_type: String, script: String) -> Self {
+        Self {
+            name,
+            script_type,
+            script,
+        }
+    }
+}
+
+/// Fields left out are kept as they are at Vultr.
+#[derive(Serialize, Deserialize)]
+pub struct UpdateStartupScript {
+    #[serde(skip_serializing)]
+    id: Uuid, // Use id as path parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    script: Option<String>,
+}
+
+impl UpdateStartupScript {
+    pub fn new(id: Uuid, name: Option<String>, script: Option<String>) -> Self {
+        Self { id, name, script }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteStartupScript {
+    pub id: Option<Uuid>,
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateStartupScript {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, "startup-scripts".to_string())
+            .json(&self)
+            .send()
+            .await?;
+        extract_schema_from_response::<Value>(response, "startup_script").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateStartupScript {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::PATCH, format!("startup-scripts/{}", self.id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteStartupScript {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::DELETE, format!("startup-scripts/{}", id))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
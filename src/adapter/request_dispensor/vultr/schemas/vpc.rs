@@ -0,0 +1,149 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{check_response, extract_schema_from_response, list_all_pages};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::{
+            ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+            ExecuteVultrListCommand, ExecuteVultrUpdateCommand,
+        },
+        VultrClient,
+    },
+    domain::project::diagrams::Vpc,
+    errors::ServiceError,
+};
+
+#[derive(Serialize)]
+pub struct ListVpc;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateVpc {
+    region: String,
+    description: String,
+    // Vultr picks a subnet when both are left out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    v4_subnet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    v4_subnet_mask: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct GetVpc {
+    id: Uuid,
+}
+
+impl GetVpc {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UpdateVpc {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>,
+    description: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DeleteVpc {
+    // This id can be None if the id is not assigned yet
+    pub id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AttachVpcToCompute {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>, // Compute id
+    pub vpc_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DetachVpcFromCompute {
+    #[serde(skip_serializing)]
+    pub id: Option<Uuid>, // Compute id
+    pub vpc_id: Uuid,
+}
+
+#[allow(refining_impl_trait)]
+impl ExecuteVultrCreateCommand for CreateVpc {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Value, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::POST, "vpcs".to_string())
+            .json(&self)
+            .send()
+            .await?;
+        extract_schema_from_response::<Value>(response, "vpc").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrGetCommand for GetVpc {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vpc, ServiceError> {
+        let response = vultr_client
+            .build_request(Method::GET, format!("vpcs/{}", self.id))
+            .send()
+            .await?;
+        extract_schema_from_response::<Vpc>(response, "vpc").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrListCommand for ListVpc {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Vec<Vpc>, ServiceError> {
+        list_all_pages::<Vpc>(vultr_client, "vpcs", "vpcs").await
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for UpdateVpc {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::PUT, format!("vpcs/{}", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrDeleteCommand for DeleteVpc {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<(), ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::DELETE, format!("vpcs/{}", id))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for AttachVpcToCompute {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::POST, format!("instances/{}/vpcs/attach", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
+#[allow(refining_impl_trait)]
+impl ExecuteVultrUpdateCommand for DetachVpcFromCompute {
+    async fn execute(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        let id = self.id.ok_or_else(|| ServiceError::NotFound)?;
+        let response = vultr_client
+            .build_request(Method::POST, format!("instances/{}/vpcs/detach", id))
+            .json(&self)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(None)
+    }
+}
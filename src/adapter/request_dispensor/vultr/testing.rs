@@ -0,0 +1,109 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, Method, StatusCode, Uri},
+    response::IntoResponse,
+    Json, Router,
+};
+use serde_json::Value;
+
+use super::{middleware::RequestPolicy, VultrClient};
+
+pub(crate) const API_KEY: &str = "test-api-key";
+
+/// Retry twice without noticeable delays.
+pub(crate) fn test_policy() -> RequestPolicy {
+    RequestPolicy {
+        timeout: Duration::from_secs(5),
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        rate_limit: 1000,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub authorization: Option<String>,
+    // Null when the request had no body
+    pub body: Value,
+}
+
+#[derive(Clone)]
+struct ServerState {
+    status: StatusCode,
+    response: Value,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+/// Local stand-in for the Vultr API. Answers every request with the same response and records it.
+pub(crate) struct RecordingServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl RecordingServer {
+    pub async fn start(response: Value) -> Self {
+        Self::start_with_status(StatusCode::OK, response).await
+    }
+
+    pub async fn start_with_status(status: StatusCode, response: Value) -> Self {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let state = ServerState {
+            status,
+            response,
+            requests: requests.clone(),
+        };
+        let app = Router::new().fallback(record).with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v2", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { base_url, requests }
+    }
+
+    pub fn client(&self) -> VultrClient {
+        VultrClient::with_policy(API_KEY, self.base_url.clone(), test_policy())
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+async fn record(
+    State(state): State<ServerState>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let request = RecordedRequest {
+        method: method.to_string(),
+        path: uri.to_string(),
+        authorization: headers
+            .get("authorization")
+            .and_then(|authorization| authorization.to_str().ok())
+            .map(str::to_string),
+        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+    };
+    state
+        .requests
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(request);
+    (
+        state.status,
+        [("x-request-id", "test-request-id")],
+        Json(state.response),
+    )
+}
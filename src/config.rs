@@ -1,5 +1,5 @@
-use crate::errors::ServiceError;
-use std::sync::OnceLock;
+use crate::{adapter::request_dispensor::vultr::schemas::BASE_URL, errors::ServiceError};
+use std::{sync::OnceLock, time::Duration};
 
 pub struct Config {
     pub database_url: String,
@@ -8,7 +8,16 @@ pub struct Config {
     pub gmail_username: String,
     pub gmail_app_password: String,
     pub jwt_secret: String,
+    pub credential_encryption_key: String,
     pub architector_server_url: String,
+    pub vultr_base_url: String,
+    pub vultr_request_timeout: Duration,
+    pub vultr_max_retries: u32,
+    pub vultr_retry_base_delay: Duration,
+    pub vultr_rate_limit: u32, // Requests per second per API key
+    pub vultr_ready_timeout: Duration,
+    pub vultr_ready_poll_interval: Duration,
+    pub deploy_job_stale_after: Duration, // Longer than a command can wait for its resource
 }
 
 impl Config {
@@ -26,8 +35,54 @@ impl Config {
             gmail_app_password: std::env::var("GMAIL_APP_PASSWORD").unwrap(),
             jwt_secret: std::env::var("JWT_SECRET")
                 .unwrap_or_else(|_| "your-secret-key".to_string()),
+            // * A well-known default would let anyone open the stored credentials
+            credential_encryption_key: match std::env::var("CREDENTIAL_ENCRYPTION_KEY") {
+                Ok(key) if !key.is_empty() => key,
+                _ if cfg!(test) => "test-credential-encryption-key".to_string(),
+                _ => panic!("CREDENTIAL_ENCRYPTION_KEY must be set"),
+            },
             architector_server_url: std::env::var("ARCHITECTOR_SERVER_URL")
                 .unwrap_or_else(|_| "http://localhost:8000".to_string()),
+            vultr_base_url: std::env::var("VULTR_BASE_URL")
+                .unwrap_or_else(|_| BASE_URL.to_string()),
+            vultr_request_timeout: Duration::from_secs(
+                std::env::var("VULTR_REQUEST_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap(),
+            ),
+            vultr_max_retries: std::env::var("VULTR_MAX_RETRIES")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap(),
+            vultr_retry_base_delay: Duration::from_millis(
+                std::env::var("VULTR_RETRY_BASE_DELAY_MS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .unwrap(),
+            ),
+            vultr_rate_limit: std::env::var("VULTR_RATE_LIMIT")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap(),
+            vultr_ready_timeout: Duration::from_secs(
+                std::env::var("VULTR_READY_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "900".to_string())
+                    .parse()
+                    .unwrap(),
+            ),
+            vultr_ready_poll_interval: Duration::from_secs(
+                std::env::var("VULTR_READY_POLL_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap(),
+            ),
+            deploy_job_stale_after: Duration::from_secs(
+                std::env::var("DEPLOY_JOB_STALE_AFTER_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()
+                    .unwrap(),
+            ),
         })
     }
 }
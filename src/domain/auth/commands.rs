@@ -6,8 +6,7 @@ pub(crate) struct CreateUserAccount {
     pub(crate) name: String,
     pub(crate) email: String,
     pub(crate) phone_num: String,
-    // TODO Hash password
-    pub(crate) password: String,
+    pub(crate) password: String, // Hashed before it is stored
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
@@ -21,9 +20,28 @@ pub(crate) struct RefreshTokens {
     pub(crate) refresh_token: String,
 }
 
+/// Ends the session the refresh token belongs to.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub(crate) struct Logout {
+    pub(crate) refresh_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct RequestPasswordReset {
+    pub(crate) email: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
-pub(crate) struct CreateVerification {
+pub(crate) struct ResetPassword {
     pub(crate) email: String,
+    pub(crate) code: String, // Emailed by `RequestPasswordReset`
+    pub(crate) new_password: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct ChangePassword {
+    pub(crate) current_password: String,
+    pub(crate) new_password: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
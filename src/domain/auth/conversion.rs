@@ -1,23 +1,25 @@
+use argon2::password_hash::Error as PasswordHashError;
 use chrono::Utc;
 use openssl::error::ErrorStack;
+use tokio::task::JoinError;
 use uuid::Uuid;
 
 use crate::{
-    domain::auth::{commands::CreateUserAccount, UserAccountAggregate},
+    domain::auth::{commands::CreateUserAccount, hash_password, UserAccountAggregate},
     errors::ServiceError,
 };
 
-impl From<CreateUserAccount> for UserAccountAggregate {
-    fn from(command: CreateUserAccount) -> Self {
-        UserAccountAggregate {
+impl UserAccountAggregate {
+    pub async fn from_command(command: CreateUserAccount) -> Result<Self, ServiceError> {
+        Ok(UserAccountAggregate {
             id: Uuid::new_v4(),
             email: command.email,
             name: command.name,
             phone_num: command.phone_num,
-            password: command.password,
+            password: hash_password(&command.password).await?,
             verified: false,
             create_dt: Utc::now(),
-        }
+        })
     }
 }
 
@@ -27,3 +29,17 @@ impl From<ErrorStack> for ServiceError {
         ServiceError::PemKeyError(error.to_string())
     }
 }
+
+impl From<JoinError> for ServiceError {
+    fn from(error: JoinError) -> Self {
+        tracing::error!("JoinError: {:?}", error);
+        ServiceError::_InternalServerError
+    }
+}
+
+impl From<PasswordHashError> for ServiceError {
+    fn from(error: PasswordHashError) -> Self {
+        tracing::error!("PasswordHashError: {:?}", error);
+        ServiceError::_InternalServerError
+    }
+}
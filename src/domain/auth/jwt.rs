@@ -15,6 +15,7 @@ pub struct Claims {
     pub exp: i64,       // expiration time
     pub iat: i64,       // issued at
     pub typ: TokenType, // token type
+    pub jti: Uuid,      // token id, refresh tokens are tracked by it
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -56,15 +57,17 @@ impl JwtToken {
         user_id: Uuid,
         email: &str,
     ) -> Result<String, ServiceError> {
-        self.generate_token(user_id, email, TokenType::Access)
+        self.generate_token(user_id, email, TokenType::Access, Uuid::new_v4())
     }
 
+    /// `jti` is the id the refresh token is stored under.
     pub fn generate_refresh_token(
         &self,
         user_id: Uuid,
         email: &str,
+        jti: Uuid,
     ) -> Result<String, ServiceError> {
-        self.generate_token(user_id, email, TokenType::Refresh)
+        self.generate_token(user_id, email, TokenType::Refresh, jti)
     }
 
     fn generate_token(
@@ -72,6 +75,7 @@ impl JwtToken {
         user_id: Uuid,
         email: &str,
         token_type: TokenType,
+        jti: Uuid,
     ) -> Result<String, ServiceError> {
         let now = Utc::now();
         let exp = now + token_type.get_duration();
@@ -81,6 +85,7 @@ impl JwtToken {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             typ: token_type,
+            jti,
         };
 
         encode(
@@ -107,6 +112,26 @@ impl JwtToken {
 
         Ok(token_data.claims)
     }
+
+    pub fn verify_access_token(&self, token: &str) -> Result<Claims, ServiceError> {
+        self.verify_token_type(token, TokenType::Access)
+    }
+
+    pub fn verify_refresh_token(&self, token: &str) -> Result<Claims, ServiceError> {
+        self.verify_token_type(token, TokenType::Refresh)
+    }
+
+    fn verify_token_type(
+        &self,
+        token: &str,
+        token_type: TokenType,
+    ) -> Result<Claims, ServiceError> {
+        let claims = self.verify_token(token)?;
+        if claims.typ != token_type {
+            return Err(ServiceError::InvalidJwtToken);
+        }
+        Ok(claims)
+    }
 }
 
 #[cfg(test)]
@@ -131,11 +156,36 @@ mod tests {
         let jwt = JwtToken::new();
         let user_id = Uuid::new_v4();
         let email = "test@example.com".to_string();
-        let token = jwt.generate_refresh_token(user_id, &email).unwrap();
+        let jti = Uuid::new_v4();
+        let token = jwt.generate_refresh_token(user_id, &email, jti).unwrap();
         let claims = jwt.verify_token(&token).unwrap();
 
         assert_eq!(claims.user_id, user_id);
         assert_eq!(claims.email, email);
         assert_eq!(claims.typ, TokenType::Refresh);
+        assert_eq!(claims.jti, jti);
+    }
+
+    #[test]
+    fn test_verify_token_type() {
+        let jwt = JwtToken::new();
+        let user_id = Uuid::new_v4();
+        let access_token = jwt
+            .generate_access_token(user_id, "test@example.com")
+            .unwrap();
+        let refresh_token = jwt
+            .generate_refresh_token(user_id, "test@example.com", Uuid::new_v4())
+            .unwrap();
+
+        assert!(jwt.verify_access_token(&access_token).is_ok());
+        assert!(jwt.verify_refresh_token(&refresh_token).is_ok());
+        assert!(matches!(
+            jwt.verify_access_token(&refresh_token),
+            Err(ServiceError::InvalidJwtToken)
+        ));
+        assert!(matches!(
+            jwt.verify_refresh_token(&access_token),
+            Err(ServiceError::InvalidJwtToken)
+        ));
     }
 }
@@ -3,13 +3,22 @@ pub mod conversion;
 pub mod jwt;
 pub mod private_key;
 
+use argon2::{
+    password_hash::{
+        rand_core::OsRng, Error as PasswordHashError, PasswordHash, PasswordHasher,
+        PasswordVerifier, SaltString,
+    },
+    Algorithm, Argon2,
+};
 use chrono::{DateTime, Duration, Utc};
+use openssl::{memcmp, sha::sha256};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::errors::ServiceError;
+use jwt::TokenType;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UserAccountAggregate {
@@ -17,7 +26,7 @@ pub struct UserAccountAggregate {
     pub email: String,
     pub name: String,
     pub phone_num: String,
-    pub password: String,
+    pub password: String, // Argon2id PHC string, plaintext only in rows written before hashing
     pub verified: bool,
     pub create_dt: DateTime<Utc>,
 }
@@ -26,6 +35,55 @@ impl UserAccountAggregate {
     pub fn set_account_verified(&mut self) {
         self.verified = true;
     }
+
+    pub async fn set_password(&mut self, password: &str) -> Result<(), ServiceError> {
+        self.password = hash_password(password).await?;
+        Ok(())
+    }
+
+    pub async fn verify_password(&self, password: &str) -> Result<(), ServiceError> {
+        if self.has_legacy_password() {
+            // Compare digests so that the lengths match and the comparison takes constant time
+            return match memcmp::eq(
+                &sha256(self.password.as_bytes()),
+                &sha256(password.as_bytes()),
+            ) {
+                true => Ok(()),
+                false => Err(ServiceError::Unauthorized),
+            };
+        }
+        let (hash, password) = (self.password.clone(), password.to_string());
+        tokio::task::spawn_blocking(move || {
+            let hash = PasswordHash::new(&hash)?;
+            match Argon2::default().verify_password(password.as_bytes(), &hash) {
+                Ok(()) => Ok(()),
+                Err(PasswordHashError::Password) => Err(ServiceError::Unauthorized),
+                Err(err) => Err(err.into()),
+            }
+        })
+        .await?
+    }
+
+    /// Rows written before passwords were hashed hold them in plaintext, they are rehashed on the next login.
+    /// Anything that isn't a PHC string carrying an Argon2 hash is one of them, whatever it starts with.
+    pub fn has_legacy_password(&self) -> bool {
+        match PasswordHash::new(&self.password) {
+            Ok(hash) => hash.hash.is_none() || Algorithm::try_from(hash.algorithm).is_err(),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Argon2 is deliberately slow, it runs on the blocking pool to keep the runtime's workers free.
+pub async fn hash_password(password: &str) -> Result<String, ServiceError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Ok(Argon2::default()
+            .hash_password(password.as_bytes(), &salt)?
+            .to_string())
+    })
+    .await?
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -61,6 +119,39 @@ impl VerificationCode {
     }
 }
 
+/// Issued refresh token. Tokens rotated from the same login share a family, which is revoked as a whole
+/// once a used token shows up again.
+#[derive(Debug, Clone)]
+pub struct RefreshTokenEntity {
+    pub jti: Uuid,
+    pub family_id: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub used_dt: Option<DateTime<Utc>>,
+    pub revoked: bool,
+    pub create_dt: DateTime<Utc>,
+}
+
+impl RefreshTokenEntity {
+    pub fn new(user_id: Uuid, family_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            jti: Uuid::new_v4(),
+            family_id,
+            user_id,
+            expires_at: now + TokenType::Refresh.get_duration(),
+            used_dt: None,
+            revoked: false,
+            create_dt: now,
+        }
+    }
+}
+
+/// Password reset codes are kept apart from the sign up verification codes, which are stored under the bare email.
+pub fn get_password_reset_key(email: &str) -> String {
+    format!("password_reset_{}", email)
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub(crate) struct AuthenticationTokens {
     pub(crate) access_token: String,
@@ -76,7 +167,66 @@ impl AuthenticationTokens {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct VultrAPIKey {
-    pub(crate) api_key: String,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_account(password: String) -> UserAccountAggregate {
+        UserAccountAggregate {
+            id: Uuid::new_v4(),
+            email: "test@test.com".to_string(),
+            name: "Test User".to_string(),
+            phone_num: "01012345678".to_string(),
+            password,
+            verified: true,
+            create_dt: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_password() {
+        // GIVEN
+        let hashed = user_account(hash_password("password123").await.unwrap());
+        let legacy = user_account("password123".to_string());
+
+        // THEN
+        assert!(hashed.password.starts_with("$argon2id$"));
+        assert!(!hashed.has_legacy_password());
+        assert!(hashed.verify_password("password123").await.is_ok());
+        assert!(matches!(
+            hashed.verify_password("password124").await,
+            Err(ServiceError::Unauthorized)
+        ));
+        assert!(legacy.has_legacy_password());
+        assert!(legacy.verify_password("password123").await.is_ok());
+        assert!(matches!(
+            legacy.verify_password("password").await,
+            Err(ServiceError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_legacy_password_looking_like_a_hash() {
+        // GIVEN
+        let legacy = user_account("$argon2-is-my-password".to_string());
+
+        // THEN
+        assert!(legacy.has_legacy_password());
+        assert!(legacy
+            .verify_password("$argon2-is-my-password")
+            .await
+            .is_ok());
+        assert!(matches!(
+            legacy.verify_password("password").await,
+            Err(ServiceError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_same_password_hashes_differently() {
+        assert_ne!(
+            hash_password("password123").await.unwrap(),
+            hash_password("password123").await.unwrap()
+        );
+    }
 }
@@ -1,51 +1,16 @@
-use std::str::FromStr;
-
 use super::{
-    diagrams::{
-        BlockStorage, Compute, FirewallGroup, FirewallRule, ManagedDatabase, ObjectPosition,
-        ObjectStorage,
-    },
+    dependency::DependencyGraph,
+    deploy_job::DeployStatus,
+    diagrams::{DiagramSnapshot, ObjectPosition},
     enums::ResourceType,
+    plan::DeployPlan,
+    vultr_command::{created_types, CommandOutcome, VultrCommand},
     UserRole, VultrExecutionContext,
 };
-use crate::{
-    adapter::{
-        repositories::project::diagram::{
-            delete_block_storage, delete_compute, delete_firewall_group, delete_firewall_rule,
-            delete_managed_database, delete_object_storage, insert_block_storage, insert_compute,
-            insert_firewall_group, insert_firewall_rule, insert_managed_database,
-            insert_object_storage, update_block_storage, update_compute, update_firewall_group,
-            update_managed_database, update_object_storage,
-        },
-        request_dispensor::vultr::{
-            interfaces::{
-                ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
-                ExecuteVultrUpdateCommand,
-            },
-            schemas::{
-                block_storage::{
-                    AttachBlockStorageToCompute, CreateBlockStorage, DeleteBlockStorage,
-                    DetachBlockStorageFromCompute, GetBlockStorage, UpdateBlockStorage,
-                },
-                firewall::{
-                    CreateFirewallGroup, CreateFirewallRule, DeleteFirewallGroup,
-                    DeleteFirewallRule, GetFirewallGroup, UpdateFirewallGroup,
-                },
-                instance::{CreateCompute, DeleteCompute, UpdateCompute},
-                managed_database::{
-                    CreateManagedDatabase, DeleteManagedDatabase, UpdateManagedDatabase,
-                },
-                object_storage::{
-                    CreateObjectStorage, DeleteObjectStorage, GetObjectStorage, UpdateObjectStorage,
-                },
-            },
-        },
-    },
-    errors::ServiceError,
-};
+use crate::errors::ServiceError;
+use futures_util::future::join_all;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::PgConnection;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -80,6 +45,63 @@ pub(crate) struct RegisterVultApiKey {
     // pub(crate) api_key: Vec<u8>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct RegisterSshKey {
+    pub(crate) project_id: Uuid,
+    pub(crate) name: String,
+    pub(crate) ssh_key: String, // e.g. "ssh-ed25519 AAAA..."
+}
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct EditSshKey {
+    pub(crate) project_id: Uuid,
+    pub(crate) id: Uuid,
+    pub(crate) name: Option<String>,
+    pub(crate) ssh_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct RegisterStartupScript {
+    pub(crate) project_id: Uuid,
+    pub(crate) name: String,
+    // "boot" or "pxe"
+    #[serde(rename = "type", default = "boot_script_type")]
+    pub(crate) script_type: String,
+    pub(crate) script: String, // Base64 encoded
+}
+
+fn boot_script_type() -> String {
+    "boot".to_string()
+}
+
+/// Fields left out are kept as they are. The type of a script cannot change.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct EditStartupScript {
+    pub(crate) project_id: Uuid,
+    pub(crate) id: Uuid,
+    pub(crate) name: Option<String>,
+    pub(crate) script: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub(crate) struct CreateBucket {
+    pub(crate) project_id: Uuid,
+    pub(crate) object_storage_id: Uuid,
+    pub(crate) name: String,
+}
+
+/// Change only where a resource sits on the canvas. Nothing is sent to Vultr.
+#[derive(Deserialize, ToSchema)]
+pub struct MoveResource {
+    pub resource_type: ResourceType,
+    // Defaults to the resource created under the command's temp_id
+    pub id: Option<Value>,
+    // Group of a firewall rule, domain of a DNS record or managed database of a user, logical
+    // database or connection pool. Their ids only identify them within it.
+    pub parent_id: Option<String>,
+}
+
 #[derive(Clone, Deserialize, Serialize, ToSchema)]
 pub struct CommandRequest {
     pub command_name: String,
@@ -115,406 +137,83 @@ impl ResourceResponse {
     }
 }
 
-#[derive(Clone, Deserialize, Serialize, ToSchema)]
-#[serde(rename_all = "snake_case")]
-pub struct CommandList {
-    pub command_list: Vec<CommandRequest>,
-}
-#[allow(unused)]
-impl CommandList {
-    pub async fn execute(
-        self,
-        context: &mut VultrExecutionContext,
-        trx: &mut PgConnection,
-    ) -> Result<(), ServiceError> {
-        for request in self.command_list {
-            match request.command_name.as_str() {
-                name if name.contains("Create") => {
-                    let id = match request.command_name.as_str() {
-                        name if name.contains("CreateCompute") => {
-                            let command: CreateCompute = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let compute: Compute = serde_json::from_value(res.clone())?;
-                            insert_compute(&compute, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateBlockStorage") => {
-                            let command: CreateBlockStorage = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let block_storage: BlockStorage = serde_json::from_value(res.clone())?;
-                            insert_block_storage(&block_storage, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateFirewallGroup") => {
-                            let command: CreateFirewallGroup =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let firewall_group: FirewallGroup =
-                                serde_json::from_value(res.clone())?;
-                            insert_firewall_group(&firewall_group, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateFirewallRule") => {
-                            let command: CreateFirewallRule = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let firewall_rule: FirewallRule = serde_json::from_value(res.clone())?;
-                            insert_firewall_rule(&firewall_rule, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateManagedDatabase") => {
-                            let command: CreateManagedDatabase =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let managed_database: ManagedDatabase =
-                                serde_json::from_value(res.clone())?;
-                            insert_managed_database(&managed_database, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateObjectStorage") => {
-                            let command: CreateObjectStorage =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let object_storage: ObjectStorage =
-                                serde_json::from_value(res.clone())?;
-                            insert_object_storage(&object_storage, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        _ => return Err(ServiceError::NotFound),
-                    };
-                    context.resource_map.insert(request.temp_id, id);
-                }
-                name if name.contains("Update") => {
-                    let id = context.get_id_with_temp_id(&request.temp_id)?;
-                    match request.command_name.as_str() {
-                        name if name.contains("UpdateCompute") => {
-                            let command: UpdateCompute = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?.unwrap();
-                            let compute: Compute = serde_json::from_value(res.clone())?;
-                            update_compute(&compute, trx).await?;
-                        }
-                        name if name.contains("UpdateBlockStorage") => {
-                            let command: UpdateBlockStorage = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let block_storage: BlockStorage = GetBlockStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_block_storage(&block_storage, trx).await?;
-                        }
-                        name if name.contains("AttachBlockStorageToCompute") => {
-                            let command: AttachBlockStorageToCompute =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let block_storage: BlockStorage = GetBlockStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_block_storage(&block_storage, trx).await?;
-                        }
-                        name if name.contains("DetachBlockStorageFromCompute") => {
-                            let command: DetachBlockStorageFromCompute =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let block_storage: BlockStorage = GetBlockStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_block_storage(&block_storage, trx).await?;
-                        }
-                        name if name.contains("UpdateFirewallGroup") => {
-                            let command: UpdateFirewallGroup =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let firewall_group: FirewallGroup = GetFirewallGroup::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_firewall_group(&firewall_group, trx).await?;
-                        }
-                        name if name.contains("UpdateManagedDatabase") => {
-                            let command: UpdateManagedDatabase =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?.unwrap();
-                            let managed_database: ManagedDatabase =
-                                serde_json::from_value(res.clone())?;
-                            update_managed_database(&managed_database, trx).await?;
-                        }
-                        name if name.contains("UpdateObjectStorage") => {
-                            let command: UpdateObjectStorage =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let object_storage: ObjectStorage = GetObjectStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_object_storage(&object_storage, trx).await?;
-                        }
-                        _ => return Err(ServiceError::NotFound),
-                    }
-                }
-                name if name.contains("Delete") => {
-                    let id = context.get_id_with_temp_id(&request.temp_id)?;
-                    match request.command_name.as_str() {
-                        name if name.contains("DeleteCompute") => {
-                            let command: DeleteCompute = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_compute(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteBlockStorage") => {
-                            let command: DeleteBlockStorage = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_block_storage(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteFirewallGroup") => {
-                            let command: DeleteFirewallGroup =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_firewall_group(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteFirewallRule") => {
-                            let command: DeleteFirewallRule = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = i64::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_firewall_rule(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteManagedDatabase") => {
-                            let command: DeleteManagedDatabase =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_managed_database(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteObjectStorage") => {
-                            let command: DeleteObjectStorage =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_object_storage(&context.project_id, &id, trx).await?;
-                        }
-                        _ => return Err(ServiceError::NotFound),
-                    }
-                }
-                _ => return Err(ServiceError::NotFound),
-            }
-        }
-        Ok(())
-    }
-}
-
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct DeployProject {
     pub project_id: Uuid,
     #[serde(alias = "commandList")]
     pub command_list: Vec<CommandRequest>,
+    // Once the job succeeded, keep polling created compute and databases until they are provisioned
+    #[serde(default, alias = "waitForReady")]
+    pub wait_for_ready: bool,
 }
 
 impl DeployProject {
+    pub fn plan(&self, snapshot: &DiagramSnapshot) -> DeployPlan {
+        DeployPlan::new(&self.command_list, snapshot)
+    }
+
+    /// Run the commands wave by wave. Vultr calls within a wave are sent concurrently, their
+    /// results are recorded one by one and returned in that order, for the caller to store.
     pub async fn execute(
         self,
         context: &mut VultrExecutionContext,
-        trx: &mut PgConnection,
-    ) -> Result<(), ServiceError> {
-        for request in self.command_list {
-            match request.command_name.as_str() {
-                name if name.contains("Create") => {
-                    let id = match request.command_name.as_str() {
-                        name if name.contains("CreateCompute") => {
-                            let command: CreateCompute = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let compute: Compute = serde_json::from_value(res.clone())?;
-                            insert_compute(&compute, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateBlockStorage") => {
-                            let command: CreateBlockStorage = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let block_storage: BlockStorage = serde_json::from_value(res.clone())?;
-                            insert_block_storage(&block_storage, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateFirewallGroup") => {
-                            let command: CreateFirewallGroup =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let firewall_group: FirewallGroup =
-                                serde_json::from_value(res.clone())?;
-                            insert_firewall_group(&firewall_group, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateFirewallRule") => {
-                            let command: CreateFirewallRule = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let firewall_rule: FirewallRule = serde_json::from_value(res.clone())?;
-                            insert_firewall_rule(&firewall_rule, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateManagedDatabase") => {
-                            let command: CreateManagedDatabase =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let managed_database: ManagedDatabase =
-                                serde_json::from_value(res.clone())?;
-                            insert_managed_database(&managed_database, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        name if name.contains("CreateObjectStorage") => {
-                            let command: CreateObjectStorage =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?;
-                            let object_storage: ObjectStorage =
-                                serde_json::from_value(res.clone())?;
-                            insert_object_storage(&object_storage, trx).await?;
-                            res.get("id")
-                                .ok_or_else(|| ServiceError::NotFound)?
-                                .to_string()
-                        }
-                        _ => return Err(ServiceError::NotFound),
-                    };
-                    context.resource_map.insert(request.temp_id, id);
-                }
-                name if name.contains("Update") => {
-                    let id = context.get_id_with_temp_id(&request.temp_id)?;
-                    match request.command_name.as_str() {
-                        name if name.contains("UpdateCompute") => {
-                            let command: UpdateCompute = serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?.unwrap();
-                            let compute: Compute = serde_json::from_value(res.clone())?;
-                            update_compute(&compute, trx).await?;
-                        }
-                        name if name.contains("UpdateBlockStorage") => {
-                            let command: UpdateBlockStorage = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let block_storage: BlockStorage = GetBlockStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_block_storage(&block_storage, trx).await?;
-                        }
-                        name if name.contains("AttachBlockStorageToCompute") => {
-                            let command: AttachBlockStorageToCompute =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let block_storage: BlockStorage = GetBlockStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_block_storage(&block_storage, trx).await?;
-                        }
-                        name if name.contains("DetachBlockStorageFromCompute") => {
-                            let command: DetachBlockStorageFromCompute =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let block_storage: BlockStorage = GetBlockStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_block_storage(&block_storage, trx).await?;
-                        }
-                        name if name.contains("UpdateFirewallGroup") => {
-                            let command: UpdateFirewallGroup =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let firewall_group: FirewallGroup = GetFirewallGroup::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_firewall_group(&firewall_group, trx).await?;
-                        }
-                        name if name.contains("UpdateManagedDatabase") => {
-                            let command: UpdateManagedDatabase =
-                                serde_json::from_value(request.data)?;
-                            let res = command.execute(context.vultr_client).await?.unwrap();
-                            let managed_database: ManagedDatabase =
-                                serde_json::from_value(res.clone())?;
-                            update_managed_database(&managed_database, trx).await?;
-                        }
-                        name if name.contains("UpdateObjectStorage") => {
-                            let command: UpdateObjectStorage =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?.unwrap();
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            let object_storage: ObjectStorage = GetObjectStorage::new(id)
-                                .execute(context.vultr_client)
-                                .await?;
-                            update_object_storage(&object_storage, trx).await?;
-                        }
-                        _ => return Err(ServiceError::NotFound),
-                    }
-                }
-                name if name.contains("Delete") => {
-                    let id = context.get_id_with_temp_id(&request.temp_id)?;
-                    match request.command_name.as_str() {
-                        name if name.contains("DeleteCompute") => {
-                            let command: DeleteCompute = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_compute(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteBlockStorage") => {
-                            let command: DeleteBlockStorage = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_block_storage(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteFirewallGroup") => {
-                            let command: DeleteFirewallGroup =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_firewall_group(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteFirewallRule") => {
-                            let command: DeleteFirewallRule = serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = i64::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_firewall_rule(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteManagedDatabase") => {
-                            let command: DeleteManagedDatabase =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_managed_database(&context.project_id, &id, trx).await?;
-                        }
-                        name if name.contains("DeleteObjectStorage") => {
-                            let command: DeleteObjectStorage =
-                                serde_json::from_value(request.data)?;
-                            command.execute(context.vultr_client).await?;
-                            let id = Uuid::from_str(&id).map_err(|_| ServiceError::NotFound)?;
-                            delete_object_storage(&context.project_id, &id, trx).await?;
-                        }
-                        _ => return Err(ServiceError::NotFound),
+    ) -> Result<Vec<CommandOutcome>, ServiceError> {
+        let graph = DependencyGraph::new(&self.command_list)?;
+        let created_types = created_types(&self.command_list);
+        let mut recorded = Vec::with_capacity(self.command_list.len());
+        let mut requests: Vec<Option<CommandRequest>> =
+            self.command_list.into_iter().map(Some).collect();
+
+        for wave in graph.waves() {
+            let mut calls = Vec::with_capacity(wave.len());
+            for index in wave.iter().copied() {
+                let Some(request) = requests[index].take() else {
+                    continue;
+                };
+                // Earlier waves created everything this command refers to
+                let (command, data) =
+                    VultrCommand::resolve(&request, &context.resource_map, &created_types)?;
+                context
+                    .report_command_status(index, DeployStatus::Running, None)
+                    .await;
+                calls.push((index, command, request.temp_id, request.position, data));
+            }
+
+            let shared_context: &VultrExecutionContext = context;
+            let outcomes = join_all(calls.into_iter().map(
+                |(index, command, temp_id, position, data)| async move {
+                    let outcome = command.call(temp_id, position, data, shared_context).await;
+                    (index, outcome)
+                },
+            ))
+            .await;
+
+            // Every successful call is recorded, even when another one in the wave failed,
+            // so that compensation knows about it. Statuses are reported once all are recorded.
+            let mut failure = None;
+            let mut statuses = Vec::with_capacity(outcomes.len());
+            for (index, outcome) in outcomes {
+                let result = outcome.and_then(|outcome| {
+                    context.record(&outcome)?;
+                    recorded.push(outcome);
+                    Ok(())
+                });
+                match result {
+                    Ok(()) => statuses.push((index, DeployStatus::Succeeded, None)),
+                    Err(err) => {
+                        statuses.push((index, DeployStatus::Failed, Some(format!("{:?}", err))));
+                        failure.get_or_insert(err);
                     }
                 }
-                _ => return Err(ServiceError::NotFound),
+            }
+            for (index, status, error) in statuses {
+                context.report_command_status(index, status, error).await;
+            }
+            if let Some(err) = failure {
+                return Err(err);
             }
         }
-        Ok(())
+        Ok(recorded)
     }
 }
 
@@ -623,17 +322,18 @@ mod tests {
             ]
         });
 
-        let command_list: CommandList = serde_json::from_value(json).unwrap();
-        assert_eq!(command_list.command_list.len(), 2);
+        let command_list: Vec<CommandRequest> =
+            serde_json::from_value(json["command_list"].clone()).unwrap();
+        assert_eq!(command_list.len(), 2);
 
-        let first_command = &command_list.command_list[0];
+        let first_command = &command_list[0];
         assert_eq!(first_command.command_name, "CreateCompute");
         assert_eq!(first_command.position.x, 20);
         assert_eq!(first_command.position.y, 20);
         assert_eq!(first_command.data["id"], "temp1");
         assert_eq!(first_command.data["project_id"], project_id.to_string());
 
-        let second_command = &command_list.command_list[1];
+        let second_command = &command_list[1];
         assert_eq!(second_command.command_name, "UpdateCompute");
         assert_eq!(second_command.position.x, 30);
         assert_eq!(second_command.position.y, 30);
@@ -989,11 +689,12 @@ mod tests {
             ]
         });
 
-        let command_list: CommandList =
-            serde_json::from_value(json).expect("Failed to parse CommandList");
+        let command_list: Vec<CommandRequest> =
+            serde_json::from_value(json["command_list"].clone())
+                .expect("Failed to parse the command list");
 
         // Create 명령어들 검증
-        let create_commands = &command_list.command_list[0..6];
+        let create_commands = &command_list[0..6];
         verify_create_compute(&create_commands[0]);
         verify_create_block_storage(&create_commands[1]);
         verify_create_firewall_group(&create_commands[2]);
@@ -1002,7 +703,7 @@ mod tests {
         verify_create_object_storage(&create_commands[5]);
 
         // Update 명령어들 검증
-        let update_commands = &command_list.command_list[6..13];
+        let update_commands = &command_list[6..13];
         verify_update_compute(&update_commands[0]);
         verify_update_block_storage(&update_commands[1]);
         verify_attach_block_storage(&update_commands[2]);
@@ -1012,7 +713,7 @@ mod tests {
         verify_update_object_storage(&update_commands[6]);
 
         // Delete 명령어들 검증
-        let delete_commands = &command_list.command_list[13..19];
+        let delete_commands = &command_list[13..19];
         verify_delete_compute(&delete_commands[0]);
         verify_delete_block_storage(&delete_commands[1]);
         verify_delete_firewall_group(&delete_commands[2]);
@@ -0,0 +1,477 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use super::{
+    enums::ResourceType,
+    resource_id,
+    vultr_command::{parse_id, required_parent, CommandKind, VultrCommand},
+    VultrExecutionContext,
+};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::ExecuteVultrGetCommand,
+        schemas::{
+            block_storage::GetBlockStorage,
+            dns::{GetDnsDomain, GetDnsRecord},
+            firewall::{GetFirewallGroup, GetFirewallRule},
+            instance::GetCompute,
+            load_balancer::GetLoadBalancer,
+            managed_database::{
+                GetConnectionPool, GetDatabaseUser, GetLogicalDatabase, GetManagedDatabase,
+            },
+            object_storage::GetObjectStorage,
+            snapshot::GetComputeSnapshot,
+            vpc::GetVpc,
+        },
+        VultrClient,
+    },
+    errors::ServiceError,
+};
+
+// Never kept past the Vultr call
+const SECRET_FIELDS: [&str; 1] = ["password"];
+
+/// A Vultr call that already took effect during the current deploy.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompletedStep {
+    Created {
+        resource_type: ResourceType,
+        resource_id: String,
+        // Firewall rules are addressed through their group, DNS records through their domain and
+        // database users, logical databases and connection pools through their managed database
+        parent_id: Option<String>,
+    },
+    Updated {
+        resource_type: ResourceType,
+        resource_id: String,
+        command_name: String,
+        data: Value,
+        previous: Value,
+    },
+    Deleted {
+        resource_type: ResourceType,
+        resource_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CompensationReport {
+    pub compensated: Vec<CompensationResult>,
+    pub not_compensated: Vec<CompensationResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CompensationResult {
+    pub step: CompletedStep,
+    pub reason: Option<String>,
+}
+
+impl VultrExecutionContext {
+    pub fn record_created(
+        &mut self,
+        resource_type: ResourceType,
+        res: &Value,
+        parent_id: Option<String>,
+    ) -> Result<(), ServiceError> {
+        self.completed_steps.push(CompletedStep::Created {
+            resource_type,
+            resource_id: resource_id(res)?,
+            parent_id,
+        });
+        Ok(())
+    }
+
+    /// Secrets in `data` are dropped, completed steps end up in the deploy job.
+    pub fn record_updated(
+        &mut self,
+        resource_type: ResourceType,
+        resource_id: &str,
+        command_name: &str,
+        mut data: Value,
+        previous: Value,
+    ) {
+        if let Some(fields) = data.as_object_mut() {
+            for field in SECRET_FIELDS {
+                fields.remove(field);
+            }
+        }
+        self.completed_steps.push(CompletedStep::Updated {
+            resource_type,
+            resource_id: resource_id.to_string(),
+            command_name: command_name.to_string(),
+            data,
+            previous,
+        });
+    }
+
+    pub fn record_deleted(&mut self, resource_type: ResourceType, resource_id: &str) {
+        self.completed_steps.push(CompletedStep::Deleted {
+            resource_type,
+            resource_id: resource_id.to_string(),
+        });
+    }
+
+    /// Live state of a resource at Vultr, kept so that an update can be reverted.
+    /// `parent_id` is the group of a firewall rule, the domain of a DNS record or the managed database of
+    /// a user, logical database or connection pool. Other resources have none.
+    pub async fn current_state(
+        &self,
+        resource_type: ResourceType,
+        resource_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<Value, ServiceError> {
+        let client = &self.vultr_client;
+        let state = match resource_type {
+            ResourceType::Compute => {
+                json!(
+                    GetCompute::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::BlockStorage => {
+                json!(
+                    GetBlockStorage::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::FirewallGroup => {
+                json!(
+                    GetFirewallGroup::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::FirewallRule => {
+                let firewall_group_id = parse_id(required_parent(parent_id)?)?;
+                json!(
+                    GetFirewallRule::new(firewall_group_id, parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::ManagedDatabase => {
+                json!(
+                    GetManagedDatabase::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::ObjectStorage => {
+                json!(
+                    GetObjectStorage::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::Vpc => json!(GetVpc::new(parse_id(resource_id)?).execute(client).await?),
+            ResourceType::LoadBalancer => {
+                json!(
+                    GetLoadBalancer::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::DnsDomain => json!(
+                GetDnsDomain::new(resource_id.to_string())
+                    .execute(client)
+                    .await?
+            ),
+            ResourceType::DnsRecord => {
+                let domain = required_parent(parent_id)?;
+                json!(
+                    GetDnsRecord::new(domain.to_string(), parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::ComputeSnapshot => {
+                json!(
+                    GetComputeSnapshot::new(parse_id(resource_id)?)
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::DatabaseUser => {
+                let database_id = parse_id(required_parent(parent_id)?)?;
+                json!(
+                    GetDatabaseUser::new(database_id, resource_id.to_string())
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::LogicalDatabase => {
+                let database_id = parse_id(required_parent(parent_id)?)?;
+                json!(
+                    GetLogicalDatabase::new(database_id, resource_id.to_string())
+                        .execute(client)
+                        .await?
+                )
+            }
+            ResourceType::ConnectionPool => {
+                let database_id = parse_id(required_parent(parent_id)?)?;
+                json!(
+                    GetConnectionPool::new(database_id, resource_id.to_string())
+                        .execute(client)
+                        .await?
+                )
+            }
+        };
+        Ok(state)
+    }
+
+    /// Undo completed steps, most recent first.
+    pub async fn compensate(&mut self) -> CompensationReport {
+        let mut report = CompensationReport::default();
+        while let Some(step) = self.completed_steps.pop() {
+            match step.compensate(&self.vultr_client).await {
+                Ok(()) => report
+                    .compensated
+                    .push(CompensationResult { step, reason: None }),
+                Err(reason) => report.not_compensated.push(CompensationResult {
+                    step,
+                    reason: Some(reason),
+                }),
+            }
+        }
+        report
+    }
+}
+
+impl CompletedStep {
+    async fn compensate(&self, vultr_client: &VultrClient) -> Result<(), String> {
+        match self {
+            CompletedStep::Created {
+                resource_type,
+                resource_id,
+                parent_id,
+            } => {
+                let data = match resource_type {
+                    ResourceType::FirewallRule => json!({
+                        "firewall_group_id": parent_id,
+                        "firewall_rule_id": i64::from_str(resource_id).map_err(|err| err.to_string())?,
+                    }),
+                    ResourceType::DnsRecord => json!({ "domain": parent_id, "id": resource_id }),
+                    ResourceType::DatabaseUser
+                    | ResourceType::LogicalDatabase
+                    | ResourceType::ConnectionPool => {
+                        json!({ "database_id": parent_id, "id": resource_id })
+                    }
+                    _ => json!({ "id": resource_id }),
+                };
+                delete_resource(resource_type, data, vultr_client)
+                    .await
+                    .map_err(|err| format!("{:?}", err))
+            }
+            CompletedStep::Updated {
+                resource_id,
+                command_name,
+                data,
+                previous,
+                ..
+            } => {
+                let (command_name, data) = match command_name.as_str() {
+                    "AttachBlockStorageToCompute" => (
+                        "DetachBlockStorageFromCompute",
+                        json!({ "id": resource_id, "live": true }),
+                    ),
+                    "DetachBlockStorageFromCompute" => (
+                        "AttachBlockStorageToCompute",
+                        json!({
+                            "id": resource_id,
+                            "instance_id": previous["attached_to_instance"],
+                            "live": true,
+                        }),
+                    ),
+                    "AttachVpcToCompute" => (
+                        "DetachVpcFromCompute",
+                        json!({ "id": resource_id, "vpc_id": data["vpc_id"] }),
+                    ),
+                    "DetachVpcFromCompute" => (
+                        "AttachVpcToCompute",
+                        json!({ "id": resource_id, "vpc_id": data["vpc_id"] }),
+                    ),
+                    // The rule was replaced, the replacement is the one to replace back
+                    "UpdateFirewallRule" => {
+                        let mut data = restore_data(data, previous);
+                        data["firewall_rule_id"] =
+                            json!(i64::from_str(resource_id).map_err(|err| err.to_string())?);
+                        ("UpdateFirewallRule", data)
+                    }
+                    // Put the previous address back rather than resolving the target again
+                    "UpdateDnsRecord" => {
+                        let mut data = restore_data(data, previous);
+                        if let Some(fields) = data.as_object_mut() {
+                            fields.remove("instance_id");
+                            fields.remove("load_balancer_id");
+                        }
+                        ("UpdateDnsRecord", data)
+                    }
+                    "UpdateDatabaseUser" => {
+                        return Err("the previous password is not known".to_string())
+                    }
+                    "RestoreCompute" => {
+                        return Err("a restored instance cannot be rolled back".to_string())
+                    }
+                    // Vultr won't report the schedule with the instance, so the previous one is unknown
+                    "SetComputeBackupSchedule" => {
+                        return Err("the previous backup schedule is not known".to_string())
+                    }
+                    name => (name, restore_data(data, previous)),
+                };
+                update_resource(command_name, data, vultr_client)
+                    .await
+                    .map_err(|err| format!("{:?}", err))
+            }
+            CompletedStep::Deleted { .. } => {
+                Err("deleted resources cannot be restored".to_string())
+            }
+        }
+    }
+}
+
+/// Payload of the applied update with every field that existed before taken from `previous`.
+fn restore_data(applied: &Value, previous: &Value) -> Value {
+    let mut restored = applied.clone();
+    if let Some(fields) = restored.as_object_mut() {
+        for (field, value) in fields.iter_mut() {
+            match previous.get(field) {
+                Some(previous_value) if !previous_value.is_null() => {
+                    *value = previous_value.clone()
+                }
+                _ => {}
+            }
+        }
+    }
+    restored
+}
+
+async fn delete_resource(
+    resource_type: &ResourceType,
+    data: Value,
+    vultr_client: &VultrClient,
+) -> Result<(), ServiceError> {
+    let command_name = match resource_type {
+        ResourceType::Compute => "DeleteCompute",
+        ResourceType::BlockStorage => "DeleteBlockStorage",
+        ResourceType::FirewallGroup => "DeleteFirewallGroup",
+        ResourceType::FirewallRule => "DeleteFirewallRule",
+        ResourceType::ManagedDatabase => "DeleteManagedDatabase",
+        ResourceType::ObjectStorage => "DeleteObjectStorage",
+        ResourceType::Vpc => "DeleteVpc",
+        ResourceType::LoadBalancer => "DeleteLoadBalancer",
+        ResourceType::DnsDomain => "DeleteDnsDomain",
+        ResourceType::DnsRecord => "DeleteDnsRecord",
+        ResourceType::ComputeSnapshot => "DeleteComputeSnapshot",
+        ResourceType::DatabaseUser => "DeleteDatabaseUser",
+        ResourceType::LogicalDatabase => "DeleteLogicalDatabase",
+        ResourceType::ConnectionPool => "DeleteConnectionPool",
+    };
+    VultrCommand::new(command_name, data)?
+        .send(vultr_client)
+        .await?;
+    Ok(())
+}
+
+async fn update_resource(
+    command_name: &str,
+    data: Value,
+    vultr_client: &VultrClient,
+) -> Result<(), ServiceError> {
+    let command = VultrCommand::new(command_name, data)?;
+    if command.kind() != CommandKind::Update {
+        return Err(ServiceError::InvalidCommand(format!(
+            "`{}` is not an update",
+            command_name
+        )));
+    }
+    command.send(vultr_client).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::adapter::request_dispensor::vultr::get_vultr_client;
+
+    #[test]
+    fn test_restore_data() {
+        // GIVEN
+        let applied = json!({ "id": "block-1", "label": "new-label", "size_gb": 200 });
+        let previous = json!({ "id": "block-1", "label": "old-label", "mount_id": "mount" });
+
+        // WHEN
+        let restored = restore_data(&applied, &previous);
+
+        // THEN
+        assert_eq!(
+            restored,
+            json!({ "id": "block-1", "label": "old-label", "size_gb": 200 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compensate_reports_steps_that_cannot_be_undone() {
+        // GIVEN
+        let project_id = Uuid::new_v4();
+        let mut context =
+            VultrExecutionContext::new(get_vultr_client(project_id, "api_key"), project_id);
+        context
+            .record_created(ResourceType::Compute, &json!({ "id": "not-a-uuid" }), None)
+            .unwrap();
+        context.record_deleted(ResourceType::BlockStorage, &Uuid::new_v4().to_string());
+
+        // WHEN
+        let report = context.compensate().await;
+
+        // THEN
+        assert!(report.compensated.is_empty());
+        assert_eq!(report.not_compensated.len(), 2);
+        // Most recent step is compensated first
+        assert!(matches!(
+            report.not_compensated[0].step,
+            CompletedStep::Deleted { .. }
+        ));
+        assert!(matches!(
+            report.not_compensated[1].step,
+            CompletedStep::Created { .. }
+        ));
+        assert!(context.completed_steps.is_empty());
+    }
+
+    #[test]
+    fn test_record_updated_drops_secrets() {
+        // GIVEN
+        let project_id = Uuid::new_v4();
+        let mut context =
+            VultrExecutionContext::new(get_vultr_client(project_id, "api_key"), project_id);
+        let database_id = Uuid::new_v4();
+
+        // WHEN
+        context.record_updated(
+            ResourceType::DatabaseUser,
+            "app",
+            "UpdateDatabaseUser",
+            json!({ "database_id": database_id, "id": "app", "password": "s3cret" }),
+            json!({ "database_id": database_id, "id": "app" }),
+        );
+
+        // THEN
+        let steps = serde_json::to_string(&context.completed_steps).unwrap();
+        assert!(!steps.contains("s3cret"));
+    }
+
+    #[test]
+    fn test_resource_id() {
+        assert_eq!(resource_id(&json!({ "id": "abc" })).unwrap(), "abc");
+        assert_eq!(resource_id(&json!({ "id": 42 })).unwrap(), "42");
+        assert!(resource_id(&json!({})).is_err());
+    }
+}
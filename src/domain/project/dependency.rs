@@ -0,0 +1,226 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::{
+    commands::CommandRequest,
+    enums::ResourceType,
+    vultr_command::{reference_fields, references, CommandKind, VultrCommand},
+};
+use crate::errors::ServiceError;
+
+/// Execution order of a command list derived from the `$ref`s in the commands' data.
+/// Commands in the same wave do not depend on each other and may run concurrently.
+#[derive(Debug)]
+pub struct DependencyGraph {
+    waves: Vec<Vec<usize>>,
+}
+
+#[derive(Debug)]
+pub struct DependencyError {
+    pub index: usize,
+    pub message: String,
+}
+
+impl From<DependencyError> for ServiceError {
+    fn from(err: DependencyError) -> Self {
+        ServiceError::InvalidCommand(format!("command {}: {}", err.index, err.message))
+    }
+}
+
+impl DependencyGraph {
+    pub fn new(command_list: &[CommandRequest]) -> Result<Self, DependencyError> {
+        // * temp_id -> index and resource type of the command creating it
+        let mut creators: HashMap<&str, (usize, ResourceType)> = HashMap::new();
+        for (index, request) in command_list.iter().enumerate() {
+            if let Some((CommandKind::Create, resource_type)) =
+                VultrCommand::describe(&request.command_name)
+            {
+                creators.insert(&request.temp_id, (index, resource_type));
+            }
+        }
+
+        let mut dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); command_list.len()];
+        let mut last_command: HashMap<&str, usize> = HashMap::new();
+        for (index, request) in command_list.iter().enumerate() {
+            // Commands on the same resource keep their submission order
+            if let Some(previous) = last_command.insert(&request.temp_id, index) {
+                dependencies[index].insert(previous);
+            }
+            // Updates and deletes of a resource created in this deploy wait for the create
+            if let Some((creator, created_type)) = creators.get(request.temp_id.as_str()) {
+                if let Some((CommandKind::Update | CommandKind::Delete, resource_type)) =
+                    VultrCommand::describe(&request.command_name)
+                {
+                    if resource_type != *created_type {
+                        return Err(DependencyError {
+                            index,
+                            message: format!(
+                                "`{}` is a {:?}, `{}` changes a {:?}",
+                                request.temp_id, created_type, request.command_name, resource_type
+                            ),
+                        });
+                    }
+                }
+                if *creator != index {
+                    dependencies[index].insert(*creator);
+                }
+            }
+            for (field, reference) in references(&request.data) {
+                let Some((creator, resource_type)) = creators.get(reference) else {
+                    return Err(DependencyError {
+                        index,
+                        message: format!("`$ref` to `{}` is not created by this deploy", reference),
+                    });
+                };
+                let expected_type = reference_fields(&request.command_name)
+                    .iter()
+                    .find(|(reference_field, _)| Some(*reference_field) == field)
+                    .map(|(_, referenced_type)| referenced_type);
+                if let Some(expected_type) = expected_type.filter(|t| *t != resource_type) {
+                    return Err(DependencyError {
+                        index,
+                        message: format!(
+                            "`{}` refers to `{}`, which is a {:?} instead of a {:?}",
+                            field.unwrap_or_default(),
+                            reference,
+                            resource_type,
+                            expected_type
+                        ),
+                    });
+                }
+                dependencies[index].insert(*creator);
+            }
+        }
+
+        let mut remaining: BTreeSet<usize> = (0..command_list.len()).collect();
+        let mut waves = Vec::new();
+        while let Some(first) = remaining.first().copied() {
+            let wave: Vec<usize> = remaining
+                .iter()
+                .copied()
+                .filter(|index| {
+                    dependencies[*index]
+                        .iter()
+                        .all(|dependency| !remaining.contains(dependency))
+                })
+                .collect();
+            if wave.is_empty() {
+                return Err(DependencyError {
+                    index: first,
+                    message: "commands depend on each other in a cycle".to_string(),
+                });
+            }
+            for index in wave.iter() {
+                remaining.remove(index);
+            }
+            waves.push(wave);
+        }
+        Ok(Self { waves })
+    }
+
+    pub fn waves(&self) -> &[Vec<usize>] {
+        &self.waves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::project::diagrams::ObjectPosition;
+
+    fn command(command_name: &str, temp_id: &str, data: Value) -> CommandRequest {
+        CommandRequest {
+            command_name: command_name.to_string(),
+            temp_id: temp_id.to_string(),
+            position: ObjectPosition { x: 0, y: 0 },
+            data,
+        }
+    }
+
+    #[test]
+    fn test_dependency_graph_orders_references() {
+        // GIVEN
+        let command_list = vec![
+            command(
+                "CreateFirewallRule",
+                "rule-1",
+                json!({ "firewall_group_id": { "$ref": "group-1" } }),
+            ),
+            command("CreateBlockStorage", "block-1", json!({})),
+            command("CreateFirewallGroup", "group-1", json!({})),
+            command("CreateCompute", "compute-1", json!({})),
+            command(
+                "AttachBlockStorageToCompute",
+                "block-1",
+                json!({ "instance_id": { "$ref": "compute-1" } }),
+            ),
+            command(
+                "CreateFirewallRule",
+                "rule-2",
+                json!({ "firewall_group_id": Uuid::new_v4().to_string() }),
+            ),
+        ];
+
+        // WHEN
+        let graph = DependencyGraph::new(&command_list).unwrap();
+
+        // THEN
+        assert_eq!(graph.waves(), &[vec![1, 2, 3, 5], vec![0, 4]]);
+    }
+
+    #[test]
+    fn test_dependency_graph_rejects_dangling_reference() {
+        // GIVEN
+        let command_list = vec![command(
+            "CreateFirewallRule",
+            "rule-1",
+            json!({ "firewall_group_id": { "$ref": "group-1" } }),
+        )];
+
+        // WHEN
+        let err = DependencyGraph::new(&command_list).unwrap_err();
+
+        // THEN
+        assert_eq!(err.index, 0);
+        assert!(err.message.contains("group-1"));
+    }
+
+    #[test]
+    fn test_dependency_graph_rejects_cycle() {
+        // GIVEN
+        // Submission order says update first, the update needs the create first
+        let command_list = vec![
+            command(
+                "UpdateFirewallGroup",
+                "group-1",
+                json!({ "description": "web" }),
+            ),
+            command("CreateFirewallGroup", "group-1", json!({})),
+        ];
+
+        // WHEN
+        let err = DependencyGraph::new(&command_list).unwrap_err();
+
+        // THEN
+        assert!(err.message.contains("cycle"));
+    }
+
+    #[test]
+    fn test_dependency_graph_rejects_target_of_other_type() {
+        // GIVEN
+        let command_list = vec![
+            command("CreateFirewallGroup", "group-1", json!({})),
+            command("DeleteCompute", "group-1", json!({})),
+        ];
+
+        // WHEN
+        let err = DependencyGraph::new(&command_list).unwrap_err();
+
+        // THEN
+        assert_eq!(err.index, 1);
+        assert!(err.message.contains("FirewallGroup"));
+    }
+}
@@ -0,0 +1,237 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{commands::CommandRequest, compensation::CompensationReport, VultrExecutionContext};
+use crate::adapter::kv_store::{interfaces::DeployJobStore, rocks_db::get_rocks_db};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeployCommandStatus {
+    pub index: usize,
+    pub command_name: String,
+    pub temp_id: String,
+    pub status: DeployStatus,
+    pub error: Option<String>,
+}
+
+/// A deploy running in the background. Progress is stored in the KV store on every change.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeployJob {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub requested_by: String,
+    pub status: DeployStatus,
+    pub commands: Vec<DeployCommandStatus>,
+    // Command indexes grouped in the waves they run in
+    pub execution_order: Vec<Vec<usize>>,
+    pub error: Option<String>,
+    pub compensation: Option<CompensationReport>,
+    pub create_dt: DateTime<Utc>,
+    pub update_dt: DateTime<Utc>,
+}
+
+impl DeployJob {
+    pub fn new(
+        project_id: Uuid,
+        requested_by: String,
+        command_list: &[CommandRequest],
+        execution_order: Vec<Vec<usize>>,
+    ) -> Self {
+        let commands = command_list
+            .iter()
+            .enumerate()
+            .map(|(index, request)| DeployCommandStatus {
+                index,
+                command_name: request.command_name.clone(),
+                temp_id: request.temp_id.clone(),
+                status: DeployStatus::Pending,
+                error: None,
+            })
+            .collect();
+        Self {
+            id: Uuid::new_v4(),
+            project_id,
+            requested_by,
+            status: DeployStatus::Pending,
+            commands,
+            execution_order,
+            error: None,
+            compensation: None,
+            create_dt: Utc::now(),
+            update_dt: Utc::now(),
+        }
+    }
+
+    pub fn set_command_status(
+        &mut self,
+        index: usize,
+        status: DeployStatus,
+        error: Option<String>,
+    ) {
+        if self.is_finished() {
+            return;
+        }
+        if let Some(command) = self.commands.get_mut(index) {
+            command.status = status;
+            command.error = error;
+        }
+        if self.status == DeployStatus::Pending {
+            self.status = DeployStatus::Running;
+        }
+        self.update_dt = Utc::now();
+    }
+
+    pub fn succeed(&mut self) {
+        self.status = DeployStatus::Succeeded;
+        self.update_dt = Utc::now();
+    }
+
+    pub fn fail(&mut self, error: String, compensation: Option<CompensationReport>) {
+        self.status = DeployStatus::Failed;
+        self.error = Some(error);
+        self.compensation = compensation;
+        self.update_dt = Utc::now();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, DeployStatus::Succeeded | DeployStatus::Failed)
+    }
+
+    /// An unfinished job without progress for `stale_after` was left behind by the server running it.
+    pub fn is_stale(&self, stale_after: Duration) -> bool {
+        !self.is_finished()
+            && Utc::now()
+                .signed_duration_since(self.update_dt)
+                .to_std()
+                .is_ok_and(|idle| idle > stale_after)
+    }
+}
+
+impl VultrExecutionContext {
+    /// Progress is only reported, a job that can't be saved doesn't stop the deploy.
+    pub async fn report_command_status(
+        &mut self,
+        index: usize,
+        status: DeployStatus,
+        error: Option<String>,
+    ) {
+        if let Some(job) = self.deploy_job.as_mut() {
+            job.set_command_status(index, status, error);
+            if let Err(err) = get_rocks_db().await.save_deploy_job(job).await {
+                tracing::error!("Failed to save deploy job {}: {err:?}", job.id);
+            }
+        }
+    }
+}
+
+pub fn get_deploy_job_key(project_id: Uuid, job_id: Uuid) -> String {
+    format!("deploy_job_{}_{}", project_id, job_id)
+}
+
+pub const LATEST_DEPLOY_JOB_KEY_PREFIX: &str = "deploy_job_latest_";
+
+pub fn get_latest_deploy_job_key(project_id: Uuid) -> String {
+    format!("{}{}", LATEST_DEPLOY_JOB_KEY_PREFIX, project_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::domain::project::diagrams::ObjectPosition;
+
+    fn command_list() -> Vec<CommandRequest> {
+        ["CreateFirewallGroup", "CreateFirewallRule"]
+            .iter()
+            .enumerate()
+            .map(|(index, command_name)| CommandRequest {
+                command_name: command_name.to_string(),
+                temp_id: format!("temp-{}", index),
+                position: ObjectPosition { x: 0, y: 0 },
+                data: json!({}),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_new_deploy_job_is_pending() {
+        // WHEN
+        let job = DeployJob::new(
+            Uuid::new_v4(),
+            "test@test.com".to_string(),
+            &command_list(),
+            vec![vec![0, 1]],
+        );
+
+        // THEN
+        assert_eq!(job.status, DeployStatus::Pending);
+        assert_eq!(job.commands.len(), 2);
+        assert!(job
+            .commands
+            .iter()
+            .all(|command| command.status == DeployStatus::Pending));
+        assert!(!job.is_finished());
+    }
+
+    #[test]
+    fn test_deploy_job_progress() {
+        // GIVEN
+        let mut job = DeployJob::new(
+            Uuid::new_v4(),
+            "test@test.com".to_string(),
+            &command_list(),
+            vec![vec![0, 1]],
+        );
+
+        // WHEN
+        job.set_command_status(0, DeployStatus::Succeeded, None);
+        job.set_command_status(1, DeployStatus::Failed, Some("boom".to_string()));
+        job.fail("boom".to_string(), None);
+
+        // THEN
+        assert_eq!(job.commands[0].status, DeployStatus::Succeeded);
+        assert_eq!(job.commands[1].status, DeployStatus::Failed);
+        assert_eq!(job.commands[1].error.as_deref(), Some("boom"));
+        assert_eq!(job.status, DeployStatus::Failed);
+        assert!(job.is_finished());
+    }
+
+    #[test]
+    fn test_deploy_job_without_progress_is_stale() {
+        // GIVEN
+        let mut job = DeployJob::new(
+            Uuid::new_v4(),
+            "test@test.com".to_string(),
+            &command_list(),
+            vec![vec![0, 1]],
+        );
+        job.set_command_status(0, DeployStatus::Running, None);
+        let stale_after = Duration::from_secs(60);
+
+        // WHEN
+        let fresh = job.is_stale(stale_after);
+        job.update_dt = Utc::now() - chrono::Duration::minutes(2);
+        let idle = job.is_stale(stale_after);
+        job.succeed();
+        job.update_dt = Utc::now() - chrono::Duration::minutes(2);
+        let finished = job.is_stale(stale_after);
+
+        // THEN
+        assert!(!fresh);
+        assert!(idle);
+        assert!(!finished);
+    }
+}
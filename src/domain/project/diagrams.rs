@@ -1,16 +1,23 @@
+//! Stored diagram resources. Vultr won't return their layout (`x`, `y`) and ownership
+//! (`project_id`), those come from the deploy request and default when a Vultr answer is read.
+
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::types::Json;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::enums::{BackupStatus, DatabaseEngine, IpType, Protocol};
+use super::enums::{
+    BackupScheduleType, BackupStatus, DatabaseEngine, IpType, Protocol, ResourceType,
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct BlockStorage {
-    #[serde(skip)]
+    #[serde(default)]
     pub project_id: Uuid,
-    #[serde(skip)]
+    #[serde(default)]
     pub y: i64,
-    #[serde(skip)]
+    #[serde(default)]
     pub x: i64,
     // Vultr won't return region
     pub region: Option<String>, // e.g."ewr"
@@ -23,11 +30,11 @@ pub struct BlockStorage {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FirewallGroup {
-    #[serde(skip)]
+    #[serde(default)]
     pub project_id: Uuid,
-    #[serde(skip)]
+    #[serde(default)]
     pub y: i64,
-    #[serde(skip)]
+    #[serde(default)]
     pub x: i64,
     pub id: Uuid,
     pub description: String,
@@ -35,12 +42,15 @@ pub struct FirewallGroup {
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FirewallRule {
-    #[serde(skip)]
+    #[serde(default)]
     pub project_id: Uuid,
-    #[serde(skip)]
+    #[serde(default)]
     pub y: i64,
-    #[serde(skip)]
+    #[serde(default)]
     pub x: i64,
+    // Vultr won't return the group, it comes from the command that addressed the rule
+    #[serde(default)]
+    pub firewall_group_id: Uuid,
     pub id: i64,
     pub action: String,
     pub port: String,
@@ -53,11 +63,11 @@ pub struct FirewallRule {
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Compute {
-    #[serde(skip)]
+    #[serde(default)]
     pub project_id: Uuid,
-    #[serde(skip)]
+    #[serde(default)]
     pub y: i64,
-    #[serde(skip)]
+    #[serde(default)]
     pub x: i64,
     // Vultr won't return region
     pub region: Option<String>,
@@ -70,15 +80,51 @@ pub struct Compute {
     pub label: String,
     pub os_id: i64,
     pub firewall_group_id: String,
+    // Vultr won't return the VPC of an instance, None keeps the stored one
+    #[serde(default)]
+    pub vpc_id: Option<String>,
+    // Vultr won't return the schedule with the instance, None keeps the stored one
+    #[serde(default)]
+    pub backup_schedule: Option<Json<BackupSchedule>>,
+}
+
+/// When Vultr takes automatic backups of an instance, times are in UTC.
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub struct BackupSchedule {
+    #[serde(rename = "type")]
+    pub schedule_type: BackupScheduleType,
+    pub hour: i64, // 0 to 23
+    // 1 (Sunday) to 7 (Saturday), only for weekly backups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dow: Option<i64>,
+}
+
+/// Snapshot of an instance, an instance can be restored from it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ComputeSnapshot {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    pub id: Uuid,
+    // Vultr won't return the instance a snapshot was taken of, None keeps the stored one
+    #[serde(default)]
+    pub instance_id: Option<Uuid>,
+    pub description: String,
+    pub status: String, // "pending" until the snapshot is "complete"
+    pub size: i64,
+    pub date_created: String,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ManagedDatabase {
-    #[serde(skip)]
+    #[serde(default)]
     pub project_id: Uuid,
-    #[serde(skip)]
+    #[serde(default)]
     pub y: i64,
-    #[serde(skip)]
+    #[serde(default)]
     pub x: i64,
     // Vultr won't return region
     pub region: Option<String>, // e.g. "ewr"
@@ -89,15 +135,76 @@ pub struct ManagedDatabase {
     pub database_engine_version: i64,
     pub latest_backup: String,
     pub label: String,
+    // Empty when the database is not in a VPC
+    #[serde(default)]
+    pub vpc_id: String,
+    // Addresses allowed to connect, everyone when empty
+    #[serde(default)]
+    pub trusted_ips: Vec<String>,
+}
+
+/// User of a managed database. Vultr answers with its password, which is dropped here and never stored.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DatabaseUser {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    // Vultr won't return the database, it comes from the command that addressed the user
+    #[serde(default)]
+    pub database_id: Uuid,
+    // Vultr addresses a user by its name
+    #[serde(alias = "username")]
+    pub id: String,
+    // MySQL only, e.g. "caching_sha2_password"
+    #[serde(default)]
+    pub encryption: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LogicalDatabase {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    // Vultr won't return the database, it comes from the command that addressed this one
+    #[serde(default)]
+    pub database_id: Uuid,
+    #[serde(alias = "name")]
+    pub id: String,
+}
+
+/// PgBouncer pool of a PostgreSQL database.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConnectionPool {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    // Vultr won't return the database, it comes from the command that addressed the pool
+    #[serde(default)]
+    pub database_id: Uuid,
+    #[serde(alias = "name")]
+    pub id: String,
+    pub database: String, // Logical database the pool connects to
+    pub username: String,
+    pub mode: String, // "session", "transaction" or "statement"
+    pub size: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ObjectStorage {
-    #[serde(skip)]
+    #[serde(default)]
     pub project_id: Uuid,
-    #[serde(skip)]
+    #[serde(default)]
     pub y: i64,
-    #[serde(skip)]
+    #[serde(default)]
     pub x: i64,
     // Vultr won't return tier_id
     pub tier_id: Option<i64>,
@@ -106,12 +213,295 @@ pub struct ObjectStorage {
     pub label: String,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Vpc {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    pub region: String,
+    pub id: Uuid,
+    pub description: String,
+    pub v4_subnet: String,
+    pub v4_subnet_mask: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub struct ForwardingRule {
+    // Assigned by Vultr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub frontend_protocol: String, // "http", "https" or "tcp"
+    pub frontend_port: i64,
+    pub backend_protocol: String,
+    pub backend_port: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub struct HealthCheck {
+    pub protocol: String,
+    pub port: i64,
+    #[serde(default)]
+    pub path: String, // Only for http and https
+    pub check_interval: i64,
+    pub response_timeout: i64,
+    pub unhealthy_threshold: i64,
+    pub healthy_threshold: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoadBalancer {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    pub region: String,
+    pub id: Uuid,
+    pub label: String,
+    pub status: String,
+    pub ipv4: String,
+    pub has_ssl: bool,
+    pub forwarding_rules: Json<Vec<ForwardingRule>>,
+    pub health_check: Json<HealthCheck>,
+    // Attached compute
+    pub instances: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DnsDomain {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    // Vultr addresses a domain by its name
+    #[serde(alias = "domain")]
+    pub id: String,
+    pub dns_sec: String, // "enabled" or "disabled"
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DnsRecord {
+    #[serde(default)]
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub y: i64,
+    #[serde(default)]
+    pub x: i64,
+    // Vultr won't return the domain, it comes from the command that addressed the record
+    #[serde(default)]
+    pub domain: String,
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub record_type: String, // e.g. "A", "CNAME"
+    pub name: String,
+    pub data: String,
+    pub priority: i64,
+    pub ttl: i64,
+    // Vultr won't return the compute or load balancer the record points at, None keeps the stored one
+    #[serde(default)]
+    pub target_id: Option<Uuid>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ObjectPosition {
     pub x: i64,
     pub y: i64,
 }
 
+/// Every resource stored for a project, as read from the diagram tables.
+#[derive(Default)]
+pub struct DiagramSnapshot {
+    pub compute: Vec<Compute>,
+    pub managed_database: Vec<ManagedDatabase>,
+    pub object_storage: Vec<ObjectStorage>,
+    pub block_storage: Vec<BlockStorage>,
+    pub firewall_group: Vec<FirewallGroup>,
+    pub firewall_rule: Vec<FirewallRule>,
+    pub vpc: Vec<Vpc>,
+    pub load_balancer: Vec<LoadBalancer>,
+    pub dns_domain: Vec<DnsDomain>,
+    pub dns_record: Vec<DnsRecord>,
+    pub compute_snapshot: Vec<ComputeSnapshot>,
+    pub database_user: Vec<DatabaseUser>,
+    pub logical_database: Vec<LogicalDatabase>,
+    pub connection_pool: Vec<ConnectionPool>,
+}
+
+impl DiagramSnapshot {
+    pub fn resources(&self) -> Vec<(ResourceType, Value)> {
+        let mut res = Vec::new();
+        res.extend(
+            self.compute
+                .iter()
+                .map(|compute| (ResourceType::Compute, json!(compute))),
+        );
+        res.extend(
+            self.managed_database
+                .iter()
+                .map(|managed_database| (ResourceType::ManagedDatabase, json!(managed_database))),
+        );
+        res.extend(
+            self.object_storage
+                .iter()
+                .map(|object_storage| (ResourceType::ObjectStorage, json!(object_storage))),
+        );
+        res.extend(
+            self.block_storage
+                .iter()
+                .map(|block_storage| (ResourceType::BlockStorage, json!(block_storage))),
+        );
+        res.extend(
+            self.firewall_group
+                .iter()
+                .map(|firewall_group| (ResourceType::FirewallGroup, json!(firewall_group))),
+        );
+        res.extend(
+            self.firewall_rule
+                .iter()
+                .map(|firewall_rule| (ResourceType::FirewallRule, json!(firewall_rule))),
+        );
+        res.extend(self.vpc.iter().map(|vpc| (ResourceType::Vpc, json!(vpc))));
+        res.extend(
+            self.load_balancer
+                .iter()
+                .map(|load_balancer| (ResourceType::LoadBalancer, json!(load_balancer))),
+        );
+        res.extend(
+            self.dns_domain
+                .iter()
+                .map(|dns_domain| (ResourceType::DnsDomain, json!(dns_domain))),
+        );
+        res.extend(
+            self.dns_record
+                .iter()
+                .map(|dns_record| (ResourceType::DnsRecord, json!(dns_record))),
+        );
+        res.extend(
+            self.compute_snapshot
+                .iter()
+                .map(|compute_snapshot| (ResourceType::ComputeSnapshot, json!(compute_snapshot))),
+        );
+        res.extend(
+            self.database_user
+                .iter()
+                .map(|database_user| (ResourceType::DatabaseUser, json!(database_user))),
+        );
+        res.extend(
+            self.logical_database
+                .iter()
+                .map(|logical_database| (ResourceType::LogicalDatabase, json!(logical_database))),
+        );
+        res.extend(
+            self.connection_pool
+                .iter()
+                .map(|connection_pool| (ResourceType::ConnectionPool, json!(connection_pool))),
+        );
+        res
+    }
+
+    /// `parent_id` is the group of a firewall rule, the domain of a DNS record or the managed database of
+    /// a user, logical database or connection pool. Their ids only identify them within it.
+    pub fn find(
+        &self,
+        resource_type: &ResourceType,
+        id: &str,
+        parent_id: Option<&str>,
+    ) -> Option<Value> {
+        match resource_type {
+            ResourceType::Compute => self
+                .compute
+                .iter()
+                .find(|compute| compute.id.to_string() == id)
+                .map(|compute| json!(compute)),
+            ResourceType::ManagedDatabase => self
+                .managed_database
+                .iter()
+                .find(|managed_database| managed_database.id.to_string() == id)
+                .map(|managed_database| json!(managed_database)),
+            ResourceType::ObjectStorage => self
+                .object_storage
+                .iter()
+                .find(|object_storage| object_storage.id.to_string() == id)
+                .map(|object_storage| json!(object_storage)),
+            ResourceType::BlockStorage => self
+                .block_storage
+                .iter()
+                .find(|block_storage| block_storage.id.to_string() == id)
+                .map(|block_storage| json!(block_storage)),
+            ResourceType::FirewallGroup => self
+                .firewall_group
+                .iter()
+                .find(|firewall_group| firewall_group.id.to_string() == id)
+                .map(|firewall_group| json!(firewall_group)),
+            ResourceType::FirewallRule => self
+                .firewall_rule
+                .iter()
+                .find(|firewall_rule| {
+                    Some(firewall_rule.firewall_group_id.to_string().as_str()) == parent_id
+                        && firewall_rule.id.to_string() == id
+                })
+                .map(|firewall_rule| json!(firewall_rule)),
+            ResourceType::Vpc => self
+                .vpc
+                .iter()
+                .find(|vpc| vpc.id.to_string() == id)
+                .map(|vpc| json!(vpc)),
+            ResourceType::LoadBalancer => self
+                .load_balancer
+                .iter()
+                .find(|load_balancer| load_balancer.id.to_string() == id)
+                .map(|load_balancer| json!(load_balancer)),
+            ResourceType::DnsDomain => self
+                .dns_domain
+                .iter()
+                .find(|dns_domain| dns_domain.id == id)
+                .map(|dns_domain| json!(dns_domain)),
+            ResourceType::DnsRecord => self
+                .dns_record
+                .iter()
+                .find(|dns_record| {
+                    Some(dns_record.domain.as_str()) == parent_id && dns_record.id.to_string() == id
+                })
+                .map(|dns_record| json!(dns_record)),
+            ResourceType::ComputeSnapshot => self
+                .compute_snapshot
+                .iter()
+                .find(|compute_snapshot| compute_snapshot.id.to_string() == id)
+                .map(|compute_snapshot| json!(compute_snapshot)),
+            ResourceType::DatabaseUser => self
+                .database_user
+                .iter()
+                .find(|database_user| {
+                    Some(database_user.database_id.to_string().as_str()) == parent_id
+                        && database_user.id == id
+                })
+                .map(|database_user| json!(database_user)),
+            ResourceType::LogicalDatabase => self
+                .logical_database
+                .iter()
+                .find(|logical_database| {
+                    Some(logical_database.database_id.to_string().as_str()) == parent_id
+                        && logical_database.id == id
+                })
+                .map(|logical_database| json!(logical_database)),
+            ResourceType::ConnectionPool => self
+                .connection_pool
+                .iter()
+                .find(|connection_pool| {
+                    Some(connection_pool.database_id.to_string().as_str()) == parent_id
+                        && connection_pool.id == id
+                })
+                .map(|connection_pool| json!(connection_pool)),
+        }
+    }
+}
+
 pub fn get_diagram_key(project_id: Uuid) -> String {
     format!("project_diagram_{}", project_id)
 }
@@ -0,0 +1,319 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use futures_util::future::join_all;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+use utoipa::ToSchema;
+
+use uuid::Uuid;
+
+use super::{
+    diagrams::{DiagramSnapshot, FirewallRule},
+    enums::ResourceType,
+    resource_id,
+    vultr_command::{delete_resource, parent_field, update_resource},
+    VultrExecutionContext,
+};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::ExecuteVultrListCommand, schemas::firewall::ListFirewallRule,
+    },
+    errors::ServiceError,
+};
+
+// Owned by this server, never returned by Vultr
+const LOCAL_FIELDS: [&str; 3] = ["project_id", "x", "y"];
+
+/// Difference between the stored diagram and the live Vultr account.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct DriftReport {
+    pub resources: Vec<ResourceDrift>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftStatus {
+    InSync,
+    Changed,
+    // Deleted at Vultr outside of this server
+    Missing,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResourceDrift {
+    pub resource_type: ResourceType,
+    pub resource_id: String,
+    // Group of a firewall rule
+    pub parent_id: Option<String>,
+    pub status: DriftStatus,
+    pub changes: Vec<FieldDrift>,
+    // Stored row with the live values applied, None when the resource is missing
+    pub live: Option<Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldDrift {
+    pub field: String,
+    pub stored: Value,
+    pub live: Value,
+}
+
+impl DriftReport {
+    /// Fetch every stored resource from Vultr and compare it field by field.
+    /// Firewall rules stored before they were linked to their group cannot be fetched, so they are skipped.
+    /// The same goes for DNS records without their domain.
+    pub async fn detect(
+        snapshot: &DiagramSnapshot,
+        context: &VultrExecutionContext,
+    ) -> Result<Self, ServiceError> {
+        let resources = snapshot
+            .resources()
+            .into_iter()
+            .filter(|(resource_type, stored)| {
+                parent_field(*resource_type).is_none()
+                    || parent_id(*resource_type, stored).is_some()
+            })
+            .collect();
+        Self::detect_resources(resources, context).await
+    }
+
+    /// Compare the given stored rows only.
+    pub async fn detect_resources(
+        resources: Vec<(ResourceType, Value)>,
+        context: &VultrExecutionContext,
+    ) -> Result<Self, ServiceError> {
+        let firewall_rules = &list_firewall_rules(&resources, context).await?;
+        let checks = resources
+            .into_iter()
+            .map(|(resource_type, stored)| async move {
+                let resource_id = resource_id(&stored)?;
+                let parent_id = parent_id(resource_type, &stored);
+                let live = if resource_type == ResourceType::FirewallRule {
+                    parent_id
+                        .as_ref()
+                        .and_then(|group_id| firewall_rules.get(group_id))
+                        .and_then(|rules| {
+                            rules.iter().find(|rule| rule.id.to_string() == resource_id)
+                        })
+                        .map(|rule| json!(rule))
+                } else {
+                    match context
+                        .current_state(resource_type, &resource_id, parent_id.as_deref())
+                        .await
+                    {
+                        Ok(live) => Some(live),
+                        Err(ServiceError::NotFound) => None,
+                        Err(err) => return Err(err),
+                    }
+                };
+                let mut resource = ResourceDrift::new(resource_type, resource_id, &stored, live);
+                resource.parent_id = parent_id;
+                Ok(resource)
+            });
+        let resources = join_all(checks)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, ServiceError>>()?;
+        Ok(Self { resources })
+    }
+
+    pub fn has_drift(&self) -> bool {
+        self.resources
+            .iter()
+            .any(|resource| resource.status != DriftStatus::InSync)
+    }
+
+    /// Take over the live values: changed rows are updated, missing ones removed.
+    pub async fn apply(
+        &self,
+        context: &VultrExecutionContext,
+        trx: &mut PgConnection,
+    ) -> Result<(), ServiceError> {
+        for resource in self.resources.iter() {
+            match (resource.status, &resource.live) {
+                (DriftStatus::Changed, Some(live)) => {
+                    update_resource(resource.resource_type, live.clone(), trx).await?
+                }
+                (DriftStatus::Missing, _) => {
+                    delete_resource(
+                        resource.resource_type,
+                        &context.project_id,
+                        &resource.resource_id,
+                        resource.parent_id.as_deref(),
+                        trx,
+                    )
+                    .await?
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ResourceDrift {
+    fn new(
+        resource_type: ResourceType,
+        resource_id: String,
+        stored: &Value,
+        live: Option<Value>,
+    ) -> Self {
+        let Some(live) = live else {
+            return Self {
+                resource_type,
+                resource_id,
+                parent_id: None,
+                status: DriftStatus::Missing,
+                changes: Vec::new(),
+                live: None,
+            };
+        };
+
+        let mut merged = stored.clone();
+        let mut changes = Vec::new();
+        if let (Some(merged), Some(live)) = (merged.as_object_mut(), live.as_object()) {
+            for (field, live_value) in live.iter() {
+                // Vultr leaves out some fields, e.g. region, keep the stored value for those
+                if live_value.is_null() || LOCAL_FIELDS.contains(&field.as_str()) {
+                    continue;
+                }
+                let stored_value = merged.get(field).cloned().unwrap_or(Value::Null);
+                if stored_value != *live_value {
+                    changes.push(FieldDrift {
+                        field: field.clone(),
+                        stored: stored_value,
+                        live: live_value.clone(),
+                    });
+                    merged.insert(field.clone(), live_value.clone());
+                }
+            }
+        }
+        Self {
+            resource_type,
+            resource_id,
+            parent_id: None,
+            status: if changes.is_empty() {
+                DriftStatus::InSync
+            } else {
+                DriftStatus::Changed
+            },
+            changes,
+            live: Some(merged),
+        }
+    }
+}
+
+/// Live rules of every group that has a stored rule among `resources`, one list request per group.
+/// A group deleted at Vultr has no rules left.
+async fn list_firewall_rules(
+    resources: &[(ResourceType, Value)],
+    context: &VultrExecutionContext,
+) -> Result<HashMap<String, Vec<FirewallRule>>, ServiceError> {
+    let group_ids: HashSet<String> = resources
+        .iter()
+        .filter(|(resource_type, _)| *resource_type == ResourceType::FirewallRule)
+        .filter_map(|(resource_type, stored)| parent_id(*resource_type, stored))
+        .collect();
+    let lists = group_ids.into_iter().map(|group_id| async move {
+        let Ok(firewall_group_id) = Uuid::from_str(&group_id) else {
+            return Ok((group_id, Vec::new()));
+        };
+        match ListFirewallRule::new(firewall_group_id)
+            .execute(&context.vultr_client)
+            .await
+        {
+            Ok(rules) => Ok((group_id, rules)),
+            Err(ServiceError::NotFound) => Ok((group_id, Vec::new())),
+            Err(err) => Err(err),
+        }
+    });
+    join_all(lists).await.into_iter().collect()
+}
+
+/// Group of a stored firewall rule or domain of a stored DNS record.
+/// Rules stored before they were linked to their group have none.
+fn parent_id(resource_type: ResourceType, stored: &Value) -> Option<String> {
+    stored[parent_field(resource_type)?]
+        .as_str()
+        .filter(|parent_id| !parent_id.is_empty() && *parent_id != Uuid::nil().to_string())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_resource_drift_changed() {
+        // GIVEN
+        let stored = json!({
+            "project_id": "project",
+            "x": 10,
+            "y": 20,
+            "region": "ewr",
+            "id": "block-1",
+            "size_gb": 100,
+            "label": "data"
+        });
+        let live = json!({
+            "project_id": null,
+            "x": 0,
+            "y": 0,
+            "region": null,
+            "id": "block-1",
+            "size_gb": 200,
+            "label": "data"
+        });
+
+        // WHEN
+        let drift = ResourceDrift::new(
+            ResourceType::BlockStorage,
+            "block-1".to_string(),
+            &stored,
+            Some(live),
+        );
+
+        // THEN
+        assert_eq!(drift.status, DriftStatus::Changed);
+        assert_eq!(drift.changes.len(), 1);
+        assert_eq!(drift.changes[0].field, "size_gb");
+        assert_eq!(drift.changes[0].stored, 100);
+        assert_eq!(drift.changes[0].live, 200);
+        let merged = drift.live.unwrap();
+        assert_eq!(merged["size_gb"], 200);
+        assert_eq!(merged["region"], "ewr");
+        assert_eq!(merged["x"], 10);
+    }
+
+    #[test]
+    fn test_resource_drift_in_sync_and_missing() {
+        // GIVEN
+        let stored = json!({ "id": "group-1", "description": "web", "x": 1, "y": 2 });
+
+        // WHEN
+        let in_sync = ResourceDrift::new(
+            ResourceType::FirewallGroup,
+            "group-1".to_string(),
+            &stored,
+            Some(json!({ "id": "group-1", "description": "web", "x": 0, "y": 0 })),
+        );
+        let missing = ResourceDrift::new(
+            ResourceType::FirewallGroup,
+            "group-1".to_string(),
+            &stored,
+            None,
+        );
+
+        // THEN
+        assert_eq!(in_sync.status, DriftStatus::InSync);
+        assert!(in_sync.changes.is_empty());
+        assert_eq!(missing.status, DriftStatus::Missing);
+        assert!(missing.live.is_none());
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Type, Clone)]
+#[derive(Serialize, Deserialize, Type, Clone, ToSchema)]
 #[sqlx(type_name = "ip_type", rename_all = "snake_case")]
 #[serde(rename_all = "lowercase")]
 pub enum IpType {
@@ -10,7 +10,7 @@ pub enum IpType {
     V6,
 }
 
-#[derive(Serialize, Deserialize, Type, Clone)]
+#[derive(Serialize, Deserialize, Type, Clone, ToSchema)]
 #[sqlx(type_name = "protocol", rename_all = "snake_case")]
 #[serde(rename_all = "lowercase")]
 pub enum Protocol {
@@ -22,7 +22,7 @@ pub enum Protocol {
     Ah,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone, Type)]
+#[derive(Serialize, Deserialize, Default, Clone, Type, ToSchema)]
 #[sqlx(type_name = "auto_backups", rename_all = "snake_case")]
 #[serde(rename_all = "lowercase")]
 pub enum BackupStatus {
@@ -31,7 +31,14 @@ pub enum BackupStatus {
     Disabled,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Type)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupScheduleType {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Type, ToSchema)]
 #[sqlx(type_name = "database_engine", rename_all = "snake_case")]
 #[serde(rename_all = "lowercase")]
 pub enum DatabaseEngine {
@@ -39,7 +46,7 @@ pub enum DatabaseEngine {
     Pg,
 }
 
-#[derive(Debug, Serialize, Deserialize, Type, ToSchema, Clone)]
+#[derive(Debug, Serialize, Deserialize, Type, ToSchema, Clone, Copy, PartialEq, Eq, Hash)]
 #[sqlx(type_name = "resource_type", rename_all = "snake_case")]
 pub enum ResourceType {
     BlockStorage,
@@ -48,4 +55,12 @@ pub enum ResourceType {
     ObjectStorage,
     FirewallGroup,
     FirewallRule,
+    Vpc,
+    LoadBalancer,
+    DnsDomain,
+    DnsRecord,
+    ComputeSnapshot,
+    DatabaseUser,
+    LogicalDatabase,
+    ConnectionPool,
 }
@@ -0,0 +1,429 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{
+    commands::ResourceResponse,
+    diagrams::{DiagramSnapshot, ObjectPosition},
+    enums::ResourceType,
+    resource_id,
+    vultr_command::insert_resource,
+};
+use crate::{
+    adapter::request_dispensor::vultr::{
+        interfaces::ExecuteVultrListCommand,
+        schemas::{
+            block_storage::ListBlockStorage, dns::ListDnsDomain, firewall::ListFirewallGroup,
+            instance::ListCompute, load_balancer::ListLoadBalancer,
+            managed_database::ListManagedDatabase, object_storage::ListObjectStorage,
+            snapshot::ListComputeSnapshot, vpc::ListVpc,
+        },
+        VultrClient,
+    },
+    errors::ServiceError,
+};
+
+// Rows on the canvas, one resource type per row
+const LAYOUT_ORDER: [ResourceType; 9] = [
+    ResourceType::Vpc,
+    ResourceType::DnsDomain,
+    ResourceType::FirewallGroup,
+    ResourceType::LoadBalancer,
+    ResourceType::Compute,
+    ResourceType::ComputeSnapshot,
+    ResourceType::BlockStorage,
+    ResourceType::ManagedDatabase,
+    ResourceType::ObjectStorage,
+];
+const COLUMNS: usize = 8;
+const COLUMN_GAP: i64 = 200;
+const ROW_GAP: i64 = 150;
+
+/// Resource found at Vultr for the project's API key.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportCandidate {
+    pub resource_type: ResourceType,
+    pub resource_id: String,
+    // Already stored in the project's diagram
+    pub adopted: bool,
+    pub attributes: Value,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportSelection {
+    pub resource_type: ResourceType,
+    pub id: String,
+}
+
+/// Adopt existing Vultr resources into the project.
+/// Firewall rules and DNS records are not listed, Vultr serves them per firewall group and domain only.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportResources {
+    pub project_id: Uuid,
+    pub resources: Vec<ImportSelection>,
+}
+
+/// Every resource of the Vultr account, paged through the list APIs.
+#[derive(Default)]
+pub struct LiveInventory {
+    pub resources: Vec<(ResourceType, Value)>,
+}
+
+impl LiveInventory {
+    pub async fn fetch(vultr_client: &VultrClient) -> Result<Self, ServiceError> {
+        let (
+            compute,
+            block_storage,
+            firewall_group,
+            managed_database,
+            object_storage,
+            vpc,
+            load_balancer,
+            dns_domain,
+            compute_snapshot,
+        ) = tokio::try_join!(
+            ListCompute.execute(vultr_client),
+            ListBlockStorage.execute(vultr_client),
+            ListFirewallGroup.execute(vultr_client),
+            ListManagedDatabase.execute(vultr_client),
+            ListObjectStorage.execute(vultr_client),
+            ListVpc.execute(vultr_client),
+            ListLoadBalancer.execute(vultr_client),
+            ListDnsDomain.execute(vultr_client),
+            ListComputeSnapshot.execute(vultr_client),
+        )?;
+        let mut resources = Vec::new();
+        resources.extend(
+            compute
+                .iter()
+                .map(|res| (ResourceType::Compute, json!(res))),
+        );
+        resources.extend(
+            block_storage
+                .iter()
+                .map(|res| (ResourceType::BlockStorage, json!(res))),
+        );
+        resources.extend(
+            firewall_group
+                .iter()
+                .map(|res| (ResourceType::FirewallGroup, json!(res))),
+        );
+        resources.extend(
+            managed_database
+                .iter()
+                .map(|res| (ResourceType::ManagedDatabase, json!(res))),
+        );
+        resources.extend(
+            object_storage
+                .iter()
+                .map(|res| (ResourceType::ObjectStorage, json!(res))),
+        );
+        resources.extend(vpc.iter().map(|res| (ResourceType::Vpc, json!(res))));
+        resources.extend(
+            load_balancer
+                .iter()
+                .map(|res| (ResourceType::LoadBalancer, json!(res))),
+        );
+        resources.extend(
+            dns_domain
+                .iter()
+                .map(|res| (ResourceType::DnsDomain, json!(res))),
+        );
+        resources.extend(
+            compute_snapshot
+                .iter()
+                .map(|res| (ResourceType::ComputeSnapshot, json!(res))),
+        );
+        Ok(Self { resources })
+    }
+
+    pub fn candidates(
+        &self,
+        snapshot: &DiagramSnapshot,
+    ) -> Result<Vec<ImportCandidate>, ServiceError> {
+        self.resources
+            .iter()
+            .map(|(resource_type, res)| {
+                let resource_id = resource_id(res)?;
+                Ok(ImportCandidate {
+                    resource_type: *resource_type,
+                    adopted: snapshot.find(resource_type, &resource_id, None).is_some(),
+                    resource_id,
+                    attributes: res.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn find(&self, resource_type: ResourceType, id: &str) -> Option<&Value> {
+        self.resources
+            .iter()
+            .find(|(live_type, res)| {
+                *live_type == resource_type && resource_id(res).is_ok_and(|live_id| live_id == id)
+            })
+            .map(|(_, res)| res)
+    }
+}
+
+impl ImportResources {
+    /// Pick the selected resources out of the inventory and place them below the current diagram.
+    pub fn adopt(
+        &self,
+        inventory: &LiveInventory,
+        snapshot: &DiagramSnapshot,
+    ) -> Result<Vec<ResourceResponse>, ServiceError> {
+        if let Some(selection) = self
+            .resources
+            .iter()
+            .find(|selection| !LAYOUT_ORDER.contains(&selection.resource_type))
+        {
+            return Err(ServiceError::InvalidCommand(format!(
+                "{:?} cannot be imported",
+                selection.resource_type
+            )));
+        }
+        let mut layout = AutoLayout::below(snapshot);
+        let mut adopted = Vec::new();
+        let mut selected = HashSet::new();
+        for resource_type in LAYOUT_ORDER {
+            for selection in self
+                .resources
+                .iter()
+                .filter(|selection| selection.resource_type == resource_type)
+            {
+                let Some(res) = inventory.find(resource_type, &selection.id) else {
+                    return Err(ServiceError::InvalidCommand(format!(
+                        "{:?} `{}` was not found at Vultr",
+                        resource_type, selection.id
+                    )));
+                };
+                if snapshot.find(&resource_type, &selection.id, None).is_some() {
+                    return Err(ServiceError::InvalidCommand(format!(
+                        "{:?} `{}` is already in the project",
+                        resource_type, selection.id
+                    )));
+                }
+                if !selected.insert((resource_type, selection.id.as_str())) {
+                    return Err(ServiceError::InvalidCommand(format!(
+                        "{:?} `{}` is selected more than once",
+                        resource_type, selection.id
+                    )));
+                }
+                let mut attributes = res.clone();
+                let position = layout.place();
+                if let Some(fields) = attributes.as_object_mut() {
+                    fields.insert("project_id".to_string(), json!(self.project_id));
+                    fields.insert("x".to_string(), json!(position.x));
+                    fields.insert("y".to_string(), json!(position.y));
+                }
+                adopted.push(ResourceResponse {
+                    temp_id: "".to_string(),
+                    resource_type,
+                    position,
+                    attributes,
+                });
+            }
+            layout.next_row();
+        }
+        Ok(adopted)
+    }
+
+    pub async fn execute(
+        &self,
+        inventory: &LiveInventory,
+        snapshot: &DiagramSnapshot,
+        trx: &mut PgConnection,
+    ) -> Result<Vec<ResourceResponse>, ServiceError> {
+        let adopted = self.adopt(inventory, snapshot)?;
+        for resource in adopted.iter() {
+            insert_resource(resource.resource_type, resource.attributes.clone(), trx).await?;
+        }
+        Ok(adopted)
+    }
+}
+
+/// Grid below everything already on the canvas, wrapping after `COLUMNS` resources.
+struct AutoLayout {
+    y: i64,
+    column: usize,
+}
+
+impl AutoLayout {
+    fn below(snapshot: &DiagramSnapshot) -> Self {
+        let y = snapshot
+            .resources()
+            .iter()
+            .filter_map(|(_, res)| res["y"].as_i64())
+            .max()
+            .map_or(0, |bottom| bottom + ROW_GAP);
+        Self { y, column: 0 }
+    }
+
+    fn place(&mut self) -> ObjectPosition {
+        if self.column == COLUMNS {
+            self.y += ROW_GAP;
+            self.column = 0;
+        }
+        let position = ObjectPosition {
+            x: self.column as i64 * COLUMN_GAP,
+            y: self.y,
+        };
+        self.column += 1;
+        position
+    }
+
+    // Types without any adopted resource leave no empty row behind
+    fn next_row(&mut self) {
+        if self.column > 0 {
+            self.y += ROW_GAP;
+            self.column = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::project::diagrams::FirewallGroup;
+
+    fn firewall_group(id: Uuid, y: i64) -> FirewallGroup {
+        FirewallGroup {
+            project_id: Uuid::nil(),
+            y,
+            x: 0,
+            id,
+            description: "web".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_adopt_lays_out_selection_below_diagram() {
+        // GIVEN
+        let stored_id = Uuid::new_v4();
+        let snapshot = DiagramSnapshot {
+            firewall_group: vec![firewall_group(stored_id, 300)],
+            ..Default::default()
+        };
+        let group_ids: Vec<Uuid> = (0..COLUMNS + 1).map(|_| Uuid::new_v4()).collect();
+        let compute_id = Uuid::new_v4();
+        let mut inventory = LiveInventory {
+            resources: group_ids
+                .iter()
+                .map(|id| (ResourceType::FirewallGroup, json!(firewall_group(*id, 0))))
+                .collect(),
+        };
+        inventory.resources.push((
+            ResourceType::Compute,
+            json!({ "id": compute_id, "label": "web-1" }),
+        ));
+        let mut resources: Vec<ImportSelection> = group_ids
+            .iter()
+            .map(|id| ImportSelection {
+                resource_type: ResourceType::FirewallGroup,
+                id: id.to_string(),
+            })
+            .collect();
+        resources.insert(
+            0,
+            ImportSelection {
+                resource_type: ResourceType::Compute,
+                id: compute_id.to_string(),
+            },
+        );
+        let project_id = Uuid::new_v4();
+        let cmd = ImportResources {
+            project_id,
+            resources,
+        };
+
+        // WHEN
+        let adopted = cmd.adopt(&inventory, &snapshot).unwrap();
+
+        // THEN
+        let positions: Vec<(i64, i64)> = adopted
+            .iter()
+            .map(|resource| (resource.position.x, resource.position.y))
+            .collect();
+        assert_eq!(positions.len(), COLUMNS + 2);
+        assert_eq!(positions[0], (0, 450));
+        assert_eq!(
+            positions[COLUMNS - 1],
+            ((COLUMNS as i64 - 1) * COLUMN_GAP, 450)
+        );
+        assert_eq!(positions[COLUMNS], (0, 600));
+        // The compute row starts below the wrapped firewall groups
+        assert_eq!(adopted[COLUMNS + 1].resource_type, ResourceType::Compute);
+        assert_eq!(positions[COLUMNS + 1], (0, 750));
+        assert_eq!(adopted[0].attributes["project_id"], json!(project_id));
+        assert_eq!(adopted[0].attributes["y"], 450);
+    }
+
+    #[test]
+    fn test_adopt_rejects_unknown_and_adopted_resources() {
+        // GIVEN
+        let stored_id = Uuid::new_v4();
+        let snapshot = DiagramSnapshot {
+            firewall_group: vec![firewall_group(stored_id, 0)],
+            ..Default::default()
+        };
+        let inventory = LiveInventory {
+            resources: vec![(
+                ResourceType::FirewallGroup,
+                json!(firewall_group(stored_id, 0)),
+            )],
+        };
+        let select = |id: String| ImportResources {
+            project_id: Uuid::new_v4(),
+            resources: vec![ImportSelection {
+                resource_type: ResourceType::FirewallGroup,
+                id,
+            }],
+        };
+
+        // WHEN
+        let unknown = select(Uuid::new_v4().to_string()).adopt(&inventory, &snapshot);
+        let adopted = select(stored_id.to_string()).adopt(&inventory, &snapshot);
+
+        // THEN
+        assert!(
+            matches!(unknown, Err(ServiceError::InvalidCommand(msg)) if msg.contains("not found"))
+        );
+        assert!(
+            matches!(adopted, Err(ServiceError::InvalidCommand(msg)) if msg.contains("already"))
+        );
+        let candidates = inventory.candidates(&snapshot).unwrap();
+        assert!(candidates[0].adopted);
+    }
+
+    #[test]
+    fn test_adopt_rejects_duplicate_selection() {
+        // GIVEN
+        let group_id = Uuid::new_v4();
+        let inventory = LiveInventory {
+            resources: vec![(
+                ResourceType::FirewallGroup,
+                json!(firewall_group(group_id, 0)),
+            )],
+        };
+        let selection = || ImportSelection {
+            resource_type: ResourceType::FirewallGroup,
+            id: group_id.to_string(),
+        };
+        let cmd = ImportResources {
+            project_id: Uuid::new_v4(),
+            resources: vec![selection(), selection()],
+        };
+
+        // WHEN
+        let result = cmd.adopt(&inventory, &DiagramSnapshot::default());
+
+        // THEN
+        assert!(
+            matches!(result, Err(ServiceError::InvalidCommand(msg)) if msg.contains("more than once"))
+        );
+    }
+}
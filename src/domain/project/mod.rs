@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     fmt::{self, Display},
+    sync::Arc,
 };
 
 use chrono::{DateTime, Utc};
@@ -9,10 +10,24 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{adapter::request_dispensor::vultr::VultrClient, errors::ServiceError};
+use compensation::CompletedStep;
+use deploy_job::DeployJob;
+use diagrams::DiagramSnapshot;
+use serde_json::Value;
 
 pub mod commands;
+pub mod compensation;
+pub mod dependency;
+pub mod deploy_job;
 pub mod diagrams;
+pub mod drift;
 pub mod enums;
+pub mod import;
+pub mod object_storage;
+pub mod plan;
+pub mod provisioning;
+pub mod readiness;
+pub mod vultr_command;
 
 #[allow(unused)]
 pub struct ProjectAggregate {
@@ -97,20 +112,27 @@ impl VultApiKeyEntity {
 }
 
 pub struct VultrExecutionContext {
-    pub vultr_client: &'static VultrClient,
+    pub vultr_client: Arc<VultrClient>,
     pub project_id: Uuid,
     pub resource_map: HashMap<String, String>,
+    // Resources stored before the deploy started, updates and deletes of them name them by id
+    pub stored: DiagramSnapshot,
+    pub completed_steps: Vec<CompletedStep>,
+    pub deploy_job: Option<DeployJob>,
 }
 
 impl VultrExecutionContext {
-    pub fn new(vultr_client: &'static VultrClient, project_id: Uuid) -> Self {
+    pub fn new(vultr_client: Arc<VultrClient>, project_id: Uuid) -> Self {
         Self {
             vultr_client,
             project_id,
             resource_map: HashMap::new(),
+            stored: DiagramSnapshot::default(),
+            completed_steps: Vec::new(),
+            deploy_job: None,
         }
     }
-    pub fn get_id_with_temp_id(&mut self, temp_id: &String) -> Result<String, ServiceError> {
+    pub fn get_id_with_temp_id(&self, temp_id: &String) -> Result<String, ServiceError> {
         self.resource_map
             .get(temp_id)
             .ok_or(ServiceError::NotFound)
@@ -118,6 +140,15 @@ impl VultrExecutionContext {
     }
 }
 
+/// Vultr ids are strings for most resources and numbers for firewall rules.
+pub fn resource_id(res: &Value) -> Result<String, ServiceError> {
+    match res.get("id") {
+        Some(Value::String(id)) => Ok(id.clone()),
+        Some(Value::Number(id)) => Ok(id.to_string()),
+        _ => Err(ServiceError::NotFound),
+    }
+}
+
 // pub struct VultrCommandManager<'a> {
 //     pub(crate) command_list: Vec<VultrCommand>,
 //     pub(crate) execution_context: VultrExecutionContext,
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use openssl::{
+    rand::rand_bytes,
+    sha::sha256,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{config::get_config, errors::ServiceError};
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// S3 credentials of an object storage subscription, as Vultr hands them out.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct S3Credentials {
+    pub s3_hostname: String, // e.g. "ewr1.vultrobjects.com"
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+}
+
+/// Credentials kept for an object storage, the secret key is only stored encrypted.
+#[derive(Debug, Clone)]
+pub struct ObjectStorageCredential {
+    pub project_id: Uuid,
+    pub object_storage_id: Uuid,
+    pub s3_hostname: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: Vec<u8>, // Nonce, sealed key and tag
+    pub update_dt: DateTime<Utc>,
+}
+
+impl ObjectStorageCredential {
+    pub fn seal(
+        project_id: Uuid,
+        object_storage_id: Uuid,
+        credentials: S3Credentials,
+    ) -> Result<Self, ServiceError> {
+        Ok(Self {
+            project_id,
+            object_storage_id,
+            s3_secret_key: encrypt(
+                credentials.s3_secret_key.as_bytes(),
+                &associated_data(project_id, object_storage_id),
+            )?,
+            s3_hostname: credentials.s3_hostname,
+            s3_access_key: credentials.s3_access_key,
+            update_dt: Utc::now(),
+        })
+    }
+
+    pub fn open(&self) -> Result<S3Credentials, ServiceError> {
+        Ok(S3Credentials {
+            s3_hostname: self.s3_hostname.clone(),
+            s3_access_key: self.s3_access_key.clone(),
+            s3_secret_key: String::from_utf8(decrypt(
+                &self.s3_secret_key,
+                &associated_data(self.project_id, self.object_storage_id),
+            )?)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Bucket {
+    pub name: String,
+    pub creation_date: String,
+}
+
+/// Names are shared by every tenant of the cluster, so S3 is strict about them.
+pub fn verify_bucket_name(name: &str) -> Result<(), ServiceError> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.');
+    let valid_ends = [name.chars().next(), name.chars().last()]
+        .into_iter()
+        .all(|c| c.is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+    if !(3..=63).contains(&name.len()) || !valid_chars || !valid_ends || name.contains("..") {
+        return Err(ServiceError::InvalidCommand(format!(
+            "`{}` is not a valid bucket name",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn encryption_key() -> [u8; 32] {
+    sha256(get_config().credential_encryption_key.as_bytes())
+}
+
+/// Binds a sealed key to its object storage, so it can't be opened as another one's.
+fn associated_data(project_id: Uuid, object_storage_id: Uuid) -> [u8; 32] {
+    let mut aad = [0; 32];
+    aad[..16].copy_from_slice(project_id.as_bytes());
+    aad[16..].copy_from_slice(object_storage_id.as_bytes());
+    aad
+}
+
+fn encrypt(plain: &[u8], aad: &[u8]) -> Result<Vec<u8>, ServiceError> {
+    let mut nonce = [0; NONCE_LEN];
+    rand_bytes(&mut nonce)?;
+    let mut tag = [0; TAG_LEN];
+    let sealed = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &encryption_key(),
+        Some(&nonce),
+        aad,
+        plain,
+        &mut tag,
+    )?;
+    Ok([nonce.as_slice(), &sealed, &tag].concat())
+}
+
+fn decrypt(sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, ServiceError> {
+    if sealed.len() < NONCE_LEN + TAG_LEN {
+        return Err(ServiceError::ParseError);
+    }
+    let (nonce, rest) = sealed.split_at(NONCE_LEN);
+    let (sealed, tag) = rest.split_at(rest.len() - TAG_LEN);
+    Ok(decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &encryption_key(),
+        Some(nonce),
+        aad,
+        sealed,
+        tag,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open_credentials() {
+        // GIVEN
+        let credentials = S3Credentials {
+            s3_hostname: "ewr1.vultrobjects.com".to_string(),
+            s3_access_key: "access".to_string(),
+            s3_secret_key: "secret".to_string(),
+        };
+
+        // WHEN
+        let sealed =
+            ObjectStorageCredential::seal(Uuid::new_v4(), Uuid::new_v4(), credentials).unwrap();
+        let mut tampered = sealed.clone();
+        tampered.s3_secret_key[NONCE_LEN] ^= 1;
+
+        // THEN
+        assert!(!sealed
+            .s3_secret_key
+            .windows("secret".len())
+            .any(|window| window == b"secret"));
+        assert_eq!(sealed.open().unwrap().s3_secret_key, "secret");
+        assert!(tampered.open().is_err());
+    }
+
+    #[test]
+    fn test_open_credentials_of_another_object_storage() {
+        // GIVEN
+        let credentials = S3Credentials {
+            s3_hostname: "ewr1.vultrobjects.com".to_string(),
+            s3_access_key: "access".to_string(),
+            s3_secret_key: "secret".to_string(),
+        };
+        let sealed =
+            ObjectStorageCredential::seal(Uuid::new_v4(), Uuid::new_v4(), credentials).unwrap();
+
+        // WHEN
+        let mut moved_project = sealed.clone();
+        moved_project.project_id = Uuid::new_v4();
+        let mut moved_storage = sealed.clone();
+        moved_storage.object_storage_id = Uuid::new_v4();
+
+        // THEN
+        assert!(moved_project.open().is_err());
+        assert!(moved_storage.open().is_err());
+    }
+
+    #[test]
+    fn test_verify_bucket_name() {
+        assert!(verify_bucket_name("assets-2025.backup").is_ok());
+        for name in ["ab", "Assets", "-assets", "assets-", "as..sets", "as_sets"] {
+            assert!(matches!(
+                verify_bucket_name(name),
+                Err(ServiceError::InvalidCommand(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::{
+    commands::CommandRequest,
+    dependency::DependencyGraph,
+    diagrams::DiagramSnapshot,
+    enums::ResourceType,
+    vultr_command::{
+        created_types, placeholder_ids, reference_fields, resolve_target, value_to_id, CommandKind,
+        Target, VultrCommand,
+    },
+};
+use crate::errors::ServiceError;
+
+/// Result of checking a command list against the stored diagram without calling Vultr.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct DeployPlan {
+    pub create: Vec<PlannedChange>,
+    pub update: Vec<PlannedChange>,
+    pub delete: Vec<PlannedChange>,
+    pub unresolved_references: Vec<UnresolvedReference>,
+    pub validation_errors: Vec<PlanValidationError>,
+    // Command indexes grouped in the waves they would run in
+    pub execution_order: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlannedChange {
+    pub index: usize,
+    pub command_name: String,
+    pub temp_id: String,
+    pub resource_type: ResourceType,
+    // None when the target is created earlier in the same command list
+    pub resource_id: Option<String>,
+    pub before: Option<Value>,
+    pub after: Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnresolvedReference {
+    pub index: usize,
+    pub command_name: String,
+    pub temp_id: String,
+    pub field: String,
+    pub reference: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlanValidationError {
+    pub index: usize,
+    pub command_name: String,
+    pub temp_id: String,
+    pub message: String,
+}
+
+impl DeployPlan {
+    pub fn new(command_list: &[CommandRequest], snapshot: &DiagramSnapshot) -> Self {
+        let mut plan = DeployPlan::default();
+        // * temp_id -> resource type of resources created earlier in the list
+        let mut pending: HashMap<String, ResourceType> = HashMap::new();
+        // * temp_id -> stand-in id, what the deploy finds in its resource map at that point
+        let mut created: HashMap<String, String> = HashMap::new();
+        let mut deleted: HashSet<(ResourceType, String)> = HashSet::new();
+
+        let placeholders = placeholder_ids(command_list);
+        let created_types = created_types(command_list);
+
+        for (index, request) in command_list.iter().enumerate() {
+            if let Err(err) = VultrCommand::resolve(request, &placeholders, &created_types) {
+                let message = match err {
+                    ServiceError::InvalidCommand(message) => message,
+                    err => format!("{:?}", err),
+                };
+                plan.validation_errors.push(PlanValidationError {
+                    index,
+                    command_name: request.command_name.clone(),
+                    temp_id: request.temp_id.clone(),
+                    message,
+                });
+            }
+            // Commands with invalid data are still planned so later references resolve
+            let Some((kind, resource_type)) = VultrCommand::describe(&request.command_name) else {
+                continue;
+            };
+            for (field, referenced_type) in reference_fields(&request.command_name) {
+                let Some(reference) = value_to_id(&request.data[*field]) else {
+                    continue;
+                };
+                if !is_resolved(referenced_type, &reference, &pending, &deleted, snapshot) {
+                    plan.unresolved_references.push(UnresolvedReference {
+                        index,
+                        command_name: request.command_name.clone(),
+                        temp_id: request.temp_id.clone(),
+                        field: field.to_string(),
+                        reference,
+                    });
+                }
+            }
+
+            if kind == CommandKind::Create {
+                if pending
+                    .insert(request.temp_id.clone(), resource_type)
+                    .is_some()
+                {
+                    plan.validation_errors.push(PlanValidationError {
+                        index,
+                        command_name: request.command_name.clone(),
+                        temp_id: request.temp_id.clone(),
+                        message: format!("temp_id `{}` is created twice", request.temp_id),
+                    });
+                }
+                if let Some(id) = placeholders.get(&request.temp_id) {
+                    created.insert(request.temp_id.clone(), id.clone());
+                }
+                deleted.remove(&(resource_type, request.temp_id.clone()));
+                plan.create.push(PlannedChange {
+                    index,
+                    command_name: request.command_name.clone(),
+                    temp_id: request.temp_id.clone(),
+                    resource_type,
+                    resource_id: None,
+                    before: None,
+                    after: request.data.clone(),
+                });
+                continue;
+            }
+
+            let target = resolve_target(
+                &request.command_name,
+                resource_type,
+                &request.temp_id,
+                &request.data,
+                &created,
+                snapshot,
+            )
+            .filter(|target| match target {
+                Target::Created(_) => true,
+                Target::Stored(id, _) => !deleted.contains(&(resource_type, id.clone())),
+            });
+            let (resource_id, before) = match target {
+                Some(Target::Created(_)) => (None, None),
+                Some(Target::Stored(id, before)) => (Some(id), Some(before)),
+                None => {
+                    plan.unresolved_references.push(UnresolvedReference {
+                        index,
+                        command_name: request.command_name.clone(),
+                        temp_id: request.temp_id.clone(),
+                        field: "temp_id".to_string(),
+                        reference: request.temp_id.clone(),
+                    });
+                    continue;
+                }
+            };
+            let change = PlannedChange {
+                index,
+                command_name: request.command_name.clone(),
+                temp_id: request.temp_id.clone(),
+                resource_type,
+                resource_id: resource_id.clone(),
+                before,
+                after: request.data.clone(),
+            };
+            if kind == CommandKind::Update {
+                plan.update.push(change);
+            } else {
+                pending.remove(&request.temp_id);
+                created.remove(&request.temp_id);
+                deleted.insert((resource_type, request.temp_id.clone()));
+                if let Some(resource_id) = resource_id {
+                    deleted.insert((resource_type, resource_id));
+                }
+                plan.delete.push(change);
+            }
+        }
+
+        match DependencyGraph::new(command_list) {
+            Ok(graph) => plan.execution_order = graph.waves().to_vec(),
+            Err(err) => {
+                let request = &command_list[err.index];
+                plan.validation_errors.push(PlanValidationError {
+                    index: err.index,
+                    command_name: request.command_name.clone(),
+                    temp_id: request.temp_id.clone(),
+                    message: err.message,
+                });
+            }
+        }
+        plan
+    }
+}
+
+/// Whether a payload field names a resource created earlier in the list or stored. Payload fields
+/// only reference resources without a parent.
+fn is_resolved(
+    resource_type: &ResourceType,
+    reference: &str,
+    pending: &HashMap<String, ResourceType>,
+    deleted: &HashSet<(ResourceType, String)>,
+    snapshot: &DiagramSnapshot,
+) -> bool {
+    !deleted.contains(&(*resource_type, reference.to_string()))
+        && (pending.get(reference) == Some(resource_type)
+            || snapshot.find(resource_type, reference, None).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::project::{
+        diagrams::{FirewallGroup, FirewallRule, ObjectPosition},
+        enums::{IpType, Protocol},
+    };
+
+    fn command(command_name: &str, temp_id: &str, data: Value) -> CommandRequest {
+        CommandRequest {
+            command_name: command_name.to_string(),
+            temp_id: temp_id.to_string(),
+            position: ObjectPosition { x: 0, y: 0 },
+            data,
+        }
+    }
+
+    fn snapshot_with_firewall_group(id: Uuid) -> DiagramSnapshot {
+        DiagramSnapshot {
+            firewall_group: vec![FirewallGroup {
+                project_id: Uuid::new_v4(),
+                y: 0,
+                x: 0,
+                id,
+                description: "stored group".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_create_then_update() {
+        // GIVEN
+        let command_list = vec![
+            command(
+                "CreateFirewallGroup",
+                "firewall-1",
+                json!({ "description": "web" }),
+            ),
+            command(
+                "UpdateFirewallGroup",
+                "firewall-1",
+                json!({ "description": "web and ssh" }),
+            ),
+        ];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &DiagramSnapshot::default());
+
+        // THEN
+        assert_eq!(plan.create.len(), 1);
+        assert_eq!(plan.update.len(), 1);
+        assert!(plan.update[0].resource_id.is_none());
+        assert!(plan.unresolved_references.is_empty());
+        assert!(plan.validation_errors.is_empty());
+    }
+
+    #[test]
+    fn test_plan_update_stored_resource() {
+        // GIVEN
+        let id = Uuid::new_v4();
+        let snapshot = snapshot_with_firewall_group(id);
+        let command_list = vec![command(
+            "UpdateFirewallGroup",
+            "firewall-1",
+            json!({ "id": id.to_string(), "description": "updated" }),
+        )];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &snapshot);
+
+        // THEN
+        assert_eq!(plan.update.len(), 1);
+        assert_eq!(plan.update[0].resource_id, Some(id.to_string()));
+        assert_eq!(
+            plan.update[0].before.as_ref().unwrap()["description"],
+            "stored group"
+        );
+        assert_eq!(plan.update[0].after["description"], "updated");
+    }
+
+    #[test]
+    fn test_plan_update_stored_firewall_rule_of_its_group() {
+        // GIVEN
+        // Rule ids are only unique within a group, both groups have a rule 1
+        let group_ids = [Uuid::new_v4(), Uuid::new_v4()];
+        let snapshot = DiagramSnapshot {
+            firewall_group: group_ids
+                .iter()
+                .map(|id| FirewallGroup {
+                    project_id: Uuid::new_v4(),
+                    y: 0,
+                    x: 0,
+                    id: *id,
+                    description: "stored group".to_string(),
+                })
+                .collect(),
+            firewall_rule: group_ids
+                .iter()
+                .map(|firewall_group_id| FirewallRule {
+                    project_id: Uuid::new_v4(),
+                    y: 0,
+                    x: 0,
+                    firewall_group_id: *firewall_group_id,
+                    id: 1,
+                    action: "accept".to_string(),
+                    port: "443".to_string(),
+                    ip_type: IpType::V4,
+                    protocol: Protocol::Tcp,
+                    subnet: "0.0.0.0".to_string(),
+                    subnet_size: 0,
+                    notes: firewall_group_id.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let command_list = vec![command(
+            "UpdateFirewallRule",
+            "rule-1",
+            json!({
+                "firewall_group_id": group_ids[1].to_string(),
+                "firewall_rule_id": 1,
+                "ip_type": "v4",
+                "protocol": "tcp",
+                "port": "22",
+                "subnet": "0.0.0.0",
+                "subnet_size": 0,
+                "notes": "ssh"
+            }),
+        )];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &snapshot);
+
+        // THEN
+        assert_eq!(plan.update.len(), 1);
+        assert_eq!(
+            plan.update[0].before.as_ref().unwrap()["notes"],
+            group_ids[1].to_string()
+        );
+    }
+
+    #[test]
+    fn test_plan_update_without_stored_target_is_unresolved() {
+        // GIVEN
+        // The deploy only looks a stored resource up by the id in the payload
+        let id = Uuid::new_v4();
+        let snapshot = snapshot_with_firewall_group(id);
+        let command_list = vec![command(
+            "UpdateFirewallGroup",
+            &id.to_string(),
+            json!({ "description": "updated" }),
+        )];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &snapshot);
+
+        // THEN
+        assert!(plan.update.is_empty());
+        assert_eq!(plan.unresolved_references.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_unresolved_references() {
+        // GIVEN
+        let command_list = vec![
+            command(
+                "DeleteCompute",
+                "compute-1",
+                json!({ "id": Uuid::new_v4().to_string() }),
+            ),
+            command(
+                "CreateFirewallRule",
+                "rule-1",
+                json!({
+                    "firewall_group_id": Uuid::new_v4().to_string(),
+                    "ip_type": "v4",
+                    "protocol": "tcp",
+                    "port": "80",
+                    "subnet": "0.0.0.0",
+                    "subnet_size": 0,
+                    "notes": "http"
+                }),
+            ),
+        ];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &DiagramSnapshot::default());
+
+        // THEN
+        assert!(plan.delete.is_empty());
+        assert_eq!(plan.create.len(), 1);
+        assert_eq!(plan.unresolved_references.len(), 2);
+        assert_eq!(plan.unresolved_references[0].field, "temp_id");
+        assert_eq!(plan.unresolved_references[1].field, "firewall_group_id");
+        assert!(plan.validation_errors.is_empty());
+    }
+
+    #[test]
+    fn test_plan_update_after_delete_is_unresolved() {
+        // GIVEN
+        let id = Uuid::new_v4();
+        let snapshot = snapshot_with_firewall_group(id);
+        let command_list = vec![
+            command(
+                "DeleteFirewallGroup",
+                "firewall-1",
+                json!({ "id": id.to_string() }),
+            ),
+            command(
+                "UpdateFirewallGroup",
+                "firewall-1",
+                json!({ "id": id.to_string(), "description": "updated" }),
+            ),
+        ];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &snapshot);
+
+        // THEN
+        assert_eq!(plan.delete.len(), 1);
+        assert!(plan.update.is_empty());
+        assert_eq!(plan.unresolved_references.len(), 1);
+        assert_eq!(plan.unresolved_references[0].index, 1);
+    }
+
+    #[test]
+    fn test_plan_validation_errors() {
+        // GIVEN
+        let command_list = vec![
+            command(
+                "CreateBlockStorage",
+                "block-1",
+                json!({ "region": "ewr", "label": "data" }),
+            ),
+            command("CreateInstance", "compute-1", json!({})),
+        ];
+
+        // WHEN
+        let plan = DeployPlan::new(&command_list, &DiagramSnapshot::default());
+
+        // THEN
+        assert_eq!(plan.validation_errors.len(), 2);
+        assert!(plan.validation_errors[0].message.contains("size_gb"));
+        assert_eq!(plan.validation_errors[1].command_name, "CreateInstance");
+        assert_eq!(plan.create.len(), 1);
+    }
+}
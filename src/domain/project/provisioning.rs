@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::vultr_command::VultrCommand;
+use crate::errors::ServiceError;
+
+/// SSH key registered at Vultr through the project, compute is created with it as `sshkey_id`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SshKey {
+    pub project_id: Uuid,
+    pub id: Uuid, // Assigned by Vultr
+    pub name: String,
+    pub ssh_key: String,
+    pub update_dt: DateTime<Utc>,
+}
+
+impl SshKey {
+    pub fn new(project_id: Uuid, id: Uuid, name: String, ssh_key: String) -> Self {
+        Self {
+            project_id,
+            id,
+            name,
+            ssh_key,
+            update_dt: Utc::now(),
+        }
+    }
+
+    pub fn edit(&mut self, name: Option<String>, ssh_key: Option<String>) {
+        if let Some(name) = name {
+            self.name = name;
+        }
+        if let Some(ssh_key) = ssh_key {
+            self.ssh_key = ssh_key;
+        }
+        self.update_dt = Utc::now();
+    }
+}
+
+/// Startup script registered at Vultr through the project, compute is created with it as `script_id`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StartupScript {
+    pub project_id: Uuid,
+    pub id: Uuid, // Assigned by Vultr
+    pub name: String,
+    #[serde(rename = "type")]
+    pub script_type: String, // "boot" or "pxe"
+    pub script: String, // Base64 encoded, as Vultr takes it
+    pub update_dt: DateTime<Utc>,
+}
+
+impl StartupScript {
+    pub fn new(
+        project_id: Uuid,
+        id: Uuid,
+        name: String,
+        script_type: String,
+        script: String,
+    ) -> Self {
+        Self {
+            project_id,
+            id,
+            name,
+            script_type,
+            script,
+            update_dt: Utc::now(),
+        }
+    }
+
+    pub fn edit(&mut self, name: Option<String>, script: Option<String>) {
+        if let Some(name) = name {
+            self.name = name;
+        }
+        if let Some(script) = script {
+            self.script = script;
+        }
+        self.update_dt = Utc::now();
+    }
+}
+
+/// Compute may only be created with SSH keys and startup scripts of its own project.
+pub fn verify_registered(
+    command: &VultrCommand,
+    ssh_keys: &[SshKey],
+    startup_scripts: &[StartupScript],
+) -> Result<(), ServiceError> {
+    let VultrCommand::CreateCompute(compute) = command else {
+        return Ok(());
+    };
+    if let Some(ssh_key_id) = compute
+        .sshkey_id
+        .iter()
+        .flatten()
+        .find(|ssh_key_id| !ssh_keys.iter().any(|ssh_key| ssh_key.id == **ssh_key_id))
+    {
+        return Err(ServiceError::InvalidCommand(format!(
+            "SSH key `{}` is not registered in the project",
+            ssh_key_id
+        )));
+    }
+    if let Some(script_id) = compute
+        .script_id
+        .filter(|script_id| !startup_scripts.iter().any(|script| script.id == *script_id))
+    {
+        return Err(ServiceError::InvalidCommand(format!(
+            "Startup script `{}` is not registered in the project",
+            script_id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_registered() {
+        // GIVEN
+        let project_id = Uuid::new_v4();
+        let ssh_key = SshKey::new(
+            project_id,
+            Uuid::new_v4(),
+            "deploy".to_string(),
+            "ssh-ed25519 AAAA".to_string(),
+        );
+        let startup_script = StartupScript::new(
+            project_id,
+            Uuid::new_v4(),
+            "bootstrap".to_string(),
+            "boot".to_string(),
+            "IyEvYmluL3NoCg==".to_string(),
+        );
+        let compute = json!({
+            "region": "ewr",
+            "plan": "vc2-1c-1gb",
+            "label": "web",
+            "os_id": 2284,
+            "backups": "disabled",
+            "hostname": "web"
+        });
+        let mut registered = compute.clone();
+        registered["sshkey_id"] = json!([ssh_key.id]);
+        registered["script_id"] = json!(startup_script.id);
+        let mut unknown_key = compute.clone();
+        unknown_key["sshkey_id"] = json!([Uuid::new_v4()]);
+        let mut unknown_script = compute.clone();
+        unknown_script["script_id"] = json!(Uuid::new_v4());
+        let ssh_keys = [ssh_key];
+        let startup_scripts = [startup_script];
+
+        // WHEN
+        let verify = |data| {
+            verify_registered(
+                &VultrCommand::new("CreateCompute", data).unwrap(),
+                &ssh_keys,
+                &startup_scripts,
+            )
+        };
+
+        // THEN
+        assert!(verify(compute).is_ok());
+        assert!(verify(registered).is_ok());
+        assert!(matches!(
+            verify(unknown_key),
+            Err(ServiceError::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            verify(unknown_script),
+            Err(ServiceError::InvalidCommand(_))
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use super::{
+    compensation::CompletedStep,
+    diagrams::DiagramSnapshot,
+    drift::{DriftReport, DriftStatus, ResourceDrift},
+    enums::ResourceType,
+    VultrExecutionContext,
+};
+use crate::errors::ServiceError;
+
+// Vultr reports `active` for instances and load balancers, `Running` for databases and
+// `complete` for snapshots
+const READY_STATUSES: [&str; 3] = ["active", "running", "complete"];
+
+/// Compute, databases, load balancers and snapshots answer `pending` right after they are created and are
+/// provisioned in the background. Tracks them until Vultr reports them as ready or the deadline passes.
+pub struct ReadinessWatch {
+    pending: Vec<(ResourceType, String)>,
+    deadline: Instant,
+}
+
+impl ReadinessWatch {
+    pub fn new(context: &VultrExecutionContext, timeout: Duration) -> Self {
+        let pending = context
+            .completed_steps
+            .iter()
+            .filter_map(|step| match step {
+                CompletedStep::Created {
+                    resource_type:
+                        resource_type @ (ResourceType::Compute
+                        | ResourceType::ManagedDatabase
+                        | ResourceType::LoadBalancer
+                        | ResourceType::ComputeSnapshot),
+                    resource_id,
+                    ..
+                } => Some((*resource_type, resource_id.clone())),
+                _ => None,
+            })
+            .collect();
+        Self {
+            pending,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    pub fn pending(&self) -> &[(ResourceType, String)] {
+        &self.pending
+    }
+
+    /// Fetch the pending resources once. Returns the ones whose live state differs from the
+    /// stored row, resources that became ready are no longer watched.
+    pub async fn poll(
+        &mut self,
+        snapshot: &DiagramSnapshot,
+        context: &VultrExecutionContext,
+    ) -> Result<DriftReport, ServiceError> {
+        let stored = self
+            .pending
+            .iter()
+            .filter_map(|(resource_type, id)| {
+                snapshot
+                    .find(resource_type, id, None)
+                    .map(|res| (*resource_type, res))
+            })
+            .collect();
+        let mut report = DriftReport::detect_resources(stored, context).await?;
+        self.settle(&report);
+        // A resource missing right after its creation is left for drift detection to handle
+        report
+            .resources
+            .retain(|resource| resource.status == DriftStatus::Changed);
+        Ok(report)
+    }
+
+    fn settle(&mut self, report: &DriftReport) {
+        self.pending.retain(|(resource_type, id)| {
+            report
+                .resources
+                .iter()
+                .find(|resource| {
+                    resource.resource_type == *resource_type && resource.resource_id == *id
+                })
+                .is_some_and(|resource| !is_ready(resource))
+        });
+    }
+}
+
+impl VultrExecutionContext {
+    /// Public address of a compute or load balancer, polled until Vultr reports it as ready.
+    pub async fn ready_address(
+        &self,
+        resource_type: ResourceType,
+        resource_id: &str,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<String, ServiceError> {
+        let address_field = match resource_type {
+            ResourceType::Compute => "main_ip",
+            ResourceType::LoadBalancer => "ipv4",
+            _ => {
+                return Err(ServiceError::InvalidCommand(format!(
+                    "{:?} has no address",
+                    resource_type
+                )))
+            }
+        };
+        let deadline = Instant::now() + timeout;
+        loop {
+            let live = self.current_state(resource_type, resource_id, None).await?;
+            if let (true, Some(address)) = (has_ready_status(&live), live[address_field].as_str()) {
+                return Ok(address.to_string());
+            }
+            if Instant::now() >= deadline {
+                return Err(ServiceError::RequestError(Box::new(format!(
+                    "{:?} `{}` not ready before the deadline",
+                    resource_type, resource_id
+                ))));
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+fn is_ready(resource: &ResourceDrift) -> bool {
+    resource.live.as_ref().is_none_or(has_ready_status)
+}
+
+fn has_ready_status(live: &Value) -> bool {
+    live["status"]
+        .as_str()
+        .is_some_and(|status| READY_STATUSES.contains(&status.to_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn resource_drift(resource_type: ResourceType, id: &str, status: &str) -> ResourceDrift {
+        ResourceDrift {
+            resource_type,
+            resource_id: id.to_string(),
+            parent_id: None,
+            status: DriftStatus::Changed,
+            changes: Vec::new(),
+            live: Some(json!({ "id": id, "status": status })),
+        }
+    }
+
+    #[test]
+    fn test_settle_keeps_resources_still_provisioning() {
+        // GIVEN
+        let mut watch = ReadinessWatch {
+            pending: vec![
+                (ResourceType::Compute, "compute-1".to_string()),
+                (ResourceType::Compute, "compute-2".to_string()),
+                (ResourceType::ManagedDatabase, "database-1".to_string()),
+                (ResourceType::ManagedDatabase, "database-2".to_string()),
+            ],
+            deadline: Instant::now() + Duration::from_secs(60),
+        };
+        let report = DriftReport {
+            resources: vec![
+                resource_drift(ResourceType::Compute, "compute-1", "active"),
+                resource_drift(ResourceType::Compute, "compute-2", "pending"),
+                resource_drift(ResourceType::ManagedDatabase, "database-1", "Running"),
+                resource_drift(ResourceType::ManagedDatabase, "database-2", "Rebuilding"),
+            ],
+        };
+
+        // WHEN
+        watch.settle(&report);
+
+        // THEN
+        assert_eq!(
+            watch.pending(),
+            &[
+                (ResourceType::Compute, "compute-2".to_string()),
+                (ResourceType::ManagedDatabase, "database-2".to_string()),
+            ]
+        );
+        assert!(!watch.is_finished());
+        assert!(!watch.is_expired());
+    }
+}
@@ -0,0 +1,1487 @@
+use std::{collections::HashMap, str::FromStr};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{
+    commands::{CommandRequest, MoveResource},
+    diagrams::{
+        BlockStorage, Compute, ComputeSnapshot, ConnectionPool, DatabaseUser, DiagramSnapshot,
+        DnsDomain, DnsRecord, FirewallGroup, FirewallRule, LoadBalancer, LogicalDatabase,
+        ManagedDatabase, ObjectPosition, ObjectStorage, Vpc,
+    },
+    enums::ResourceType,
+    resource_id, VultrExecutionContext,
+};
+use crate::{
+    adapter::{
+        repositories::project::diagram::{
+            delete_block_storage, delete_compute, delete_compute_snapshot, delete_connection_pool,
+            delete_database_user, delete_dns_domain, delete_dns_record, delete_firewall_group,
+            delete_firewall_rule, delete_load_balancer, delete_logical_database,
+            delete_managed_database, delete_object_storage, delete_vpc, insert_block_storage,
+            insert_compute, insert_compute_snapshot, insert_connection_pool, insert_database_user,
+            insert_dns_domain, insert_dns_record, insert_firewall_group, insert_firewall_rule,
+            insert_load_balancer, insert_logical_database, insert_managed_database,
+            insert_object_storage, insert_vpc, update_block_storage, update_block_storage_position,
+            update_compute, update_compute_position, update_compute_snapshot,
+            update_compute_snapshot_position, update_connection_pool,
+            update_connection_pool_position, update_database_user, update_database_user_position,
+            update_dns_domain, update_dns_domain_position, update_dns_record,
+            update_dns_record_position, update_firewall_group, update_firewall_group_position,
+            update_firewall_rule, update_firewall_rule_position, update_load_balancer,
+            update_load_balancer_position, update_logical_database,
+            update_logical_database_position, update_managed_database,
+            update_managed_database_position, update_object_storage,
+            update_object_storage_position, update_vpc, update_vpc_position,
+        },
+        request_dispensor::vultr::{
+            interfaces::{
+                ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrUpdateCommand,
+            },
+            schemas::{
+                block_storage::{
+                    AttachBlockStorageToCompute, CreateBlockStorage, DeleteBlockStorage,
+                    DetachBlockStorageFromCompute, UpdateBlockStorage,
+                },
+                dns::{
+                    CreateDnsDomain, CreateDnsRecord, DeleteDnsDomain, DeleteDnsRecord,
+                    UpdateDnsDomain, UpdateDnsRecord,
+                },
+                firewall::{
+                    CreateFirewallGroup, CreateFirewallRule, DeleteFirewallGroup,
+                    DeleteFirewallRule, UpdateFirewallGroup, UpdateFirewallRule,
+                },
+                instance::{
+                    CreateCompute, DeleteCompute, RestoreCompute, SetComputeBackupSchedule,
+                    UpdateCompute,
+                },
+                load_balancer::{CreateLoadBalancer, DeleteLoadBalancer, UpdateLoadBalancer},
+                managed_database::{
+                    CreateConnectionPool, CreateDatabaseUser, CreateLogicalDatabase,
+                    CreateManagedDatabase, DeleteConnectionPool, DeleteDatabaseUser,
+                    DeleteLogicalDatabase, DeleteManagedDatabase, UpdateConnectionPool,
+                    UpdateDatabaseUser, UpdateManagedDatabase,
+                },
+                object_storage::{CreateObjectStorage, DeleteObjectStorage, UpdateObjectStorage},
+                snapshot::{CreateComputeSnapshot, DeleteComputeSnapshot, UpdateComputeSnapshot},
+                vpc::{AttachVpcToCompute, CreateVpc, DeleteVpc, DetachVpcFromCompute, UpdateVpc},
+            },
+            VultrClient,
+        },
+    },
+    config::get_config,
+    errors::ServiceError,
+};
+
+/// Every command a deploy can run, tagged by `command_name` with its payload in `data`.
+/// All of them go to Vultr except `MoveResource`, which only changes the layout.
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "command_name", content = "data")]
+pub enum VultrCommand {
+    CreateCompute(CreateCompute),
+    UpdateCompute(UpdateCompute),
+    DeleteCompute(DeleteCompute),
+    CreateBlockStorage(CreateBlockStorage),
+    UpdateBlockStorage(UpdateBlockStorage),
+    AttachBlockStorageToCompute(AttachBlockStorageToCompute),
+    DetachBlockStorageFromCompute(DetachBlockStorageFromCompute),
+    DeleteBlockStorage(DeleteBlockStorage),
+    CreateFirewallGroup(CreateFirewallGroup),
+    UpdateFirewallGroup(UpdateFirewallGroup),
+    DeleteFirewallGroup(DeleteFirewallGroup),
+    CreateFirewallRule(CreateFirewallRule),
+    UpdateFirewallRule(UpdateFirewallRule),
+    DeleteFirewallRule(DeleteFirewallRule),
+    CreateManagedDatabase(CreateManagedDatabase),
+    UpdateManagedDatabase(UpdateManagedDatabase),
+    DeleteManagedDatabase(DeleteManagedDatabase),
+    CreateDatabaseUser(CreateDatabaseUser),
+    UpdateDatabaseUser(UpdateDatabaseUser),
+    DeleteDatabaseUser(DeleteDatabaseUser),
+    CreateLogicalDatabase(CreateLogicalDatabase),
+    DeleteLogicalDatabase(DeleteLogicalDatabase),
+    CreateConnectionPool(CreateConnectionPool),
+    UpdateConnectionPool(UpdateConnectionPool),
+    DeleteConnectionPool(DeleteConnectionPool),
+    CreateObjectStorage(CreateObjectStorage),
+    UpdateObjectStorage(UpdateObjectStorage),
+    DeleteObjectStorage(DeleteObjectStorage),
+    CreateVpc(CreateVpc),
+    UpdateVpc(UpdateVpc),
+    AttachVpcToCompute(AttachVpcToCompute),
+    DetachVpcFromCompute(DetachVpcFromCompute),
+    DeleteVpc(DeleteVpc),
+    CreateLoadBalancer(CreateLoadBalancer),
+    UpdateLoadBalancer(UpdateLoadBalancer),
+    DeleteLoadBalancer(DeleteLoadBalancer),
+    CreateDnsDomain(CreateDnsDomain),
+    UpdateDnsDomain(UpdateDnsDomain),
+    DeleteDnsDomain(DeleteDnsDomain),
+    CreateDnsRecord(CreateDnsRecord),
+    UpdateDnsRecord(UpdateDnsRecord),
+    DeleteDnsRecord(DeleteDnsRecord),
+    SetComputeBackupSchedule(SetComputeBackupSchedule),
+    CreateComputeSnapshot(CreateComputeSnapshot),
+    UpdateComputeSnapshot(UpdateComputeSnapshot),
+    DeleteComputeSnapshot(DeleteComputeSnapshot),
+    RestoreCompute(RestoreCompute),
+    MoveResource(MoveResource),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind {
+    Create,
+    Update,
+    Delete,
+    Move,
+}
+
+/// Result of a Vultr call that still has to be recorded and written to the stored diagram.
+pub enum CommandOutcome {
+    Created {
+        temp_id: String,
+        resource_type: ResourceType,
+        position: ObjectPosition,
+        res: Value,
+        parent_id: Option<String>,
+    },
+    Updated {
+        temp_id: String,
+        resource_type: ResourceType,
+        id: String,
+        position: ObjectPosition,
+        command_name: &'static str,
+        data: Value,
+        previous: Value,
+        res: Value,
+    },
+    Deleted {
+        resource_type: ResourceType,
+        id: String,
+        parent_id: Option<String>,
+    },
+    Moved {
+        resource_type: ResourceType,
+        id: String,
+        parent_id: Option<String>,
+        position: ObjectPosition,
+    },
+}
+
+const COMMANDS: &[(&str, CommandKind, ResourceType)] = &[
+    ("CreateCompute", CommandKind::Create, ResourceType::Compute),
+    ("UpdateCompute", CommandKind::Update, ResourceType::Compute),
+    ("DeleteCompute", CommandKind::Delete, ResourceType::Compute),
+    (
+        "CreateBlockStorage",
+        CommandKind::Create,
+        ResourceType::BlockStorage,
+    ),
+    (
+        "UpdateBlockStorage",
+        CommandKind::Update,
+        ResourceType::BlockStorage,
+    ),
+    (
+        "AttachBlockStorageToCompute",
+        CommandKind::Update,
+        ResourceType::BlockStorage,
+    ),
+    (
+        "DetachBlockStorageFromCompute",
+        CommandKind::Update,
+        ResourceType::BlockStorage,
+    ),
+    (
+        "DeleteBlockStorage",
+        CommandKind::Delete,
+        ResourceType::BlockStorage,
+    ),
+    (
+        "CreateFirewallGroup",
+        CommandKind::Create,
+        ResourceType::FirewallGroup,
+    ),
+    (
+        "UpdateFirewallGroup",
+        CommandKind::Update,
+        ResourceType::FirewallGroup,
+    ),
+    (
+        "DeleteFirewallGroup",
+        CommandKind::Delete,
+        ResourceType::FirewallGroup,
+    ),
+    (
+        "CreateFirewallRule",
+        CommandKind::Create,
+        ResourceType::FirewallRule,
+    ),
+    (
+        "UpdateFirewallRule",
+        CommandKind::Update,
+        ResourceType::FirewallRule,
+    ),
+    (
+        "DeleteFirewallRule",
+        CommandKind::Delete,
+        ResourceType::FirewallRule,
+    ),
+    (
+        "CreateManagedDatabase",
+        CommandKind::Create,
+        ResourceType::ManagedDatabase,
+    ),
+    (
+        "UpdateManagedDatabase",
+        CommandKind::Update,
+        ResourceType::ManagedDatabase,
+    ),
+    (
+        "DeleteManagedDatabase",
+        CommandKind::Delete,
+        ResourceType::ManagedDatabase,
+    ),
+    (
+        "CreateDatabaseUser",
+        CommandKind::Create,
+        ResourceType::DatabaseUser,
+    ),
+    (
+        "UpdateDatabaseUser",
+        CommandKind::Update,
+        ResourceType::DatabaseUser,
+    ),
+    (
+        "DeleteDatabaseUser",
+        CommandKind::Delete,
+        ResourceType::DatabaseUser,
+    ),
+    (
+        "CreateLogicalDatabase",
+        CommandKind::Create,
+        ResourceType::LogicalDatabase,
+    ),
+    (
+        "DeleteLogicalDatabase",
+        CommandKind::Delete,
+        ResourceType::LogicalDatabase,
+    ),
+    (
+        "CreateConnectionPool",
+        CommandKind::Create,
+        ResourceType::ConnectionPool,
+    ),
+    (
+        "UpdateConnectionPool",
+        CommandKind::Update,
+        ResourceType::ConnectionPool,
+    ),
+    (
+        "DeleteConnectionPool",
+        CommandKind::Delete,
+        ResourceType::ConnectionPool,
+    ),
+    (
+        "CreateObjectStorage",
+        CommandKind::Create,
+        ResourceType::ObjectStorage,
+    ),
+    (
+        "UpdateObjectStorage",
+        CommandKind::Update,
+        ResourceType::ObjectStorage,
+    ),
+    (
+        "DeleteObjectStorage",
+        CommandKind::Delete,
+        ResourceType::ObjectStorage,
+    ),
+    ("CreateVpc", CommandKind::Create, ResourceType::Vpc),
+    ("UpdateVpc", CommandKind::Update, ResourceType::Vpc),
+    (
+        "AttachVpcToCompute",
+        CommandKind::Update,
+        ResourceType::Compute,
+    ),
+    (
+        "DetachVpcFromCompute",
+        CommandKind::Update,
+        ResourceType::Compute,
+    ),
+    ("DeleteVpc", CommandKind::Delete, ResourceType::Vpc),
+    (
+        "CreateLoadBalancer",
+        CommandKind::Create,
+        ResourceType::LoadBalancer,
+    ),
+    (
+        "UpdateLoadBalancer",
+        CommandKind::Update,
+        ResourceType::LoadBalancer,
+    ),
+    (
+        "DeleteLoadBalancer",
+        CommandKind::Delete,
+        ResourceType::LoadBalancer,
+    ),
+    (
+        "CreateDnsDomain",
+        CommandKind::Create,
+        ResourceType::DnsDomain,
+    ),
+    (
+        "UpdateDnsDomain",
+        CommandKind::Update,
+        ResourceType::DnsDomain,
+    ),
+    (
+        "DeleteDnsDomain",
+        CommandKind::Delete,
+        ResourceType::DnsDomain,
+    ),
+    (
+        "CreateDnsRecord",
+        CommandKind::Create,
+        ResourceType::DnsRecord,
+    ),
+    (
+        "UpdateDnsRecord",
+        CommandKind::Update,
+        ResourceType::DnsRecord,
+    ),
+    (
+        "DeleteDnsRecord",
+        CommandKind::Delete,
+        ResourceType::DnsRecord,
+    ),
+    (
+        "SetComputeBackupSchedule",
+        CommandKind::Update,
+        ResourceType::Compute,
+    ),
+    (
+        "CreateComputeSnapshot",
+        CommandKind::Create,
+        ResourceType::ComputeSnapshot,
+    ),
+    (
+        "UpdateComputeSnapshot",
+        CommandKind::Update,
+        ResourceType::ComputeSnapshot,
+    ),
+    (
+        "DeleteComputeSnapshot",
+        CommandKind::Delete,
+        ResourceType::ComputeSnapshot,
+    ),
+    ("RestoreCompute", CommandKind::Update, ResourceType::Compute),
+];
+
+impl TryFrom<&CommandRequest> for VultrCommand {
+    type Error = ServiceError;
+
+    fn try_from(request: &CommandRequest) -> Result<Self, Self::Error> {
+        Self::resolve(request, &HashMap::new(), &HashMap::new()).map(|(command, _)| command)
+    }
+}
+
+impl VultrCommand {
+    /// Build the command after replacing every `$ref` in its data with the id found in `ids`.
+    /// The resolved data is returned as well since that is what goes to Vultr.
+    pub fn resolve(
+        request: &CommandRequest,
+        ids: &HashMap<String, String>,
+        created_types: &HashMap<String, ResourceType>,
+    ) -> Result<(Self, Value), ServiceError> {
+        resolve_references(&request.data, ids, created_types)
+            .and_then(|data| Ok((Self::new(&request.command_name, data.clone())?, data)))
+            .map_err(|err| match err {
+                ServiceError::InvalidCommand(message) => {
+                    ServiceError::InvalidCommand(format!("`{}`: {}", request.temp_id, message))
+                }
+                err => err,
+            })
+    }
+
+    pub fn new(command_name: &str, data: Value) -> Result<Self, ServiceError> {
+        let command: Self = serde_json::from_value(
+            json!({ "command_name": command_name, "data": data }),
+        )
+        .map_err(|err| {
+            ServiceError::InvalidCommand(format!("invalid command `{}`: {}", command_name, err))
+        })?;
+        if let Self::MoveResource(MoveResource {
+            resource_type,
+            parent_id: None,
+            ..
+        }) = &command
+        {
+            if let Some(field) = parent_field(*resource_type) {
+                return Err(ServiceError::InvalidCommand(format!(
+                    "moving a {:?} needs its `parent_id` ({})",
+                    resource_type, field
+                )));
+            }
+        }
+        Ok(command)
+    }
+
+    /// Kind of change and affected resource for a command name, whether or not its data is valid.
+    pub fn describe(command_name: &str) -> Option<(CommandKind, ResourceType)> {
+        COMMANDS
+            .iter()
+            .find(|(name, _, _)| *name == command_name)
+            .map(|(_, kind, resource_type)| (*kind, *resource_type))
+    }
+
+    pub fn kind(&self) -> CommandKind {
+        match self {
+            Self::MoveResource(_) => CommandKind::Move,
+            _ => self.description().0,
+        }
+    }
+
+    pub fn resource_type(&self) -> ResourceType {
+        match self {
+            Self::MoveResource(command) => command.resource_type,
+            _ => self.description().1,
+        }
+    }
+
+    fn description(&self) -> (CommandKind, ResourceType) {
+        Self::describe(self.name()).expect("every command is listed in COMMANDS")
+    }
+
+    /// Send the command to Vultr without touching the stored diagram.
+    pub async fn send(self, vultr_client: &VultrClient) -> Result<Option<Value>, ServiceError> {
+        match self {
+            Self::CreateCompute(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateCompute(command) => command.execute(vultr_client).await,
+            Self::DeleteCompute(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateBlockStorage(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateBlockStorage(command) => command.execute(vultr_client).await,
+            Self::AttachBlockStorageToCompute(command) => command.execute(vultr_client).await,
+            Self::DetachBlockStorageFromCompute(command) => command.execute(vultr_client).await,
+            Self::DeleteBlockStorage(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateFirewallGroup(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateFirewallGroup(command) => command.execute(vultr_client).await,
+            Self::DeleteFirewallGroup(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateFirewallRule(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateFirewallRule(command) => command.execute(vultr_client).await,
+            Self::DeleteFirewallRule(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateManagedDatabase(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateManagedDatabase(command) => command.execute(vultr_client).await,
+            Self::DeleteManagedDatabase(command) => {
+                command.execute(vultr_client).await.map(|_| None)
+            }
+            Self::CreateDatabaseUser(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateDatabaseUser(command) => command.execute(vultr_client).await,
+            Self::DeleteDatabaseUser(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateLogicalDatabase(command) => command.execute(vultr_client).await.map(Some),
+            Self::DeleteLogicalDatabase(command) => {
+                command.execute(vultr_client).await.map(|_| None)
+            }
+            Self::CreateConnectionPool(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateConnectionPool(command) => command.execute(vultr_client).await,
+            Self::DeleteConnectionPool(command) => {
+                command.execute(vultr_client).await.map(|_| None)
+            }
+            Self::CreateObjectStorage(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateObjectStorage(command) => command.execute(vultr_client).await,
+            Self::DeleteObjectStorage(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateVpc(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateVpc(command) => command.execute(vultr_client).await,
+            Self::AttachVpcToCompute(command) => command.execute(vultr_client).await,
+            Self::DetachVpcFromCompute(command) => command.execute(vultr_client).await,
+            Self::DeleteVpc(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateLoadBalancer(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateLoadBalancer(command) => command.execute(vultr_client).await,
+            Self::DeleteLoadBalancer(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateDnsDomain(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateDnsDomain(command) => command.execute(vultr_client).await,
+            Self::DeleteDnsDomain(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::CreateDnsRecord(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateDnsRecord(command) => command.execute(vultr_client).await,
+            Self::DeleteDnsRecord(command) => command.execute(vultr_client).await.map(|_| None),
+            Self::SetComputeBackupSchedule(command) => command.execute(vultr_client).await,
+            Self::CreateComputeSnapshot(command) => command.execute(vultr_client).await.map(Some),
+            Self::UpdateComputeSnapshot(command) => command.execute(vultr_client).await,
+            Self::DeleteComputeSnapshot(command) => {
+                command.execute(vultr_client).await.map(|_| None)
+            }
+            Self::RestoreCompute(command) => command.execute(vultr_client).await,
+            Self::MoveResource(_) => Ok(None),
+        }
+    }
+
+    /// Apply the command at Vultr. Only reads the context, so independent commands can run
+    /// concurrently, the outcome is recorded and stored by the caller.
+    pub async fn call(
+        mut self,
+        temp_id: String,
+        position: ObjectPosition,
+        data: Value,
+        context: &VultrExecutionContext,
+    ) -> Result<CommandOutcome, ServiceError> {
+        let resource_type = self.resource_type();
+        let parent_id = self.parent_id();
+        // A record pointing at a compute or load balancer takes its address once it is ready
+        if let Some((target_type, target_id)) = self.dns_target() {
+            let config = get_config();
+            let address = context
+                .ready_address(
+                    target_type,
+                    &target_id.to_string(),
+                    config.vultr_ready_timeout,
+                    config.vultr_ready_poll_interval,
+                )
+                .await?;
+            self.fill_dns_data(address);
+        }
+        let local_fields = self.local_fields();
+        let outcome = match self.kind() {
+            CommandKind::Create => {
+                let mut res = self
+                    .send(&context.vultr_client)
+                    .await?
+                    .ok_or(ServiceError::NotFound)?;
+                // Vultr won't return auto_backups, keep the one requested
+                if let (ResourceType::Compute, Some(fields), Some(backups)) =
+                    (resource_type, res.as_object_mut(), data.get("backups"))
+                {
+                    fields
+                        .entry("auto_backups")
+                        .or_insert_with(|| backups.clone());
+                }
+                let res = with_local_fields(res, local_fields);
+                let res = with_parent(res, resource_type, parent_id.as_deref());
+                CommandOutcome::Created {
+                    temp_id,
+                    resource_type,
+                    position,
+                    res,
+                    parent_id,
+                }
+            }
+            CommandKind::Update => {
+                let id = context.target_id(self.name(), resource_type, &temp_id, &data)?;
+                let previous = context
+                    .current_state(resource_type, &id, parent_id.as_deref())
+                    .await?;
+                let command_name = self.name();
+                let res = self.send(&context.vultr_client).await?;
+                // Not every update answers with the resource, fetch it in that case
+                let res = match res {
+                    Some(res) => res,
+                    None => {
+                        context
+                            .current_state(resource_type, &id, parent_id.as_deref())
+                            .await?
+                    }
+                };
+                let res = with_local_fields(res, local_fields);
+                CommandOutcome::Updated {
+                    temp_id,
+                    resource_type,
+                    id,
+                    position,
+                    command_name,
+                    data,
+                    previous,
+                    res: with_parent(res, resource_type, parent_id.as_deref()),
+                }
+            }
+            CommandKind::Delete => {
+                let id = context.target_id(self.name(), resource_type, &temp_id, &data)?;
+                self.send(&context.vultr_client).await?;
+                CommandOutcome::Deleted {
+                    resource_type,
+                    id,
+                    parent_id,
+                }
+            }
+            CommandKind::Move => {
+                let id = match self {
+                    Self::MoveResource(MoveResource { id: Some(id), .. }) => {
+                        resource_id(&json!({ "id": id }))?
+                    }
+                    _ => context.get_id_with_temp_id(&temp_id)?,
+                };
+                CommandOutcome::Moved {
+                    resource_type,
+                    id,
+                    parent_id,
+                    position,
+                }
+            }
+        };
+        Ok(outcome)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CreateCompute(_) => "CreateCompute",
+            Self::UpdateCompute(_) => "UpdateCompute",
+            Self::DeleteCompute(_) => "DeleteCompute",
+            Self::CreateBlockStorage(_) => "CreateBlockStorage",
+            Self::UpdateBlockStorage(_) => "UpdateBlockStorage",
+            Self::AttachBlockStorageToCompute(_) => "AttachBlockStorageToCompute",
+            Self::DetachBlockStorageFromCompute(_) => "DetachBlockStorageFromCompute",
+            Self::DeleteBlockStorage(_) => "DeleteBlockStorage",
+            Self::CreateFirewallGroup(_) => "CreateFirewallGroup",
+            Self::UpdateFirewallGroup(_) => "UpdateFirewallGroup",
+            Self::DeleteFirewallGroup(_) => "DeleteFirewallGroup",
+            Self::CreateFirewallRule(_) => "CreateFirewallRule",
+            Self::UpdateFirewallRule(_) => "UpdateFirewallRule",
+            Self::DeleteFirewallRule(_) => "DeleteFirewallRule",
+            Self::CreateManagedDatabase(_) => "CreateManagedDatabase",
+            Self::UpdateManagedDatabase(_) => "UpdateManagedDatabase",
+            Self::DeleteManagedDatabase(_) => "DeleteManagedDatabase",
+            Self::CreateDatabaseUser(_) => "CreateDatabaseUser",
+            Self::UpdateDatabaseUser(_) => "UpdateDatabaseUser",
+            Self::DeleteDatabaseUser(_) => "DeleteDatabaseUser",
+            Self::CreateLogicalDatabase(_) => "CreateLogicalDatabase",
+            Self::DeleteLogicalDatabase(_) => "DeleteLogicalDatabase",
+            Self::CreateConnectionPool(_) => "CreateConnectionPool",
+            Self::UpdateConnectionPool(_) => "UpdateConnectionPool",
+            Self::DeleteConnectionPool(_) => "DeleteConnectionPool",
+            Self::CreateObjectStorage(_) => "CreateObjectStorage",
+            Self::UpdateObjectStorage(_) => "UpdateObjectStorage",
+            Self::DeleteObjectStorage(_) => "DeleteObjectStorage",
+            Self::CreateVpc(_) => "CreateVpc",
+            Self::UpdateVpc(_) => "UpdateVpc",
+            Self::AttachVpcToCompute(_) => "AttachVpcToCompute",
+            Self::DetachVpcFromCompute(_) => "DetachVpcFromCompute",
+            Self::DeleteVpc(_) => "DeleteVpc",
+            Self::CreateLoadBalancer(_) => "CreateLoadBalancer",
+            Self::UpdateLoadBalancer(_) => "UpdateLoadBalancer",
+            Self::DeleteLoadBalancer(_) => "DeleteLoadBalancer",
+            Self::CreateDnsDomain(_) => "CreateDnsDomain",
+            Self::UpdateDnsDomain(_) => "UpdateDnsDomain",
+            Self::DeleteDnsDomain(_) => "DeleteDnsDomain",
+            Self::CreateDnsRecord(_) => "CreateDnsRecord",
+            Self::UpdateDnsRecord(_) => "UpdateDnsRecord",
+            Self::DeleteDnsRecord(_) => "DeleteDnsRecord",
+            Self::SetComputeBackupSchedule(_) => "SetComputeBackupSchedule",
+            Self::CreateComputeSnapshot(_) => "CreateComputeSnapshot",
+            Self::UpdateComputeSnapshot(_) => "UpdateComputeSnapshot",
+            Self::DeleteComputeSnapshot(_) => "DeleteComputeSnapshot",
+            Self::RestoreCompute(_) => "RestoreCompute",
+            Self::MoveResource(_) => "MoveResource",
+        }
+    }
+
+    /// Firewall rules are addressed through their group, DNS records through their domain and
+    /// users, logical databases and connection pools through their managed database.
+    fn parent_id(&self) -> Option<String> {
+        match self {
+            Self::CreateFirewallRule(command) => Some(command.firewall_group_id.to_string()),
+            Self::UpdateFirewallRule(command) => Some(command.firewall_group_id.to_string()),
+            Self::DeleteFirewallRule(command) => Some(command.firewall_group_id.to_string()),
+            Self::CreateDnsRecord(command) => Some(command.domain.clone()),
+            Self::UpdateDnsRecord(command) => Some(command.domain.clone()),
+            Self::DeleteDnsRecord(command) => Some(command.domain.clone()),
+            Self::CreateDatabaseUser(command) => Some(command.database_id.to_string()),
+            Self::UpdateDatabaseUser(command) => Some(command.database_id.to_string()),
+            Self::DeleteDatabaseUser(command) => Some(command.database_id.to_string()),
+            Self::CreateLogicalDatabase(command) => Some(command.database_id.to_string()),
+            Self::DeleteLogicalDatabase(command) => Some(command.database_id.to_string()),
+            Self::CreateConnectionPool(command) => Some(command.database_id.to_string()),
+            Self::UpdateConnectionPool(command) => Some(command.database_id.to_string()),
+            Self::DeleteConnectionPool(command) => Some(command.database_id.to_string()),
+            Self::MoveResource(command) => command.parent_id.clone(),
+            _ => None,
+        }
+    }
+
+    /// Fields Vultr won't report back, the stored row takes them from the command.
+    /// The VPC of an instance is empty once detached.
+    fn local_fields(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            Self::AttachVpcToCompute(command) => vec![("vpc_id", json!(command.vpc_id))],
+            Self::DetachVpcFromCompute(_) => vec![("vpc_id", json!(""))],
+            Self::SetComputeBackupSchedule(command) => {
+                vec![("backup_schedule", json!(command.schedule))]
+            }
+            Self::CreateComputeSnapshot(command) => {
+                vec![("instance_id", json!(command.instance_id))]
+            }
+            Self::CreateDnsRecord(_) | Self::UpdateDnsRecord(_) => self
+                .dns_target()
+                .map(|(_, target_id)| vec![("target_id", json!(target_id))])
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn dns_target(&self) -> Option<(ResourceType, Uuid)> {
+        match self {
+            Self::CreateDnsRecord(command) => command.target(),
+            Self::UpdateDnsRecord(command) => command.target(),
+            _ => None,
+        }
+    }
+
+    fn fill_dns_data(&mut self, address: String) {
+        match self {
+            Self::CreateDnsRecord(command) => command.data = Some(address),
+            Self::UpdateDnsRecord(command) => command.data = Some(address),
+            _ => {}
+        }
+    }
+}
+
+/// Field of a child resource holding the resource it is addressed through.
+pub fn parent_field(resource_type: ResourceType) -> Option<&'static str> {
+    match resource_type {
+        ResourceType::FirewallRule => Some("firewall_group_id"),
+        ResourceType::DnsRecord => Some("domain"),
+        ResourceType::DatabaseUser
+        | ResourceType::LogicalDatabase
+        | ResourceType::ConnectionPool => Some("database_id"),
+        _ => None,
+    }
+}
+
+/// Vultr answers a child resource without its parent, keep the one it was addressed through.
+fn with_parent(mut res: Value, resource_type: ResourceType, parent_id: Option<&str>) -> Value {
+    if let (Some(fields), Some(field), Some(parent_id)) =
+        (res.as_object_mut(), parent_field(resource_type), parent_id)
+    {
+        fields.insert(field.to_string(), json!(parent_id));
+    }
+    res
+}
+
+fn with_local_fields(mut res: Value, local_fields: Vec<(&'static str, Value)>) -> Value {
+    if let Some(fields) = res.as_object_mut() {
+        for (field, value) in local_fields {
+            fields.insert(field.to_string(), value);
+        }
+    }
+    res
+}
+
+/// `{"$ref": "<temp_id>"}` stands for the id of the resource created under that temp_id.
+pub fn reference_of(value: &Value) -> Option<&str> {
+    let object = value.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    object.get("$ref")?.as_str()
+}
+
+/// Every `$ref` in a payload with the name of the field holding it.
+pub fn references(data: &Value) -> Vec<(Option<&str>, &str)> {
+    fn collect<'a>(
+        value: &'a Value,
+        field: Option<&'a str>,
+        found: &mut Vec<(Option<&'a str>, &'a str)>,
+    ) {
+        if let Some(temp_id) = reference_of(value) {
+            found.push((field, temp_id));
+            return;
+        }
+        match value {
+            Value::Object(fields) => {
+                for (field, value) in fields.iter() {
+                    collect(value, Some(field), found);
+                }
+            }
+            Value::Array(values) => {
+                for value in values.iter() {
+                    collect(value, field, found);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut found = Vec::new();
+    collect(data, None, &mut found);
+    found
+}
+
+/// `created_types` is the resource type created under each temp_id, see `created_types`.
+pub fn resolve_references(
+    data: &Value,
+    ids: &HashMap<String, String>,
+    created_types: &HashMap<String, ResourceType>,
+) -> Result<Value, ServiceError> {
+    if let Some(temp_id) = reference_of(data) {
+        let id = ids.get(temp_id).ok_or_else(|| {
+            ServiceError::InvalidCommand(format!("`$ref` to `{}` is not resolved", temp_id))
+        })?;
+        // Firewall rule ids are numbers at Vultr
+        if created_types.get(temp_id) == Some(&ResourceType::FirewallRule) {
+            let id = id.parse::<i64>().map_err(|_| {
+                ServiceError::InvalidCommand(format!(
+                    "`$ref` to `{}` is not a firewall rule id",
+                    temp_id
+                ))
+            })?;
+            return Ok(Value::from(id));
+        }
+        return Ok(Value::String(id.clone()));
+    }
+    let resolved = match data {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(field, value)| {
+                    Ok((
+                        field.clone(),
+                        resolve_references(value, ids, created_types)?,
+                    ))
+                })
+                .collect::<Result<_, ServiceError>>()?,
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| resolve_references(value, ids, created_types))
+                .collect::<Result<_, ServiceError>>()?,
+        ),
+        value => value.clone(),
+    };
+    Ok(resolved)
+}
+
+/// Resource type created under each temp_id of the list.
+pub fn created_types(command_list: &[CommandRequest]) -> HashMap<String, ResourceType> {
+    command_list
+        .iter()
+        .filter_map(
+            |request| match VultrCommand::describe(&request.command_name) {
+                Some((CommandKind::Create, resource_type)) => {
+                    Some((request.temp_id.clone(), resource_type))
+                }
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// Stand-in ids for every resource the list creates, so payloads with `$ref`s can be
+/// checked before anything exists at Vultr.
+pub fn placeholder_ids(command_list: &[CommandRequest]) -> HashMap<String, String> {
+    command_list
+        .iter()
+        .filter_map(
+            |request| match VultrCommand::describe(&request.command_name) {
+                Some((CommandKind::Create, ResourceType::FirewallRule)) => {
+                    Some((request.temp_id.clone(), "0".to_string()))
+                }
+                Some((CommandKind::Create, _)) => {
+                    Some((request.temp_id.clone(), Uuid::nil().to_string()))
+                }
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// Payload fields holding the id of another resource, with the type they refer to.
+pub fn reference_fields(command_name: &str) -> &'static [(&'static str, ResourceType)] {
+    match command_name {
+        "CreateFirewallRule" | "UpdateFirewallRule" | "DeleteFirewallRule" | "UpdateCompute" => {
+            &[("firewall_group_id", ResourceType::FirewallGroup)]
+        }
+        "AttachBlockStorageToCompute" => &[("instance_id", ResourceType::Compute)],
+        "AttachVpcToCompute"
+        | "DetachVpcFromCompute"
+        | "CreateManagedDatabase"
+        | "UpdateManagedDatabase" => &[("vpc_id", ResourceType::Vpc)],
+        "CreateLoadBalancer" | "UpdateLoadBalancer" => &[("instances", ResourceType::Compute)],
+        "CreateComputeSnapshot" => &[("instance_id", ResourceType::Compute)],
+        "CreateDatabaseUser"
+        | "UpdateDatabaseUser"
+        | "DeleteDatabaseUser"
+        | "CreateLogicalDatabase"
+        | "DeleteLogicalDatabase"
+        | "CreateConnectionPool"
+        | "UpdateConnectionPool"
+        | "DeleteConnectionPool" => &[("database_id", ResourceType::ManagedDatabase)],
+        "RestoreCompute" => &[("snapshot_id", ResourceType::ComputeSnapshot)],
+        "CreateDnsRecord" | "UpdateDnsRecord" | "DeleteDnsRecord" => &[
+            ("domain", ResourceType::DnsDomain),
+            ("instance_id", ResourceType::Compute),
+            ("load_balancer_id", ResourceType::LoadBalancer),
+        ],
+        _ => &[],
+    }
+}
+
+/// Existing resource an update or delete is aimed at.
+pub enum Target {
+    // Created under the command's temp_id earlier in the same deploy
+    Created(String),
+    // Stored by an earlier deploy, with the stored row
+    Stored(String, Value),
+}
+
+/// The resource created under the command's temp_id comes first, otherwise the payload has to name
+/// one of the project's stored resources. The plan and the deploy both resolve targets through this.
+pub fn resolve_target(
+    command_name: &str,
+    resource_type: ResourceType,
+    temp_id: &str,
+    data: &Value,
+    created: &HashMap<String, String>,
+    stored: &DiagramSnapshot,
+) -> Option<Target> {
+    if let Some(id) = created.get(temp_id) {
+        return Some(Target::Created(id.clone()));
+    }
+    let id = value_to_id(&data[target_field(command_name)])?;
+    let parent_id = parent_field(resource_type).and_then(|field| value_to_id(&data[field]));
+    stored
+        .find(&resource_type, &id, parent_id.as_deref())
+        .map(|row| Target::Stored(id, row))
+}
+
+/// Payload field holding the id of the resource an update or delete is aimed at.
+pub fn target_field(command_name: &str) -> &'static str {
+    match command_name {
+        "UpdateFirewallRule" | "DeleteFirewallRule" => "firewall_rule_id",
+        _ => "id",
+    }
+}
+
+pub fn value_to_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) if !id.is_empty() => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+impl VultrExecutionContext {
+    fn target_id(
+        &self,
+        command_name: &str,
+        resource_type: ResourceType,
+        temp_id: &str,
+        data: &Value,
+    ) -> Result<String, ServiceError> {
+        match resolve_target(
+            command_name,
+            resource_type,
+            temp_id,
+            data,
+            &self.resource_map,
+            &self.stored,
+        ) {
+            Some(Target::Created(id)) | Some(Target::Stored(id, _)) => Ok(id),
+            None => Err(ServiceError::NotFound),
+        }
+    }
+
+    /// Record the step for compensation and the id later commands refer to. The stored diagram is
+    /// only written by `store`, once the deploy is done with Vultr.
+    pub fn record(&mut self, outcome: &CommandOutcome) -> Result<(), ServiceError> {
+        match outcome {
+            CommandOutcome::Created {
+                temp_id,
+                resource_type,
+                res,
+                parent_id,
+                ..
+            } => {
+                self.record_created(*resource_type, res, parent_id.clone())?;
+                self.resource_map.insert(temp_id.clone(), resource_id(res)?);
+            }
+            CommandOutcome::Updated {
+                temp_id,
+                resource_type,
+                id,
+                command_name,
+                data,
+                previous,
+                res,
+                ..
+            } => {
+                if *resource_type == ResourceType::FirewallRule {
+                    // The rule was replaced, later commands refer to the new one
+                    let replacement_id = resource_id(res)?;
+                    self.record_updated(
+                        *resource_type,
+                        &replacement_id,
+                        command_name,
+                        data.clone(),
+                        previous.clone(),
+                    );
+                    self.resource_map.insert(temp_id.clone(), replacement_id);
+                } else {
+                    self.record_updated(
+                        *resource_type,
+                        id,
+                        command_name,
+                        data.clone(),
+                        previous.clone(),
+                    );
+                }
+            }
+            CommandOutcome::Deleted {
+                resource_type, id, ..
+            } => self.record_deleted(*resource_type, id),
+            CommandOutcome::Moved { .. } => (),
+        }
+        Ok(())
+    }
+
+    /// Write a recorded outcome to the stored diagram.
+    pub async fn store(
+        &self,
+        outcome: CommandOutcome,
+        trx: &mut PgConnection,
+    ) -> Result<(), ServiceError> {
+        match outcome {
+            CommandOutcome::Created {
+                resource_type,
+                position,
+                res,
+                ..
+            } => insert_resource(resource_type, self.place(res, &position), trx).await,
+            CommandOutcome::Updated {
+                resource_type,
+                id,
+                position,
+                res,
+                ..
+            } => {
+                let res = self.place(res, &position);
+                if resource_type == ResourceType::FirewallRule {
+                    // The replacement takes over the row of the rule it replaced
+                    let firewall_rule = serde_json::from_value::<FirewallRule>(res)?;
+                    return update_firewall_rule(&parse_id(&id)?, &firewall_rule, trx).await;
+                }
+                update_resource(resource_type, res, trx).await
+            }
+            CommandOutcome::Deleted {
+                resource_type,
+                id,
+                parent_id,
+            } => {
+                delete_resource(
+                    resource_type,
+                    &self.project_id,
+                    &id,
+                    parent_id.as_deref(),
+                    trx,
+                )
+                .await
+            }
+            CommandOutcome::Moved {
+                resource_type,
+                id,
+                parent_id,
+                position,
+            } => {
+                move_resource(
+                    resource_type,
+                    &self.project_id,
+                    &id,
+                    parent_id.as_deref(),
+                    &position,
+                    trx,
+                )
+                .await
+            }
+        }
+    }
+}
+
+impl VultrExecutionContext {
+    /// Vultr answers without layout and ownership, take them from the deploy request.
+    fn place(&self, mut res: Value, position: &ObjectPosition) -> Value {
+        if let Some(fields) = res.as_object_mut() {
+            fields.insert("project_id".to_string(), json!(self.project_id));
+            fields.insert("x".to_string(), json!(position.x));
+            fields.insert("y".to_string(), json!(position.y));
+        }
+        res
+    }
+}
+
+pub async fn insert_resource(
+    resource_type: ResourceType,
+    res: Value,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    match resource_type {
+        ResourceType::Compute => {
+            insert_compute(&serde_json::from_value::<Compute>(res)?, trx).await
+        }
+        ResourceType::BlockStorage => {
+            insert_block_storage(&serde_json::from_value::<BlockStorage>(res)?, trx).await
+        }
+        ResourceType::FirewallGroup => {
+            insert_firewall_group(&serde_json::from_value::<FirewallGroup>(res)?, trx).await
+        }
+        ResourceType::FirewallRule => {
+            insert_firewall_rule(&serde_json::from_value::<FirewallRule>(res)?, trx).await
+        }
+        ResourceType::ManagedDatabase => {
+            insert_managed_database(&serde_json::from_value::<ManagedDatabase>(res)?, trx).await
+        }
+        ResourceType::ObjectStorage => {
+            insert_object_storage(&serde_json::from_value::<ObjectStorage>(res)?, trx).await
+        }
+        ResourceType::Vpc => insert_vpc(&serde_json::from_value::<Vpc>(res)?, trx).await,
+        ResourceType::LoadBalancer => {
+            insert_load_balancer(&serde_json::from_value::<LoadBalancer>(res)?, trx).await
+        }
+        ResourceType::DnsDomain => {
+            insert_dns_domain(&serde_json::from_value::<DnsDomain>(res)?, trx).await
+        }
+        ResourceType::DnsRecord => {
+            insert_dns_record(&serde_json::from_value::<DnsRecord>(res)?, trx).await
+        }
+        ResourceType::ComputeSnapshot => {
+            insert_compute_snapshot(&serde_json::from_value::<ComputeSnapshot>(res)?, trx).await
+        }
+        ResourceType::DatabaseUser => {
+            insert_database_user(&serde_json::from_value::<DatabaseUser>(res)?, trx).await
+        }
+        ResourceType::LogicalDatabase => {
+            insert_logical_database(&serde_json::from_value::<LogicalDatabase>(res)?, trx).await
+        }
+        ResourceType::ConnectionPool => {
+            insert_connection_pool(&serde_json::from_value::<ConnectionPool>(res)?, trx).await
+        }
+    }
+}
+
+pub async fn update_resource(
+    resource_type: ResourceType,
+    res: Value,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    match resource_type {
+        ResourceType::Compute => {
+            update_compute(&serde_json::from_value::<Compute>(res)?, trx).await
+        }
+        ResourceType::BlockStorage => {
+            update_block_storage(&serde_json::from_value::<BlockStorage>(res)?, trx).await
+        }
+        ResourceType::FirewallGroup => {
+            update_firewall_group(&serde_json::from_value::<FirewallGroup>(res)?, trx).await
+        }
+        ResourceType::ManagedDatabase => {
+            update_managed_database(&serde_json::from_value::<ManagedDatabase>(res)?, trx).await
+        }
+        ResourceType::ObjectStorage => {
+            update_object_storage(&serde_json::from_value::<ObjectStorage>(res)?, trx).await
+        }
+        ResourceType::FirewallRule => {
+            let firewall_rule = serde_json::from_value::<FirewallRule>(res)?;
+            update_firewall_rule(&firewall_rule.id, &firewall_rule, trx).await
+        }
+        ResourceType::Vpc => update_vpc(&serde_json::from_value::<Vpc>(res)?, trx).await,
+        ResourceType::LoadBalancer => {
+            update_load_balancer(&serde_json::from_value::<LoadBalancer>(res)?, trx).await
+        }
+        ResourceType::DnsDomain => {
+            update_dns_domain(&serde_json::from_value::<DnsDomain>(res)?, trx).await
+        }
+        ResourceType::DnsRecord => {
+            update_dns_record(&serde_json::from_value::<DnsRecord>(res)?, trx).await
+        }
+        ResourceType::ComputeSnapshot => {
+            update_compute_snapshot(&serde_json::from_value::<ComputeSnapshot>(res)?, trx).await
+        }
+        ResourceType::DatabaseUser => {
+            update_database_user(&serde_json::from_value::<DatabaseUser>(res)?, trx).await
+        }
+        ResourceType::LogicalDatabase => {
+            update_logical_database(&serde_json::from_value::<LogicalDatabase>(res)?, trx).await
+        }
+        ResourceType::ConnectionPool => {
+            update_connection_pool(&serde_json::from_value::<ConnectionPool>(res)?, trx).await
+        }
+    }
+}
+
+/// Stored id of a resource, a number for firewall rules and a UUID for most others.
+pub(crate) fn parse_id<T: FromStr>(id: &str) -> Result<T, ServiceError> {
+    T::from_str(id).map_err(|_| ServiceError::NotFound)
+}
+
+/// Group, domain or managed database a child resource is addressed through.
+pub(crate) fn required_parent(parent_id: Option<&str>) -> Result<&str, ServiceError> {
+    parent_id.ok_or(ServiceError::NotFound)
+}
+
+/// `parent_id` is the group of a firewall rule, the domain of a DNS record or the managed database of
+/// a user, logical database or connection pool. Other resources have none.
+pub async fn delete_resource(
+    resource_type: ResourceType,
+    project_id: &Uuid,
+    id: &str,
+    parent_id: Option<&str>,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    match resource_type {
+        ResourceType::Compute => delete_compute(project_id, &parse_id(id)?, trx).await,
+        ResourceType::BlockStorage => delete_block_storage(project_id, &parse_id(id)?, trx).await,
+        ResourceType::FirewallGroup => delete_firewall_group(project_id, &parse_id(id)?, trx).await,
+        ResourceType::FirewallRule => {
+            let firewall_group_id = parse_id(required_parent(parent_id)?)?;
+            delete_firewall_rule(project_id, &firewall_group_id, &parse_id(id)?, trx).await
+        }
+        ResourceType::ManagedDatabase => {
+            delete_managed_database(project_id, &parse_id(id)?, trx).await
+        }
+        ResourceType::ObjectStorage => delete_object_storage(project_id, &parse_id(id)?, trx).await,
+        ResourceType::Vpc => delete_vpc(project_id, &parse_id(id)?, trx).await,
+        ResourceType::LoadBalancer => delete_load_balancer(project_id, &parse_id(id)?, trx).await,
+        ResourceType::DnsDomain => delete_dns_domain(project_id, id, trx).await,
+        ResourceType::DnsRecord => {
+            let domain = required_parent(parent_id)?;
+            delete_dns_record(project_id, domain, &parse_id(id)?, trx).await
+        }
+        ResourceType::ComputeSnapshot => {
+            delete_compute_snapshot(project_id, &parse_id(id)?, trx).await
+        }
+        ResourceType::DatabaseUser => {
+            let database_id = parse_id(required_parent(parent_id)?)?;
+            delete_database_user(project_id, &database_id, id, trx).await
+        }
+        ResourceType::LogicalDatabase => {
+            let database_id = parse_id(required_parent(parent_id)?)?;
+            delete_logical_database(project_id, &database_id, id, trx).await
+        }
+        ResourceType::ConnectionPool => {
+            let database_id = parse_id(required_parent(parent_id)?)?;
+            delete_connection_pool(project_id, &database_id, id, trx).await
+        }
+    }
+}
+
+/// `parent_id` is the same as for `delete_resource`.
+async fn move_resource(
+    resource_type: ResourceType,
+    project_id: &Uuid,
+    id: &str,
+    parent_id: Option<&str>,
+    position: &ObjectPosition,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    match resource_type {
+        ResourceType::Compute => {
+            update_compute_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::BlockStorage => {
+            update_block_storage_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::FirewallGroup => {
+            update_firewall_group_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::FirewallRule => {
+            let firewall_group_id = parse_id(required_parent(parent_id)?)?;
+            update_firewall_rule_position(
+                project_id,
+                &firewall_group_id,
+                &parse_id(id)?,
+                position,
+                trx,
+            )
+            .await
+        }
+        ResourceType::ManagedDatabase => {
+            update_managed_database_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::ObjectStorage => {
+            update_object_storage_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::Vpc => update_vpc_position(project_id, &parse_id(id)?, position, trx).await,
+        ResourceType::LoadBalancer => {
+            update_load_balancer_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::DnsDomain => update_dns_domain_position(project_id, id, position, trx).await,
+        ResourceType::DnsRecord => {
+            let domain = required_parent(parent_id)?;
+            update_dns_record_position(project_id, domain, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::ComputeSnapshot => {
+            update_compute_snapshot_position(project_id, &parse_id(id)?, position, trx).await
+        }
+        ResourceType::DatabaseUser => {
+            let database_id = parse_id(required_parent(parent_id)?)?;
+            update_database_user_position(project_id, &database_id, id, position, trx).await
+        }
+        ResourceType::LogicalDatabase => {
+            let database_id = parse_id(required_parent(parent_id)?)?;
+            update_logical_database_position(project_id, &database_id, id, position, trx).await
+        }
+        ResourceType::ConnectionPool => {
+            let database_id = parse_id(required_parent(parent_id)?)?;
+            update_connection_pool_position(project_id, &database_id, id, position, trx).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_vultr_command_from_request() {
+        // GIVEN
+        let request: CommandRequest = serde_json::from_value(json!({
+            "command_name": "CreateFirewallGroup",
+            "temp_id": "firewall-1",
+            "position": { "x": 0, "y": 0 },
+            "data": { "description": "web" }
+        }))
+        .unwrap();
+
+        // WHEN
+        let command = VultrCommand::try_from(&request).unwrap();
+
+        // THEN
+        assert!(matches!(command, VultrCommand::CreateFirewallGroup(_)));
+        assert_eq!(command.kind(), CommandKind::Create);
+        assert_eq!(command.resource_type(), ResourceType::FirewallGroup);
+        assert_eq!(command.name(), "CreateFirewallGroup");
+    }
+
+    #[test]
+    fn test_unknown_command_is_rejected() {
+        // Substring matches of a known name used to be routed to that command
+        let err = VultrCommand::new("CreateComputeUpdate", json!({}))
+            .err()
+            .unwrap();
+        let ServiceError::InvalidCommand(message) = err else {
+            panic!("unexpected error: {:?}", err);
+        };
+        assert!(message.contains("CreateComputeUpdate"));
+        assert!(message.contains("unknown variant"));
+    }
+
+    #[test]
+    fn test_every_command_is_described() {
+        // GIVEN
+        // serde names every variant it knows when it meets an unknown one
+        let err = VultrCommand::new("NoSuchCommand", json!({})).err().unwrap();
+        let ServiceError::InvalidCommand(message) = err else {
+            panic!("unexpected error: {:?}", err);
+        };
+        let (_, expected) = message.split_once("expected one of").unwrap();
+        let variants: BTreeSet<&str> = expected.split('`').skip(1).step_by(2).collect();
+
+        // WHEN
+        let described: BTreeSet<&str> = COMMANDS.iter().map(|(name, _, _)| *name).collect();
+
+        // THEN
+        // A move changes no resource of its own, its kind and type come from its data
+        assert_eq!(
+            variants.difference(&described).collect::<Vec<_>>(),
+            vec![&"MoveResource"]
+        );
+        assert!(described.is_subset(&variants));
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        // GIVEN
+        let group_id = Uuid::new_v4();
+        let request: CommandRequest = serde_json::from_value(json!({
+            "command_name": "DeleteFirewallRule",
+            "temp_id": "rule-1",
+            "position": { "x": 0, "y": 0 },
+            "data": {
+                "firewall_group_id": { "$ref": "group-1" },
+                "firewall_rule_id": { "$ref": "rule-1" }
+            }
+        }))
+        .unwrap();
+        let ids = HashMap::from([
+            ("group-1".to_string(), group_id.to_string()),
+            ("rule-1".to_string(), "3".to_string()),
+        ]);
+        let created_types = HashMap::from([
+            ("group-1".to_string(), ResourceType::FirewallGroup),
+            ("rule-1".to_string(), ResourceType::FirewallRule),
+        ]);
+
+        // WHEN
+        let (command, data) = VultrCommand::resolve(&request, &ids, &created_types).unwrap();
+
+        // THEN
+        assert!(matches!(command, VultrCommand::DeleteFirewallRule(_)));
+        assert_eq!(
+            data,
+            json!({ "firewall_group_id": group_id.to_string(), "firewall_rule_id": 3 })
+        );
+        assert_eq!(
+            references(&request.data),
+            vec![
+                (Some("firewall_group_id"), "group-1"),
+                (Some("firewall_rule_id"), "rule-1")
+            ]
+        );
+        assert!(matches!(
+            VultrCommand::try_from(&request),
+            Err(ServiceError::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_numeric_id_of_other_resource() {
+        // GIVEN
+        // Only firewall rule ids are numbers at Vultr, a domain may look like one
+        let data = json!({ "domain": { "$ref": "domain-1" } });
+        let ids = HashMap::from([("domain-1".to_string(), "20251017".to_string())]);
+        let created_types = HashMap::from([("domain-1".to_string(), ResourceType::DnsDomain)]);
+
+        // WHEN
+        let resolved = resolve_references(&data, &ids, &created_types).unwrap();
+
+        // THEN
+        assert_eq!(resolved, json!({ "domain": "20251017" }));
+    }
+
+    #[test]
+    fn test_invalid_data_is_rejected() {
+        assert!(matches!(
+            VultrCommand::new("CreateFirewallGroup", json!({ "label": "web" })),
+            Err(ServiceError::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_move_of_child_resource_needs_parent() {
+        // GIVEN
+        let group_id = Uuid::new_v4();
+
+        // WHEN
+        let without_parent = VultrCommand::new(
+            "MoveResource",
+            json!({ "resource_type": "FirewallRule", "id": 3 }),
+        );
+        let with_parent = VultrCommand::new(
+            "MoveResource",
+            json!({ "resource_type": "FirewallRule", "id": 3, "parent_id": group_id }),
+        )
+        .unwrap();
+
+        // THEN
+        assert!(
+            matches!(without_parent, Err(ServiceError::InvalidCommand(msg)) if msg.contains("parent_id"))
+        );
+        assert_eq!(with_parent.parent_id(), Some(group_id.to_string()));
+        assert!(VultrCommand::new(
+            "MoveResource",
+            json!({ "resource_type": "FirewallGroup", "id": group_id }),
+        )
+        .is_ok());
+    }
+}
@@ -1,5 +1,10 @@
 use std::fmt::Debug;
 
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::adapter::request_dispensor::vultr::schemas::VultrApiError;
+
 #[derive(Debug)]
 pub enum ServiceError {
     _InternalServerError,
@@ -18,4 +23,10 @@ pub enum ServiceError {
     RequestError(Box<dyn Debug + Send>),
     ParseError,
     PemKeyError(String),
+    InvalidCommand(String),
+    VultrApiError(VultrApiError),
+    // Id of the project's deploy job that hasn't finished yet
+    DeployInProgress(Uuid),
+    // Cause of a failed deploy and the report of the Vultr changes that were undone
+    DeploymentFailed(Box<ServiceError>, Value),
 }
@@ -7,6 +7,7 @@ use adapter::{
 };
 use axum::Router;
 use reqwest::Method;
+use service::project::fail_interrupted_deploy_jobs;
 use std::{env, net::SocketAddr};
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
@@ -28,6 +29,9 @@ async fn main() {
         .run(connection_pool())
         .await
         .expect("Running Migration Script Failed!");
+    fail_interrupted_deploy_jobs()
+        .await
+        .expect("Failing Interrupted Deploy Jobs Failed!");
 
     tracing_subscriber::registry()
         .with(
@@ -1,3 +1,4 @@
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use crate::{
@@ -5,33 +6,43 @@ use crate::{
         kv_store::{interfaces::KVStore, rocks_db::get_rocks_db},
         mail::{send_email, Email, EmailType},
         repositories::{
-            auth::{get_user_account_by_email, insert_user_account, update_user_account},
+            auth::{
+                get_refresh_token, get_user_account_by_email, insert_refresh_token,
+                insert_user_account, revoke_refresh_token_family, revoke_user_refresh_tokens,
+                update_user_account, use_refresh_token,
+            },
             connection_pool,
             interfaces::TExecutor,
             SqlExecutor,
         },
     },
     domain::auth::{
-        commands::{CheckVerification, CreateUserAccount, IssueTokens, RefreshTokens},
+        commands::{
+            ChangePassword, CheckVerification, CreateUserAccount, IssueTokens, Logout,
+            RefreshTokens, RequestPasswordReset, ResetPassword,
+        },
+        get_password_reset_key,
         jwt::JwtToken,
-        AuthenticationTokens, UserAccountAggregate, VerificationCode,
+        AuthenticationTokens, RefreshTokenEntity, UserAccountAggregate, VerificationCode,
     },
     errors::ServiceError,
+    CurrentUser,
 };
 // TODO refactor to use repository instead of executor
 pub async fn handle_create_user_account(command: CreateUserAccount) -> Result<Uuid, ServiceError> {
-    let ext = SqlExecutor::new();
-    ext.write().await.begin().await?;
-
     let code = VerificationCode::new();
     let email = Email::new(
         command.email.clone(),
         EmailType::VerificationCode(&code.code),
     );
-    let user = UserAccountAggregate::from(command);
+    // Hash before taking a connection so the transaction isn't held open during Argon2
+    let user = UserAccountAggregate::from_command(command).await?;
 
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
     insert_user_account(&user, ext.write().await.transaction()).await?;
     ext.write().await.commit().await?;
+    ext.write().await.close().await;
 
     // Insert Data to KVStore
     let rocks_db = get_rocks_db().await;
@@ -66,46 +77,214 @@ pub async fn handle_issue_tokens(
     command: IssueTokens,
 ) -> Result<AuthenticationTokens, ServiceError> {
     let ext = SqlExecutor::new();
-    let user = get_user_account_by_email(&command.email, connection_pool()).await?;
+    let mut user = get_user_account_by_email(&command.email, connection_pool()).await?;
     if !user.verified {
         return Err(ServiceError::UserNotVerified);
-    } else if user.password != command.password {
-        return Err(ServiceError::Unauthorized);
+    }
+    user.verify_password(&command.password).await?;
+    // Hash before taking a connection, the transaction only stores the result
+    let rehash = user.has_legacy_password();
+    if rehash {
+        user.set_password(&command.password).await?;
     }
     ext.write().await.begin().await?;
+    if rehash {
+        update_user_account(&user, ext.write().await.transaction()).await?;
+    }
 
-    let jwt = JwtToken::new();
-    let access_token = jwt.generate_access_token(user.id, &command.email)?;
-    let refresh_token = jwt.generate_refresh_token(user.id, &command.email)?;
-
-    let tokens = AuthenticationTokens::new(access_token, refresh_token);
-
+    // Every login starts a new token family
+    let tokens = issue_tokens(
+        &user.id,
+        &command.email,
+        Uuid::new_v4(),
+        ext.write().await.transaction(),
+    )
+    .await?;
+    ext.write().await.commit().await?;
     Ok(tokens)
 }
 
+/// Trade a refresh token for a new pair. The refresh token is single use, presenting it again
+/// revokes every token of its family.
 pub async fn handle_refresh_tokens(
     command: RefreshTokens,
 ) -> Result<AuthenticationTokens, ServiceError> {
+    let jwt = JwtToken::new();
+    let claims = jwt.verify_refresh_token(&command.refresh_token)?;
+    let refresh_token = get_refresh_token(&claims.jti, connection_pool())
+        .await
+        .map_err(|_| ServiceError::InvalidJwtToken)?;
+
     let ext = SqlExecutor::new();
     ext.write().await.begin().await?;
-    let jwt = JwtToken::new();
-    let claims = jwt.verify_token(&command.refresh_token)?;
-    let access_token = jwt.generate_access_token(claims.user_id, &claims.email)?;
+    if !use_refresh_token(&refresh_token.jti, ext.write().await.transaction()).await? {
+        if !refresh_token.revoked {
+            tracing::warn!(
+                "Refresh token of user {} was reused, revoking its family",
+                refresh_token.user_id
+            );
+            revoke_refresh_token_family(&refresh_token.family_id, ext.write().await.transaction())
+                .await?;
+            ext.write().await.commit().await?;
+        }
+        return Err(ServiceError::InvalidJwtToken);
+    }
+    let tokens = issue_tokens(
+        &claims.user_id,
+        &claims.email,
+        refresh_token.family_id,
+        ext.write().await.transaction(),
+    )
+    .await?;
+    ext.write().await.commit().await?;
+    Ok(tokens)
+}
 
-    let tokens = AuthenticationTokens::new(access_token, command.refresh_token);
+/// Access tokens already handed out stay valid until they expire.
+pub async fn handle_logout(command: Logout) -> Result<(), ServiceError> {
+    let claims = JwtToken::new().verify_refresh_token(&command.refresh_token)?;
+    let refresh_token = get_refresh_token(&claims.jti, connection_pool())
+        .await
+        .map_err(|_| ServiceError::InvalidJwtToken)?;
 
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    revoke_refresh_token_family(&refresh_token.family_id, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    Ok(())
+}
+
+/// Log out of every session of the current user.
+pub async fn handle_logout_all(current_user: CurrentUser) -> Result<(), ServiceError> {
+    let user = get_user_account_by_email(&current_user.email, connection_pool()).await?;
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    revoke_user_refresh_tokens(&user.id, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    Ok(())
+}
+
+/// Email a one-time code to reset the password with. Unknown emails get the same answer so that
+/// accounts can't be probed.
+pub async fn handle_request_password_reset(
+    command: RequestPasswordReset,
+) -> Result<(), ServiceError> {
+    let user = match get_user_account_by_email(&command.email, connection_pool()).await {
+        Ok(user) => user,
+        Err(ServiceError::NotFound) => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    let code = VerificationCode::new();
+    get_rocks_db()
+        .await
+        .insert(
+            get_password_reset_key(&user.email).as_bytes(),
+            &code.to_bytes()?,
+        )
+        .await?;
+    send_email(Email::new(
+        user.email.clone(),
+        EmailType::PasswordResetCode(&code.code),
+    ))
+    .await
+}
+
+/// The code is used up by the first attempt, right or wrong.
+pub async fn handle_reset_password(command: ResetPassword) -> Result<(), ServiceError> {
+    let rocks_db = get_rocks_db().await;
+    let code = VerificationCode::from_bytes(
+        &rocks_db
+            .pop(get_password_reset_key(&command.email).as_bytes())
+            .await
+            .map_err(|_| ServiceError::InvalidVerificationCode)?,
+    )?;
+    code.verify_code(&command.code)?;
+
+    let mut user = get_user_account_by_email(&command.email, connection_pool()).await?;
+    user.set_password(&command.new_password).await?;
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    replace_password(&user, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+
+    notify_password_changed(&user.email).await;
+    Ok(())
+}
+
+/// Every other session is signed out, the caller continues with the returned tokens.
+pub async fn handle_change_password(
+    command: ChangePassword,
+    current_user: CurrentUser,
+) -> Result<AuthenticationTokens, ServiceError> {
+    let mut user = get_user_account_by_email(&current_user.email, connection_pool()).await?;
+    user.verify_password(&command.current_password).await?;
+    user.set_password(&command.new_password).await?;
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    replace_password(&user, ext.write().await.transaction()).await?;
+    let tokens = issue_tokens(
+        &user.id,
+        &user.email,
+        Uuid::new_v4(),
+        ext.write().await.transaction(),
+    )
+    .await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+
+    notify_password_changed(&user.email).await;
     Ok(tokens)
 }
 
+/// Store the new password and revoke every refresh token issued with the old one.
+async fn replace_password(
+    user: &UserAccountAggregate,
+    trx: &mut PgConnection,
+) -> Result<(), ServiceError> {
+    update_user_account(user, trx).await?;
+    revoke_user_refresh_tokens(&user.id, trx).await
+}
+
+/// The password is changed by the time this is sent, a failed email must not fail the request.
+async fn notify_password_changed(email: &str) {
+    if let Err(err) = send_email(Email::new(email.to_string(), EmailType::PasswordChanged)).await {
+        tracing::error!("Failed to send password changed email to {email}: {err:?}");
+    }
+}
+
+async fn issue_tokens(
+    user_id: &Uuid,
+    email: &str,
+    family_id: Uuid,
+    trx: &mut PgConnection,
+) -> Result<AuthenticationTokens, ServiceError> {
+    let refresh_token = RefreshTokenEntity::new(*user_id, family_id);
+    insert_refresh_token(&refresh_token, trx).await?;
+
+    let jwt = JwtToken::new();
+    Ok(AuthenticationTokens::new(
+        jwt.generate_access_token(*user_id, email)?,
+        jwt.generate_refresh_token(*user_id, email, refresh_token.jti)?,
+    ))
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
     use chrono::{Duration, Utc};
 
+    // Password of the accounts created by `create_user_account_helper`
+    pub const TEST_PASSWORD: &str = "password123";
+
     pub async fn create_user_account_helper() -> UserAccountAggregate {
         let cmd = CreateUserAccount {
             email: format!("{}@test.com", Uuid::new_v4()),
-            password: format!("password{}", Uuid::new_v4()),
+            password: TEST_PASSWORD.to_string(),
             name: "Test User".to_string(),
             phone_num: "01012345678".to_string(),
         };
@@ -134,11 +313,13 @@ pub(crate) mod tests {
         assert_eq!(user.email, cmd.email);
         assert_eq!(user.name, cmd.name);
         assert_eq!(user.phone_num, cmd.phone_num);
-        assert_eq!(user.password, cmd.password);
-        assert_eq!(user.verified, false);
+        assert_ne!(user.password, cmd.password);
+        assert!(!user.has_legacy_password());
+        assert!(user.verify_password(&cmd.password).await.is_ok());
+        assert!(!user.verified);
         let rocks_db = get_rocks_db().await;
         let verfication_code =
-            VerificationCode::from_bytes(&rocks_db.get(&cmd.email.as_bytes()).await.unwrap())
+            VerificationCode::from_bytes(&rocks_db.get(cmd.email.as_bytes()).await.unwrap())
                 .unwrap();
         assert!(verfication_code.expires_at > Utc::now());
     }
@@ -149,7 +330,7 @@ pub(crate) mod tests {
         let rocks_db = get_rocks_db().await;
         let user_account = create_user_account_helper().await;
         let verfication_code = VerificationCode::from_bytes(
-            &rocks_db.get(&user_account.email.as_bytes()).await.unwrap(),
+            &rocks_db.get(user_account.email.as_bytes()).await.unwrap(),
         )
         .unwrap();
 
@@ -163,13 +344,13 @@ pub(crate) mod tests {
 
         // THEN
         assert!(matches!(
-            rocks_db.get(&cmd.email.as_bytes()).await.unwrap_err(),
+            rocks_db.get(cmd.email.as_bytes()).await.unwrap_err(),
             ServiceError::NotFound
         ));
         let user = get_user_account_by_email(&cmd.email, connection_pool())
             .await
             .unwrap();
-        assert_eq!(user.verified, true);
+        assert!(user.verified);
     }
 
     #[tokio::test]
@@ -178,13 +359,13 @@ pub(crate) mod tests {
         let rocks_db = get_rocks_db().await;
         let user_account = create_user_account_helper().await;
         let mut verfication_code = VerificationCode::from_bytes(
-            &rocks_db.pop(&user_account.email.as_bytes()).await.unwrap(),
+            &rocks_db.pop(user_account.email.as_bytes()).await.unwrap(),
         )
         .unwrap();
         verfication_code.expires_at = Utc::now() - Duration::minutes(1);
         rocks_db
             .insert(
-                &user_account.email.as_bytes(),
+                user_account.email.as_bytes(),
                 &verfication_code.to_bytes().unwrap(),
             )
             .await
@@ -209,13 +390,13 @@ pub(crate) mod tests {
         let user_account = create_user_account_helper().await;
         let issue_tokens_cmd = IssueTokens {
             email: user_account.email.clone(),
-            password: user_account.password.clone(),
+            password: TEST_PASSWORD.to_string(),
         };
         let user = get_user_account_by_email(&user_account.email, connection_pool())
             .await
             .unwrap();
         let verfication_code = VerificationCode::from_bytes(
-            &rocks_db.get(&user_account.email.as_bytes()).await.unwrap(),
+            &rocks_db.get(user_account.email.as_bytes()).await.unwrap(),
         )
         .unwrap();
 
@@ -243,6 +424,67 @@ pub(crate) mod tests {
         assert!(claims.user_id == user.id);
     }
 
+    #[tokio::test]
+    async fn test_issue_tokens_with_wrong_password() {
+        // GIVEN
+        let user_account = create_user_account_helper().await;
+        let mut user = user_account.clone();
+        user.set_account_verified();
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        update_user_account(&user, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+
+        // WHEN
+        let result = handle_issue_tokens(IssueTokens {
+            email: user_account.email.clone(),
+            password: format!("{}4", TEST_PASSWORD),
+        })
+        .await;
+
+        // THEN
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_rehashes_legacy_password() {
+        // GIVEN
+        let legacy_password = format!("password{}", Uuid::new_v4());
+        let user_account = UserAccountAggregate {
+            id: Uuid::new_v4(),
+            email: format!("{}@test.com", Uuid::new_v4()),
+            name: "Legacy User".to_string(),
+            phone_num: "01012345678".to_string(),
+            password: legacy_password.clone(),
+            verified: true,
+            create_dt: Utc::now(),
+        };
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        insert_user_account(&user_account, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        let cmd = IssueTokens {
+            email: user_account.email.clone(),
+            password: legacy_password.clone(),
+        };
+
+        // WHEN
+        handle_issue_tokens(cmd.clone()).await.unwrap();
+
+        // THEN
+        let user = get_user_account_by_email(&user_account.email, connection_pool())
+            .await
+            .unwrap();
+        assert_ne!(user.password, legacy_password);
+        assert!(!user.has_legacy_password());
+        // The rehashed password keeps working
+        handle_issue_tokens(cmd).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_refresh_tokens() {
         // GIVEN
@@ -253,7 +495,7 @@ pub(crate) mod tests {
         // Verify email
         let rocks_db = get_rocks_db().await;
         let verfication_code = VerificationCode::from_bytes(
-            &rocks_db.get(&user_account.email.as_bytes()).await.unwrap(),
+            &rocks_db.get(user_account.email.as_bytes()).await.unwrap(),
         )
         .unwrap();
         let verify_cmd = CheckVerification {
@@ -265,7 +507,7 @@ pub(crate) mod tests {
         // Get initial tokens
         let issue_cmd = IssueTokens {
             email: user_account.email.clone(),
-            password: user_account.password.clone(),
+            password: TEST_PASSWORD.to_string(),
         };
 
         // WHEN
@@ -293,6 +535,323 @@ pub(crate) mod tests {
         assert_eq!(new_access_claims.user_id, initial_access_claims.user_id);
         assert_eq!(new_refresh_claims.user_id, initial_refresh_claims.user_id);
         assert!(new_access_claims.exp > Utc::now().timestamp());
-        assert_eq!(new_tokens.refresh_token, initial_tokens.refresh_token);
+        // Rotated into the same family
+        assert_ne!(new_refresh_claims.jti, initial_refresh_claims.jti);
+        let initial_row = get_refresh_token(&initial_refresh_claims.jti, connection_pool())
+            .await
+            .unwrap();
+        let new_row = get_refresh_token(&new_refresh_claims.jti, connection_pool())
+            .await
+            .unwrap();
+        assert!(initial_row.used_dt.is_some());
+        assert_eq!(new_row.family_id, initial_row.family_id);
+    }
+
+    async fn login_helper() -> (UserAccountAggregate, AuthenticationTokens) {
+        let user_account = create_user_account_helper().await;
+        let code = VerificationCode::from_bytes(
+            &get_rocks_db()
+                .await
+                .get(user_account.email.as_bytes())
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        handle_check_verification_email(CheckVerification {
+            email: user_account.email.clone(),
+            verification_code: code.code,
+        })
+        .await
+        .unwrap();
+        let tokens = handle_issue_tokens(IssueTokens {
+            email: user_account.email.clone(),
+            password: TEST_PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+        (user_account, tokens)
+    }
+
+    fn refresh(tokens: &AuthenticationTokens) -> RefreshTokens {
+        RefreshTokens {
+            refresh_token: tokens.refresh_token.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_reuse_revokes_family() {
+        // GIVEN
+        let (_, tokens) = login_helper().await;
+        let (_, other_session) = login_helper().await;
+        let rotated = handle_refresh_tokens(refresh(&tokens)).await.unwrap();
+
+        // WHEN
+        let reused = handle_refresh_tokens(refresh(&tokens)).await;
+
+        // THEN
+        assert!(matches!(reused, Err(ServiceError::InvalidJwtToken)));
+        // The token rotated from it went down with the family
+        assert!(matches!(
+            handle_refresh_tokens(refresh(&rotated)).await,
+            Err(ServiceError::InvalidJwtToken)
+        ));
+        assert!(handle_refresh_tokens(refresh(&other_session)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_access_token_is_not_a_refresh_token() {
+        // GIVEN
+        let (_, tokens) = login_helper().await;
+
+        // WHEN
+        let result = handle_refresh_tokens(RefreshTokens {
+            refresh_token: tokens.access_token.clone(),
+        })
+        .await;
+
+        // THEN
+        assert!(matches!(result, Err(ServiceError::InvalidJwtToken)));
+    }
+
+    #[tokio::test]
+    async fn test_logout() {
+        // GIVEN
+        let (user_account, tokens) = login_helper().await;
+        let other_session = handle_issue_tokens(IssueTokens {
+            email: user_account.email.clone(),
+            password: TEST_PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+
+        // WHEN
+        handle_logout(Logout {
+            refresh_token: tokens.refresh_token.clone(),
+        })
+        .await
+        .unwrap();
+
+        // THEN
+        assert!(matches!(
+            handle_refresh_tokens(refresh(&tokens)).await,
+            Err(ServiceError::InvalidJwtToken)
+        ));
+        assert!(handle_refresh_tokens(refresh(&other_session)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_logout_all() {
+        // GIVEN
+        let (user_account, tokens) = login_helper().await;
+        let other_session = handle_issue_tokens(IssueTokens {
+            email: user_account.email.clone(),
+            password: TEST_PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+        let (_, other_user_tokens) = login_helper().await;
+
+        // WHEN
+        handle_logout_all(CurrentUser {
+            email: user_account.email.clone(),
+        })
+        .await
+        .unwrap();
+
+        // THEN
+        for tokens in [&tokens, &other_session] {
+            assert!(matches!(
+                handle_refresh_tokens(refresh(tokens)).await,
+                Err(ServiceError::InvalidJwtToken)
+            ));
+        }
+        assert!(handle_refresh_tokens(refresh(&other_user_tokens))
+            .await
+            .is_ok());
+    }
+
+    async fn password_reset_code_helper(email: &str) -> VerificationCode {
+        handle_request_password_reset(RequestPasswordReset {
+            email: email.to_string(),
+        })
+        .await
+        .unwrap();
+        VerificationCode::from_bytes(
+            &get_rocks_db()
+                .await
+                .get(get_password_reset_key(email).as_bytes())
+                .await
+                .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reset_password() {
+        // GIVEN
+        let (user_account, tokens) = login_helper().await;
+        let code = password_reset_code_helper(&user_account.email).await;
+        let cmd = ResetPassword {
+            email: user_account.email.clone(),
+            code: code.code.clone(),
+            new_password: "new-password123".to_string(),
+        };
+
+        // WHEN
+        handle_reset_password(cmd.clone()).await.unwrap();
+
+        // THEN
+        let login = |password: &str| IssueTokens {
+            email: user_account.email.clone(),
+            password: password.to_string(),
+        };
+        assert!(matches!(
+            handle_issue_tokens(login(TEST_PASSWORD)).await,
+            Err(ServiceError::Unauthorized)
+        ));
+        assert!(handle_issue_tokens(login(&cmd.new_password)).await.is_ok());
+        assert!(matches!(
+            handle_refresh_tokens(refresh(&tokens)).await,
+            Err(ServiceError::InvalidJwtToken)
+        ));
+        // The code is used up
+        assert!(matches!(
+            handle_reset_password(cmd).await,
+            Err(ServiceError::InvalidVerificationCode)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reset_password_with_wrong_code() {
+        // GIVEN
+        let (user_account, tokens) = login_helper().await;
+        let code = password_reset_code_helper(&user_account.email).await;
+        let wrong_code = ResetPassword {
+            email: user_account.email.clone(),
+            code: format!("{}0", code.code),
+            new_password: "new-password123".to_string(),
+        };
+
+        // WHEN
+        let result = handle_reset_password(wrong_code.clone()).await;
+
+        // THEN
+        assert!(matches!(result, Err(ServiceError::InvalidVerificationCode)));
+        // A failed attempt burns the code as well
+        assert!(matches!(
+            handle_reset_password(ResetPassword {
+                code: code.code,
+                ..wrong_code
+            })
+            .await,
+            Err(ServiceError::InvalidVerificationCode)
+        ));
+        assert!(handle_refresh_tokens(refresh(&tokens)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_request_password_reset_for_unknown_email() {
+        // GIVEN
+        let email = format!("{}@test.com", Uuid::new_v4());
+
+        // WHEN
+        let result = handle_request_password_reset(RequestPasswordReset {
+            email: email.clone(),
+        })
+        .await;
+
+        // THEN
+        assert!(result.is_ok());
+        assert!(matches!(
+            get_rocks_db()
+                .await
+                .get(get_password_reset_key(&email).as_bytes())
+                .await,
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_password() {
+        // GIVEN
+        let (user_account, tokens) = login_helper().await;
+        let current_user = CurrentUser {
+            email: user_account.email.clone(),
+        };
+
+        // WHEN
+        let wrong_current = handle_change_password(
+            ChangePassword {
+                current_password: "wrong-password".to_string(),
+                new_password: "new-password123".to_string(),
+            },
+            current_user.clone(),
+        )
+        .await;
+        let new_tokens = handle_change_password(
+            ChangePassword {
+                current_password: TEST_PASSWORD.to_string(),
+                new_password: "new-password123".to_string(),
+            },
+            current_user,
+        )
+        .await
+        .unwrap();
+
+        // THEN
+        assert!(matches!(wrong_current, Err(ServiceError::Unauthorized)));
+        assert!(matches!(
+            handle_refresh_tokens(refresh(&tokens)).await,
+            Err(ServiceError::InvalidJwtToken)
+        ));
+        assert!(handle_refresh_tokens(refresh(&new_tokens)).await.is_ok());
+        assert!(handle_issue_tokens(IssueTokens {
+            email: user_account.email.clone(),
+            password: "new-password123".to_string(),
+        })
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_change_password_when_notification_fails() {
+        // GIVEN
+        // The address is stored as given but can't be mailed to
+        let user = UserAccountAggregate::from_command(CreateUserAccount {
+            email: format!("{} @test.com", Uuid::new_v4()),
+            password: TEST_PASSWORD.to_string(),
+            name: "Test User".to_string(),
+            phone_num: "01012345678".to_string(),
+        })
+        .await
+        .unwrap();
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        insert_user_account(&user, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        assert!(
+            send_email(Email::new(user.email.clone(), EmailType::PasswordChanged))
+                .await
+                .is_err()
+        );
+
+        // WHEN
+        let tokens = handle_change_password(
+            ChangePassword {
+                current_password: TEST_PASSWORD.to_string(),
+                new_password: "new-password123".to_string(),
+            },
+            CurrentUser {
+                email: user.email.clone(),
+            },
+        )
+        .await
+        .unwrap();
+
+        // THEN
+        assert!(handle_refresh_tokens(refresh(&tokens)).await.is_ok());
     }
 }
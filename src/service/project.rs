@@ -1,10 +1,17 @@
-use crate::adapter::kv_store::interfaces::{KVStore, VultrKeyPairStore};
+use crate::adapter::kv_store::interfaces::{DeployJobStore, KVStore, VultrKeyPairStore};
 use crate::adapter::kv_store::rocks_db::get_rocks_db;
 use crate::adapter::mail::{send_email, Email, EmailType};
 use crate::adapter::repositories::interfaces::TExecutor;
 use crate::adapter::repositories::project::diagram::{
-    list_block_storage, list_compute, list_firewall_group, list_firewall_rule,
-    list_managed_database, list_object_storage,
+    list_block_storage, list_compute, list_compute_snapshot, list_connection_pool,
+    list_database_user, list_dns_domain, list_dns_record, list_firewall_group, list_firewall_rule,
+    list_load_balancer, list_logical_database, list_managed_database, list_object_storage,
+    list_vpc,
+};
+use crate::adapter::repositories::project::provisioning::{
+    delete_ssh_key, delete_startup_script, get_object_storage_credential, get_ssh_key,
+    get_startup_script, insert_ssh_key, insert_startup_script, list_ssh_key, list_startup_script,
+    update_ssh_key, update_startup_script, upsert_object_storage_credential,
 };
 use crate::adapter::repositories::project::workspace::{
     delete_project, delete_user_role, get_project, get_user_role, get_vult_api_key, insert_project,
@@ -14,18 +21,48 @@ use crate::adapter::repositories::{connection_pool, SqlExecutor};
 use crate::adapter::request_dispensor::architector_server::{
     request_architecture_recommendation, ArchitectureRecommendation, RequestArchitectureSuggestion,
 };
-use crate::adapter::request_dispensor::vultr::get_vultr_client;
+use crate::adapter::request_dispensor::s3::S3Client;
+use crate::adapter::request_dispensor::vultr::interfaces::{
+    ExecuteVultrCreateCommand, ExecuteVultrDeleteCommand, ExecuteVultrGetCommand,
+    ExecuteVultrUpdateCommand,
+};
+use crate::adapter::request_dispensor::vultr::schemas::object_storage::{
+    GetObjectStorageCredentials, RegenerateObjectStorageKeys,
+};
+use crate::adapter::request_dispensor::vultr::schemas::ssh_key::{
+    CreateSshKey, DeleteSshKey, UpdateSshKey,
+};
+use crate::adapter::request_dispensor::vultr::schemas::startup_script::{
+    CreateStartupScript, DeleteStartupScript, UpdateStartupScript,
+};
+use crate::adapter::request_dispensor::vultr::{evict_vultr_client, get_vultr_client, VultrClient};
+use crate::config::get_config;
 use crate::domain::project::commands::{
-    AssignRole, DeleteProject, DeployProject, ExpelMember, RegisterVultApiKey, ResourceResponse,
+    AssignRole, CreateBucket, DeleteProject, DeployProject, EditSshKey, EditStartupScript,
+    ExpelMember, RegisterSshKey, RegisterStartupScript, RegisterVultApiKey, ResourceResponse,
+};
+use crate::domain::project::dependency::DependencyGraph;
+use crate::domain::project::deploy_job::DeployJob;
+use crate::domain::project::diagrams::{get_diagram_key, get_diagram_update_dt, DiagramSnapshot};
+use crate::domain::project::drift::DriftReport;
+use crate::domain::project::import::{ImportCandidate, ImportResources, LiveInventory};
+use crate::domain::project::object_storage::{
+    verify_bucket_name, Bucket, ObjectStorageCredential, S3Credentials,
+};
+use crate::domain::project::plan::DeployPlan;
+use crate::domain::project::provisioning::{verify_registered, SshKey, StartupScript};
+use crate::domain::project::readiness::ReadinessWatch;
+use crate::domain::project::vultr_command::{
+    created_types, placeholder_ids, CommandOutcome, VultrCommand,
 };
-use crate::domain::project::diagrams::{get_diagram_key, get_diagram_update_dt};
-use crate::domain::project::enums::ResourceType;
 use crate::domain::project::{commands::CreateProject, ProjectAggregate};
 use crate::domain::project::{UserRole, UserRoleEntity, VultApiKeyEntity, VultrExecutionContext};
 use crate::errors::ServiceError;
 use crate::CurrentUser;
 use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 pub async fn handle_create_project(
@@ -54,6 +91,7 @@ pub async fn handle_delete_project(cmd: DeleteProject) -> Result<(), ServiceErro
 
     ext.write().await.commit().await?;
     ext.write().await.close().await;
+    evict_vultr_client(&cmd.project_id);
     Ok(())
 }
 
@@ -132,6 +170,7 @@ pub async fn handle_register_vultr_api_key(
     .await?;
     ext.write().await.commit().await?;
     ext.write().await.close().await;
+    evict_vultr_client(&cmd.project_id);
     Ok(())
 }
 
@@ -144,7 +183,12 @@ pub async fn handle_session_sse(
     let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
 
     let key = get_diagram_update_dt(project_id);
-    let update_dt = rocks_db.get(key.as_bytes()).await?;
+    let update_dt = match rocks_db.get(key.as_bytes()).await {
+        Ok(update_dt) => update_dt,
+        // Nothing has been deployed yet
+        Err(ServiceError::NotFound) => return Ok(None),
+        Err(err) => return Err(err),
+    };
     let update_dt =
         String::from_utf8(update_dt).map_err(|err| ServiceError::ParsingError(Box::new(err)))?;
     let update_dt = DateTime::parse_from_rfc3339(&update_dt)
@@ -161,37 +205,237 @@ pub async fn handle_session_sse(
     }
 }
 
+pub async fn handle_deploy_job_sse(
+    current_user: &CurrentUser,
+    project_id: Uuid,
+    last_update_dt: &mut DateTime<Utc>,
+) -> Result<Option<DeployJob>, ServiceError> {
+    let rocks_db = get_rocks_db().await;
+    let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
+
+    let job = match rocks_db.get_latest_deploy_job(project_id).await {
+        Ok(job) => job,
+        Err(ServiceError::NotFound) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    if job.update_dt > *last_update_dt {
+        *last_update_dt = job.update_dt;
+        Ok(Some(job))
+    } else {
+        Ok(None)
+    }
+}
+
 pub async fn handle_deploy_project(
     cmd: DeployProject,
     current_user: CurrentUser,
-) -> Result<(), ServiceError> {
+) -> Result<Uuid, ServiceError> {
     let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
     user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    // Reject the whole list before anything is sent to Vultr
+    let graph = DependencyGraph::new(&cmd.command_list)?;
+    let placeholders = placeholder_ids(&cmd.command_list);
+    let created_types = created_types(&cmd.command_list);
+    let (ssh_keys, startup_scripts) = tokio::try_join!(
+        list_ssh_key(&cmd.project_id, connection_pool()),
+        list_startup_script(&cmd.project_id, connection_pool()),
+    )?;
+    for request in cmd.command_list.iter() {
+        let (command, _) = VultrCommand::resolve(request, &placeholders, &created_types)?;
+        verify_registered(&command, &ssh_keys, &startup_scripts)?;
+    }
     let vultr_api_key = get_vult_api_key(cmd.project_id, connection_pool()).await?;
     if vultr_api_key.api_key.is_empty() {
         return Err(ServiceError::NotFound);
     }
 
-    let ext = SqlExecutor::new();
-    ext.write().await.begin().await?;
-    let mut trx = ext.write().await;
-    let vultr_client = get_vultr_client(&vultr_api_key.api_key);
+    let job = DeployJob::new(
+        cmd.project_id,
+        current_user.email,
+        &cmd.command_list,
+        graph.waves().to_vec(),
+    );
+    // One job at a time per project, the session stream only follows the latest one.
+    // The check and the save are one step so two requests can't both get through.
+    static DEPLOY_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+    let _guard = DEPLOY_LOCK.lock().await;
+    let rocks_db = get_rocks_db().await;
+    match rocks_db.get_latest_deploy_job(cmd.project_id).await {
+        Ok(mut latest) if latest.is_stale(get_config().deploy_job_stale_after) => {
+            tracing::warn!(
+                "Deploy job {} of project {} is stale",
+                latest.id,
+                cmd.project_id
+            );
+            latest.fail(
+                format!(
+                    "No progress since {}, the deploy was abandoned",
+                    latest.update_dt
+                ),
+                None,
+            );
+            rocks_db.save_deploy_job(&latest).await?;
+        }
+        Ok(latest) if !latest.is_finished() => {
+            return Err(ServiceError::DeployInProgress(latest.id));
+        }
+        Ok(_) | Err(ServiceError::NotFound) => (),
+        Err(err) => return Err(err),
+    }
+    rocks_db.save_deploy_job(&job).await?;
+    let job_id = job.id;
+    let vultr_client = get_vultr_client(cmd.project_id, &vultr_api_key.api_key);
+    tokio::spawn(run_deploy_job(cmd, job, vultr_client));
+    Ok(job_id)
+}
+
+/// Deploys run in the background of the server that accepted them, the ones it was running when it
+/// stopped will never finish.
+pub async fn fail_interrupted_deploy_jobs() -> Result<(), ServiceError> {
+    let rocks_db = get_rocks_db().await;
+    for mut job in rocks_db.list_latest_deploy_jobs().await? {
+        if job.is_finished() {
+            continue;
+        }
+        job.fail("Interrupted by a server restart".to_string(), None);
+        rocks_db.save_deploy_job(&job).await?;
+    }
+    Ok(())
+}
+
+async fn run_deploy_job(cmd: DeployProject, job: DeployJob, vultr_client: Arc<VultrClient>) {
+    let job_id = job.id;
     let mut vultr_execution_context = VultrExecutionContext::new(vultr_client, cmd.project_id);
+    vultr_execution_context.deploy_job = Some(job);
+
     let project_id = cmd.project_id;
-    match cmd
-        .execute(&mut vultr_execution_context, trx.transaction())
+    let wait_for_ready = cmd.wait_for_ready;
+    let result = execute_deploy(cmd, &mut vultr_execution_context).await;
+    let succeeded = result.is_ok();
+    if let Some(mut job) = vultr_execution_context.deploy_job.take() {
+        match result {
+            Ok(()) => job.succeed(),
+            Err(ServiceError::DeploymentFailed(cause, compensation)) => job.fail(
+                format!("{:?}", cause),
+                serde_json::from_value(compensation).ok(),
+            ),
+            Err(err) => job.fail(format!("{:?}", err), None),
+        }
+        if let Err(err) = get_rocks_db().await.save_deploy_job(&job).await {
+            tracing::error!("Failed to save deploy job {job_id}: {err:?}");
+        }
+    }
+    if succeeded && wait_for_ready {
+        // The deploy itself is committed and finished, a resource that is slow to come up
+        // does not fail it nor keep the project from being deployed again
+        let config = get_config();
+        if let Err(err) = wait_until_ready(
+            project_id,
+            &vultr_execution_context,
+            config.vultr_ready_timeout,
+            config.vultr_ready_poll_interval,
+        )
         .await
-    {
-        Ok(_) => {
-            trx.commit().await?;
+        {
+            tracing::error!("Failed to wait for resources of project {project_id}: {err:?}");
         }
+    }
+}
+
+async fn execute_deploy(
+    cmd: DeployProject,
+    vultr_execution_context: &mut VultrExecutionContext,
+) -> Result<(), ServiceError> {
+    let project_id = cmd.project_id;
+    vultr_execution_context.stored = load_diagram_snapshot(project_id).await?;
+    let result = match cmd.execute(vultr_execution_context).await {
+        Ok(outcomes) => store_outcomes(outcomes, vultr_execution_context).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => (),
         Err(e) => {
+            let report = vultr_execution_context.compensate().await;
+            tracing::error!(
+                "Deploy failed for project {project_id}: {e:?}, {} step(s) compensated, {} not compensated",
+                report.compensated.len(),
+                report.not_compensated.len()
+            );
+            return Err(ServiceError::DeploymentFailed(
+                Box::new(e),
+                serde_json::to_value(report)?,
+            ));
+        }
+    }
+
+    publish_project_diagram(project_id).await
+}
+
+/// The deploy is done with Vultr by then, waiting on it never holds the transaction open.
+async fn store_outcomes(
+    outcomes: Vec<CommandOutcome>,
+    vultr_execution_context: &VultrExecutionContext,
+) -> Result<(), ServiceError> {
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    let mut trx = ext.write().await;
+    for outcome in outcomes {
+        if let Err(e) = vultr_execution_context
+            .store(outcome, trx.transaction())
+            .await
+        {
             trx.rollback().await?;
             return Err(e);
         }
     }
+    trx.commit().await?;
     trx.close().await;
+    Ok(())
+}
+
+/// Poll created compute and databases, storing and publishing every state change.
+async fn wait_until_ready(
+    project_id: Uuid,
+    vultr_execution_context: &VultrExecutionContext,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<(), ServiceError> {
+    let mut watch = ReadinessWatch::new(vultr_execution_context, timeout);
+    while !watch.is_finished() {
+        if watch.is_expired() {
+            tracing::warn!(
+                "Resources of project {project_id} not ready before the deadline: {:?}",
+                watch.pending()
+            );
+            return Ok(());
+        }
+        tokio::time::sleep(poll_interval).await;
+
+        let snapshot = load_diagram_snapshot(project_id).await?;
+        let report = watch.poll(&snapshot, vultr_execution_context).await?;
+        if !report.has_drift() {
+            continue;
+        }
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await?;
+        let mut trx = ext.write().await;
+        match report
+            .apply(vultr_execution_context, trx.transaction())
+            .await
+        {
+            Ok(()) => trx.commit().await?,
+            Err(e) => {
+                trx.rollback().await?;
+                return Err(e);
+            }
+        }
+        trx.close().await;
+        publish_project_diagram(project_id).await?;
+    }
+    Ok(())
+}
 
+async fn publish_project_diagram(project_id: Uuid) -> Result<(), ServiceError> {
     let res = update_project_diagram(project_id).await?;
     let rocks_db = get_rocks_db().await;
     let res_bytes = serde_json::to_vec(&res)?;
@@ -206,16 +450,35 @@ pub async fn handle_deploy_project(
     Ok(())
 }
 
-async fn update_project_diagram(project_id: Uuid) -> Result<Vec<ResourceResponse>, ServiceError> {
-    let mut res: Vec<ResourceResponse> = Vec::new();
+pub async fn handle_get_deploy_job(
+    project_id: Uuid,
+    job_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<DeployJob, ServiceError> {
+    let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
+    get_rocks_db()
+        .await
+        .get_deploy_job(project_id, job_id)
+        .await
+}
+
+async fn load_diagram_snapshot(project_id: Uuid) -> Result<DiagramSnapshot, ServiceError> {
     let conn = connection_pool();
     let (
-        mut compute_list,
-        mut managed_database,
-        mut object_storage,
-        mut block_storage,
-        mut firewall_group,
-        mut firewall_rule,
+        compute,
+        managed_database,
+        object_storage,
+        block_storage,
+        firewall_group,
+        firewall_rule,
+        vpc,
+        load_balancer,
+        dns_domain,
+        dns_record,
+        compute_snapshot,
+        database_user,
+        logical_database,
+        connection_pool,
     ) = tokio::try_join!(
         list_compute(&project_id, conn),
         list_managed_database(&project_id, conn),
@@ -223,144 +486,558 @@ async fn update_project_diagram(project_id: Uuid) -> Result<Vec<ResourceResponse
         list_block_storage(&project_id, conn),
         list_firewall_group(&project_id, conn),
         list_firewall_rule(&project_id, conn),
+        list_vpc(&project_id, conn),
+        list_load_balancer(&project_id, conn),
+        list_dns_domain(&project_id, conn),
+        list_dns_record(&project_id, conn),
+        list_compute_snapshot(&project_id, conn),
+        list_database_user(&project_id, conn),
+        list_logical_database(&project_id, conn),
+        list_connection_pool(&project_id, conn),
     )?;
-    compute_list.iter_mut().for_each(|compute| {
-        res.push(ResourceResponse::into_response(ResourceType::Compute, json!(compute)).unwrap());
-    });
-    managed_database.iter_mut().for_each(|managed_database| {
-        res.push(
-            ResourceResponse::into_response(ResourceType::ManagedDatabase, json!(managed_database))
-                .unwrap(),
-        );
-    });
-    object_storage.iter_mut().for_each(|object_storage| {
-        res.push(
-            ResourceResponse::into_response(ResourceType::ObjectStorage, json!(object_storage))
-                .unwrap(),
-        );
-    });
-    block_storage.iter_mut().for_each(|block_storage| {
-        res.push(
-            ResourceResponse::into_response(ResourceType::BlockStorage, json!(block_storage))
-                .unwrap(),
-        );
-    });
-    firewall_group.iter_mut().for_each(|firewall_group| {
-        res.push(
-            ResourceResponse::into_response(ResourceType::FirewallGroup, json!(firewall_group))
-                .unwrap(),
-        );
-    });
-    firewall_rule.iter_mut().for_each(|firewall_rule| {
-        res.push(
-            ResourceResponse::into_response(ResourceType::FirewallRule, json!(firewall_rule))
-                .unwrap(),
-        );
-    });
-    Ok(res)
+    Ok(DiagramSnapshot {
+        compute,
+        managed_database,
+        object_storage,
+        block_storage,
+        firewall_group,
+        firewall_rule,
+        vpc,
+        load_balancer,
+        dns_domain,
+        dns_record,
+        compute_snapshot,
+        database_user,
+        logical_database,
+        connection_pool,
+    })
 }
 
-pub async fn handle_request_architecture_suggestion(
-    cmd: RequestArchitectureSuggestion,
+async fn update_project_diagram(project_id: Uuid) -> Result<Vec<ResourceResponse>, ServiceError> {
+    let snapshot = load_diagram_snapshot(project_id).await?;
+    snapshot
+        .resources()
+        .into_iter()
+        .map(|(resource_type, attributes)| {
+            ResourceResponse::into_response(resource_type, attributes)
+        })
+        .collect()
+}
+
+pub async fn handle_plan_deploy_project(
+    cmd: DeployProject,
+    current_user: CurrentUser,
+) -> Result<DeployPlan, ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let snapshot = load_diagram_snapshot(cmd.project_id).await?;
+    Ok(cmd.plan(&snapshot))
+}
+
+pub async fn handle_detect_drift(
+    project_id: Uuid,
     current_user: CurrentUser,
+) -> Result<DriftReport, ServiceError> {
+    let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    let vultr_execution_context = VultrExecutionContext::new(vultr_client, project_id);
+    let snapshot = load_diagram_snapshot(project_id).await?;
+    DriftReport::detect(&snapshot, &vultr_execution_context).await
+}
+
+pub async fn handle_reconcile_drift(
     project_id: Uuid,
-) -> Result<ArchitectureRecommendation, ServiceError> {
+    current_user: CurrentUser,
+) -> Result<DriftReport, ServiceError> {
     let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
     user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
-    tracing::info!("Waiting for architecture recommendation...\nproject_id: {project_id}");
-    request_architecture_recommendation(cmd).await
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    let vultr_execution_context = VultrExecutionContext::new(vultr_client, project_id);
+    let snapshot = load_diagram_snapshot(project_id).await?;
+    let report = DriftReport::detect(&snapshot, &vultr_execution_context).await?;
+    if !report.has_drift() {
+        return Ok(report);
+    }
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    let mut trx = ext.write().await;
+    match report
+        .apply(&vultr_execution_context, trx.transaction())
+        .await
+    {
+        Ok(()) => trx.commit().await?,
+        Err(e) => {
+            trx.rollback().await?;
+            return Err(e);
+        }
+    }
+    trx.close().await;
+
+    publish_project_diagram(project_id).await?;
+    Ok(report)
 }
 
-#[cfg(test)]
-mod tests {
-    use openssl::rsa::Padding;
+async fn get_project_vultr_client(project_id: Uuid) -> Result<Arc<VultrClient>, ServiceError> {
+    let vultr_api_key = get_vult_api_key(project_id, connection_pool()).await?;
+    if vultr_api_key.api_key.is_empty() {
+        return Err(ServiceError::NotFound);
+    }
+    Ok(get_vultr_client(project_id, &vultr_api_key.api_key))
+}
 
-    use super::*;
-    use crate::{
-        adapter::repositories::{
-            connection_pool,
-            project::workspace::{get_project, get_user_role},
-        },
-        domain::auth::{commands::CreateUserAccount, private_key::PublicKey, UserAccountAggregate},
-        service::auth::tests::create_user_account_helper,
+pub async fn handle_list_import_candidates(
+    project_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<Vec<ImportCandidate>, ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    let inventory = LiveInventory::fetch(&vultr_client).await?;
+    let snapshot = load_diagram_snapshot(project_id).await?;
+    inventory.candidates(&snapshot)
+}
+
+pub async fn handle_import_resources(
+    cmd: ImportResources,
+    current_user: CurrentUser,
+) -> Result<Vec<ResourceResponse>, ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let vultr_client = get_project_vultr_client(cmd.project_id).await?;
+    let inventory = LiveInventory::fetch(&vultr_client).await?;
+    let snapshot = load_diagram_snapshot(cmd.project_id).await?;
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    let mut trx = ext.write().await;
+    let adopted = match cmd.execute(&inventory, &snapshot, trx.transaction()).await {
+        Ok(adopted) => {
+            trx.commit().await?;
+            adopted
+        }
+        Err(e) => {
+            trx.rollback().await?;
+            return Err(e);
+        }
     };
+    trx.close().await;
 
-    pub async fn create_project_helper() -> (UserAccountAggregate, ProjectAggregate, CurrentUser) {
-        let user_account = create_user_account_helper().await;
-        let create_project_cmd = CreateProject {
-            name: "test".to_string(),
-            description: "test".to_string(),
-        };
-        let current_user = CurrentUser {
-            email: user_account.email.clone(),
-        };
-        let project_id = handle_create_project(create_project_cmd.clone(), current_user.clone())
-            .await
-            .unwrap();
-        let project = get_project(project_id, connection_pool()).await.unwrap();
-        (user_account, project, current_user)
-    }
+    publish_project_diagram(cmd.project_id).await?;
+    Ok(adopted)
+}
 
-    #[tokio::test]
-    async fn test_create_project() {
-        // GIVEN
-        let create_user_cmd = CreateUserAccount {
-            email: format!("{}@test.com", Uuid::new_v4()),
-            password: format!("password{}", Uuid::new_v4()),
-            name: "test".to_string(),
-            phone_num: "01012345678".to_string(),
-        };
-        let create_project_cmd = CreateProject {
-            name: "test".to_string(),
-            description: "test".to_string(),
-        };
+pub async fn handle_register_ssh_key(
+    cmd: RegisterSshKey,
+    current_user: CurrentUser,
+) -> Result<SshKey, ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let vultr_client = get_project_vultr_client(cmd.project_id).await?;
+    let res = CreateSshKey::new(cmd.name.clone(), cmd.ssh_key.clone())
+        .execute(&vultr_client)
+        .await?;
+    let ssh_key = SshKey::new(cmd.project_id, vultr_id(&res)?, cmd.name, cmd.ssh_key);
 
-        // WHEN
-        let current_user = CurrentUser {
-            email: create_user_cmd.email.clone(),
-        };
-        let project_id = handle_create_project(create_project_cmd, current_user.clone())
-            .await
-            .unwrap();
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    insert_ssh_key(&ssh_key, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(ssh_key)
+}
 
-        // THEN
-        let project = get_project(project_id, connection_pool()).await.unwrap();
-        let user_role = get_user_role(project_id, &create_user_cmd.email, connection_pool())
-            .await
-            .unwrap();
-        assert_eq!(project.name, "test");
-        assert_eq!(project.description, "test");
-        assert_eq!(project.id, project_id);
-        assert_eq!(user_role.role, UserRole::Admin);
-        assert_eq!(user_role.project_id, project_id);
-        assert_eq!(user_role.user_email, create_user_cmd.email);
-    }
+pub async fn handle_list_ssh_keys(
+    project_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<Vec<SshKey>, ServiceError> {
+    let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
+    list_ssh_key(&project_id, connection_pool()).await
+}
 
-    #[tokio::test]
-    async fn test_delete_project() {
-        // GIVEN
-        let (user_account, project, _) = create_project_helper().await;
-        let delete_project_cmd = DeleteProject {
-            project_id: project.id,
-        };
+pub async fn handle_edit_ssh_key(
+    cmd: EditSshKey,
+    current_user: CurrentUser,
+) -> Result<SshKey, ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let mut ssh_key = get_ssh_key(&cmd.project_id, &cmd.id, connection_pool()).await?;
+    let vultr_client = get_project_vultr_client(cmd.project_id).await?;
+    UpdateSshKey::new(ssh_key.id, cmd.name.clone(), cmd.ssh_key.clone())
+        .execute(&vultr_client)
+        .await?;
+    ssh_key.edit(cmd.name, cmd.ssh_key);
 
-        // WHEN
-        handle_delete_project(delete_project_cmd).await.unwrap();
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    update_ssh_key(&ssh_key, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(ssh_key)
+}
 
-        // THEN
-        assert!(matches!(
-            get_project(project.id, connection_pool()).await,
-            Err(ServiceError::NotFound)
-        ));
-        assert!(matches!(
-            get_user_role(project.id, &user_account.email, connection_pool()).await,
-            Err(ServiceError::NotFound)
-        ));
+pub async fn handle_delete_ssh_key(
+    project_id: Uuid,
+    id: Uuid,
+    current_user: CurrentUser,
+) -> Result<(), ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let ssh_key = get_ssh_key(&project_id, &id, connection_pool()).await?;
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    match (DeleteSshKey {
+        id: Some(ssh_key.id),
+    })
+    .execute(&vultr_client)
+    .await
+    {
+        // Already removed at Vultr, only the stored key is left
+        Ok(()) | Err(ServiceError::NotFound) => {}
+        Err(err) => return Err(err),
     }
 
-    #[tokio::test]
-    async fn test_assign_role() {
-        // GIVEN
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    delete_ssh_key(&project_id, &id, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(())
+}
+
+pub async fn handle_register_startup_script(
+    cmd: RegisterStartupScript,
+    current_user: CurrentUser,
+) -> Result<StartupScript, ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let vultr_client = get_project_vultr_client(cmd.project_id).await?;
+    let res = CreateStartupScript::new(
+        cmd.name.clone(),
+        cmd.script_type.clone(),
+        cmd.script.clone(),
+    )
+    .execute(&vultr_client)
+    .await?;
+    let startup_script = StartupScript::new(
+        cmd.project_id,
+        vultr_id(&res)?,
+        cmd.name,
+        cmd.script_type,
+        cmd.script,
+    );
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    insert_startup_script(&startup_script, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(startup_script)
+}
+
+pub async fn handle_list_startup_scripts(
+    project_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<Vec<StartupScript>, ServiceError> {
+    let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
+    list_startup_script(&project_id, connection_pool()).await
+}
+
+pub async fn handle_edit_startup_script(
+    cmd: EditStartupScript,
+    current_user: CurrentUser,
+) -> Result<StartupScript, ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let mut startup_script =
+        get_startup_script(&cmd.project_id, &cmd.id, connection_pool()).await?;
+    let vultr_client = get_project_vultr_client(cmd.project_id).await?;
+    UpdateStartupScript::new(startup_script.id, cmd.name.clone(), cmd.script.clone())
+        .execute(&vultr_client)
+        .await?;
+    startup_script.edit(cmd.name, cmd.script);
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    update_startup_script(&startup_script, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(startup_script)
+}
+
+pub async fn handle_delete_startup_script(
+    project_id: Uuid,
+    id: Uuid,
+    current_user: CurrentUser,
+) -> Result<(), ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    let startup_script = get_startup_script(&project_id, &id, connection_pool()).await?;
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    match (DeleteStartupScript {
+        id: Some(startup_script.id),
+    })
+    .execute(&vultr_client)
+    .await
+    {
+        // Already removed at Vultr, only the stored script is left
+        Ok(()) | Err(ServiceError::NotFound) => {}
+        Err(err) => return Err(err),
+    }
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    delete_startup_script(&project_id, &id, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(())
+}
+
+/// S3 credentials of an object storage, read from Vultr and stored the first time they are needed.
+async fn get_s3_credentials(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+) -> Result<S3Credentials, ServiceError> {
+    match get_object_storage_credential(&project_id, &object_storage_id, connection_pool()).await {
+        Ok(credential) => return credential.open(),
+        Err(ServiceError::NotFound) => {}
+        Err(err) => return Err(err),
+    }
+    if !list_object_storage(&project_id, connection_pool())
+        .await?
+        .iter()
+        .any(|object_storage| object_storage.id == object_storage_id)
+    {
+        return Err(ServiceError::NotFound);
+    }
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    let credentials = GetObjectStorageCredentials::new(object_storage_id)
+        .execute(&vultr_client)
+        .await?;
+    store_s3_credentials(project_id, object_storage_id, credentials).await
+}
+
+async fn store_s3_credentials(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+    credentials: S3Credentials,
+) -> Result<S3Credentials, ServiceError> {
+    // Vultr hands out the keys once the subscription is active
+    if credentials.s3_access_key.is_empty() {
+        return Err(ServiceError::InvalidCommand(
+            "The object storage is not ready yet".to_string(),
+        ));
+    }
+    let credential =
+        ObjectStorageCredential::seal(project_id, object_storage_id, credentials.clone())?;
+
+    let ext = SqlExecutor::new();
+    ext.write().await.begin().await?;
+    upsert_object_storage_credential(&credential, ext.write().await.transaction()).await?;
+    ext.write().await.commit().await?;
+    ext.write().await.close().await;
+    Ok(credentials)
+}
+
+async fn get_s3_client(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+) -> Result<S3Client, ServiceError> {
+    S3Client::from_credentials(get_s3_credentials(project_id, object_storage_id).await?)
+}
+
+pub async fn handle_get_object_storage_credentials(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<S3Credentials, ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin])?;
+    get_s3_credentials(project_id, object_storage_id).await
+}
+
+pub async fn handle_regenerate_object_storage_keys(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<S3Credentials, ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin])?;
+    // The stored keys are the proof that the object storage is the project's
+    get_s3_credentials(project_id, object_storage_id).await?;
+    let vultr_client = get_project_vultr_client(project_id).await?;
+    let res = RegenerateObjectStorageKeys::new(object_storage_id)
+        .execute(&vultr_client)
+        .await?
+        .ok_or(ServiceError::ParseError)?;
+    store_s3_credentials(project_id, object_storage_id, serde_json::from_value(res)?).await
+}
+
+pub async fn handle_list_buckets(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+    current_user: CurrentUser,
+) -> Result<Vec<Bucket>, ServiceError> {
+    let _ = get_user_role(project_id, &current_user.email, connection_pool()).await?; // To check if the user is a member of the project
+    get_s3_client(project_id, object_storage_id)
+        .await?
+        .list_buckets()
+        .await
+}
+
+pub async fn handle_create_bucket(
+    cmd: CreateBucket,
+    current_user: CurrentUser,
+) -> Result<(), ServiceError> {
+    let user_role = get_user_role(cmd.project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    verify_bucket_name(&cmd.name)?;
+    get_s3_client(cmd.project_id, cmd.object_storage_id)
+        .await?
+        .create_bucket(&cmd.name)
+        .await
+}
+
+pub async fn handle_delete_bucket(
+    project_id: Uuid,
+    object_storage_id: Uuid,
+    name: String,
+    current_user: CurrentUser,
+) -> Result<(), ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    verify_bucket_name(&name)?;
+    get_s3_client(project_id, object_storage_id)
+        .await?
+        .delete_bucket(&name)
+        .await
+}
+
+/// Id Vultr assigned to a created SSH key or startup script.
+fn vultr_id(res: &Value) -> Result<Uuid, ServiceError> {
+    res["id"]
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or(ServiceError::ParseError)
+}
+
+pub async fn handle_request_architecture_suggestion(
+    cmd: RequestArchitectureSuggestion,
+    current_user: CurrentUser,
+    project_id: Uuid,
+) -> Result<ArchitectureRecommendation, ServiceError> {
+    let user_role = get_user_role(project_id, &current_user.email, connection_pool()).await?;
+    user_role.verify_role(&[UserRole::Admin, UserRole::Editor])?;
+    tracing::info!("Waiting for architecture recommendation...\nproject_id: {project_id}");
+    request_architecture_recommendation(cmd).await
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::rsa::Padding;
+
+    use super::*;
+    use crate::{
+        adapter::{
+            repositories::{
+                connection_pool,
+                project::{
+                    diagram::{insert_database_user, insert_firewall_group, insert_firewall_rule},
+                    provisioning::insert_ssh_key,
+                    workspace::{get_project, get_user_role},
+                },
+            },
+            request_dispensor::vultr::{
+                fake_server::FakeVultrServer, schemas::firewall::DeleteFirewallRule,
+            },
+        },
+        domain::{
+            auth::{commands::CreateUserAccount, private_key::PublicKey, UserAccountAggregate},
+            project::{
+                deploy_job::DeployStatus,
+                diagrams::{DatabaseUser, FirewallGroup, FirewallRule},
+                drift::DriftStatus,
+                enums::{BackupStatus, IpType, Protocol, ResourceType},
+            },
+        },
+        service::auth::tests::create_user_account_helper,
+    };
+
+    pub async fn create_project_helper() -> (UserAccountAggregate, ProjectAggregate, CurrentUser) {
+        let user_account = create_user_account_helper().await;
+        let create_project_cmd = CreateProject {
+            name: "test".to_string(),
+            description: "test".to_string(),
+        };
+        let current_user = CurrentUser {
+            email: user_account.email.clone(),
+        };
+        let project_id = handle_create_project(create_project_cmd.clone(), current_user.clone())
+            .await
+            .unwrap();
+        let project = get_project(project_id, connection_pool()).await.unwrap();
+        (user_account, project, current_user)
+    }
+
+    #[tokio::test]
+    async fn test_create_project() {
+        // GIVEN
+        let create_user_cmd = CreateUserAccount {
+            email: format!("{}@test.com", Uuid::new_v4()),
+            password: format!("password{}", Uuid::new_v4()),
+            name: "test".to_string(),
+            phone_num: "01012345678".to_string(),
+        };
+        let create_project_cmd = CreateProject {
+            name: "test".to_string(),
+            description: "test".to_string(),
+        };
+
+        // WHEN
+        let current_user = CurrentUser {
+            email: create_user_cmd.email.clone(),
+        };
+        let project_id = handle_create_project(create_project_cmd, current_user.clone())
+            .await
+            .unwrap();
+
+        // THEN
+        let project = get_project(project_id, connection_pool()).await.unwrap();
+        let user_role = get_user_role(project_id, &create_user_cmd.email, connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(project.name, "test");
+        assert_eq!(project.description, "test");
+        assert_eq!(project.id, project_id);
+        assert_eq!(user_role.role, UserRole::Admin);
+        assert_eq!(user_role.project_id, project_id);
+        assert_eq!(user_role.user_email, create_user_cmd.email);
+    }
+
+    #[tokio::test]
+    async fn test_delete_project() {
+        // GIVEN
+        let (user_account, project, _) = create_project_helper().await;
+        let delete_project_cmd = DeleteProject {
+            project_id: project.id,
+        };
+
+        // WHEN
+        handle_delete_project(delete_project_cmd).await.unwrap();
+
+        // THEN
+        assert!(matches!(
+            get_project(project.id, connection_pool()).await,
+            Err(ServiceError::NotFound)
+        ));
+        assert!(matches!(
+            get_user_role(project.id, &user_account.email, connection_pool()).await,
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_assign_role() {
+        // GIVEN
         let (user_account, project, current_user) = create_project_helper().await;
         let assign_role_cmd = AssignRole {
             project_id: project.id,
@@ -453,6 +1130,1040 @@ mod tests {
             Err(ServiceError::Unauthorized)
         ));
     }
+    #[tokio::test]
+    async fn test_plan_deploy_project() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let firewall_group = FirewallGroup {
+            project_id: project.id,
+            y: 10,
+            x: 10,
+            id: Uuid::new_v4(),
+            description: "stored group".to_string(),
+        };
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        insert_firewall_group(&firewall_group, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "UpdateFirewallGroup",
+                    "temp_id": "firewall-1",
+                    "position": { "x": 10, "y": 10 },
+                    "data": { "id": firewall_group.id, "description": "updated group" }
+                },
+                {
+                    "command_name": "DeleteCompute",
+                    "temp_id": "compute-1",
+                    "position": { "x": 0, "y": 0 },
+                    "data": { "id": Uuid::new_v4() }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        let plan = handle_plan_deploy_project(cmd, current_user).await.unwrap();
+
+        // THEN
+        assert_eq!(plan.update.len(), 1);
+        assert_eq!(
+            plan.update[0].before.as_ref().unwrap()["description"],
+            "stored group"
+        );
+        assert!(plan.delete.is_empty());
+        assert_eq!(plan.unresolved_references.len(), 1);
+        assert_eq!(plan.unresolved_references[0].temp_id, "compute-1");
+    }
+
+    #[tokio::test]
+    async fn test_deploy_project_runs_as_background_job() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        upsert_vult_api_key(
+            &VultApiKeyEntity::new(project.id, "api_key".to_string()),
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let cmd = DeployProject {
+            project_id: project.id,
+            command_list: vec![],
+            wait_for_ready: true,
+        };
+
+        // WHEN
+        let job_id = handle_deploy_project(cmd, current_user.clone())
+            .await
+            .unwrap();
+
+        // THEN
+        let mut job = handle_get_deploy_job(project.id, job_id, current_user.clone())
+            .await
+            .unwrap();
+        for _ in 0..50 {
+            if job.is_finished() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            job = handle_get_deploy_job(project.id, job_id, current_user.clone())
+                .await
+                .unwrap();
+        }
+        assert_eq!(job.id, job_id);
+        assert!(matches!(job.status, DeployStatus::Succeeded));
+
+        let mut last_update_dt = DateTime::<Utc>::MIN_UTC;
+        let latest_job = handle_deploy_job_sse(&current_user, project.id, &mut last_update_dt)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest_job.id, job_id);
+        assert!(
+            handle_deploy_job_sse(&current_user, project.id, &mut last_update_dt)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_firewall_rule_within_its_group() {
+        // GIVEN
+        let (_, project, _) = create_project_helper().await;
+        let fake_vultr = FakeVultrServer::start().await;
+        // Rule ids are only unique within a group, both groups have a rule 1
+        let group_ids = [Uuid::new_v4(), Uuid::new_v4()];
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        for firewall_group_id in group_ids {
+            let rule = FirewallRule {
+                project_id: project.id,
+                y: 0,
+                x: 0,
+                firewall_group_id,
+                id: 1,
+                action: "accept".to_string(),
+                port: "443".to_string(),
+                ip_type: IpType::V4,
+                protocol: Protocol::Tcp,
+                subnet: "0.0.0.0".to_string(),
+                subnet_size: 0,
+                notes: "https".to_string(),
+            };
+            insert_firewall_rule(&rule, ext.write().await.transaction())
+                .await
+                .unwrap();
+        }
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "MoveResource",
+                    "temp_id": "rule-1",
+                    "position": { "x": 120, "y": 40 },
+                    "data": {
+                        "resource_type": "FirewallRule",
+                        "id": 1,
+                        "parent_id": group_ids[0]
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        execute_deploy(
+            cmd,
+            &mut VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id),
+        )
+        .await
+        .unwrap();
+
+        // THEN
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        let position = |firewall_group_id: Uuid| {
+            let rule = snapshot
+                .firewall_rule
+                .iter()
+                .find(|rule| rule.firewall_group_id == firewall_group_id)
+                .unwrap();
+            (rule.x, rule.y)
+        };
+        assert_eq!(position(group_ids[0]), (120, 40));
+        assert_eq!(position(group_ids[1]), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_move_database_user_within_its_database() {
+        // GIVEN
+        let (_, project, _) = create_project_helper().await;
+        let fake_vultr = FakeVultrServer::start().await;
+        // Users are named per database, both databases have a "vultradmin"
+        let database_ids = [Uuid::new_v4(), Uuid::new_v4()];
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        for database_id in database_ids {
+            let user = DatabaseUser {
+                project_id: project.id,
+                y: 0,
+                x: 0,
+                database_id,
+                id: "vultradmin".to_string(),
+                encryption: None,
+            };
+            insert_database_user(&user, ext.write().await.transaction())
+                .await
+                .unwrap();
+        }
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "MoveResource",
+                    "temp_id": "user-1",
+                    "position": { "x": 120, "y": 40 },
+                    "data": {
+                        "resource_type": "DatabaseUser",
+                        "id": "vultradmin",
+                        "parent_id": database_ids[0]
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        execute_deploy(
+            cmd,
+            &mut VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id),
+        )
+        .await
+        .unwrap();
+
+        // THEN
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        let position = |database_id: Uuid| {
+            let user = snapshot
+                .database_user
+                .iter()
+                .find(|user| user.database_id == database_id)
+                .unwrap();
+            (user.x, user.y)
+        };
+        assert_eq!(position(database_ids[0]), (120, 40));
+        assert_eq!(position(database_ids[1]), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_move_resource() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let firewall_group = FirewallGroup {
+            project_id: project.id,
+            y: 0,
+            x: 0,
+            id: Uuid::new_v4(),
+            description: "stored group".to_string(),
+        };
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        insert_firewall_group(&firewall_group, ext.write().await.transaction())
+            .await
+            .unwrap();
+        upsert_vult_api_key(
+            &VultApiKeyEntity::new(project.id, "api_key".to_string()),
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "MoveResource",
+                    "temp_id": "firewall-1",
+                    "position": { "x": 120, "y": 40 },
+                    "data": { "resource_type": "FirewallGroup", "id": firewall_group.id }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        let job_id = handle_deploy_project(cmd, current_user.clone())
+            .await
+            .unwrap();
+
+        // THEN
+        let mut job = handle_get_deploy_job(project.id, job_id, current_user.clone())
+            .await
+            .unwrap();
+        for _ in 0..50 {
+            if job.is_finished() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            job = handle_get_deploy_job(project.id, job_id, current_user.clone())
+                .await
+                .unwrap();
+        }
+        assert!(matches!(job.status, DeployStatus::Succeeded));
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        assert_eq!(snapshot.firewall_group[0].x, 120);
+        assert_eq!(snapshot.firewall_group[0].y, 40);
+
+        let mut last_update_dt = DateTime::<Utc>::MIN_UTC;
+        let diagram = handle_session_sse(&current_user, project.id, &mut last_update_dt)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(diagram[0]["position"], json!({ "x": 120, "y": 40 }));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_project_against_fake_vultr() {
+        // GIVEN
+        let (_, project, _) = create_project_helper().await;
+        let fake_vultr = FakeVultrServer::start().await;
+        let mut vultr_execution_context =
+            VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id);
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "CreateVpc",
+                    "temp_id": "vpc-1",
+                    "position": { "x": 0, "y": 300 },
+                    "data": { "region": "ewr", "description": "private" }
+                },
+                {
+                    "command_name": "CreateFirewallGroup",
+                    "temp_id": "firewall-1",
+                    "position": { "x": 0, "y": 0 },
+                    "data": { "description": "web" }
+                },
+                {
+                    "command_name": "CreateFirewallRule",
+                    "temp_id": "rule-1",
+                    "position": { "x": 0, "y": 100 },
+                    "data": {
+                        "firewall_group_id": { "$ref": "firewall-1" },
+                        "ip_type": "v4",
+                        "protocol": "tcp",
+                        "port": "443",
+                        "subnet": "0.0.0.0",
+                        "subnet_size": 0,
+                        "notes": "https"
+                    }
+                },
+                {
+                    "command_name": "CreateCompute",
+                    "temp_id": "compute-1",
+                    "position": { "x": 200, "y": 0 },
+                    "data": {
+                        "region": "ewr",
+                        "plan": "vc2-1c-1gb",
+                        "label": "web",
+                        "os_id": 2284,
+                        "backups": "disabled",
+                        "hostname": "web"
+                    }
+                },
+                {
+                    "command_name": "CreateBlockStorage",
+                    "temp_id": "block-1",
+                    "position": { "x": 400, "y": 0 },
+                    "data": { "region": "ewr", "size_gb": 50, "label": "data" }
+                },
+                {
+                    "command_name": "AttachBlockStorageToCompute",
+                    "temp_id": "block-1",
+                    "position": { "x": 400, "y": 0 },
+                    "data": {
+                        "id": { "$ref": "block-1" },
+                        "instance_id": { "$ref": "compute-1" },
+                        "live": true
+                    }
+                },
+                {
+                    "command_name": "AttachVpcToCompute",
+                    "temp_id": "compute-1",
+                    "position": { "x": 200, "y": 0 },
+                    "data": {
+                        "id": { "$ref": "compute-1" },
+                        "vpc_id": { "$ref": "vpc-1" }
+                    }
+                },
+                {
+                    "command_name": "CreateManagedDatabase",
+                    "temp_id": "database-1",
+                    "position": { "x": 600, "y": 0 },
+                    "data": {
+                        "database_engine": "pg",
+                        "database_engine_version": 16,
+                        "region": "ewr",
+                        "plan": "vultr-dbaas-hobbyist-cc-1-25-1",
+                        "label": "db",
+                        "vpc_id": { "$ref": "vpc-1" }
+                    }
+                },
+                {
+                    "command_name": "CreateLoadBalancer",
+                    "temp_id": "lb-1",
+                    "position": { "x": 200, "y": 200 },
+                    "data": {
+                        "region": "ewr",
+                        "label": "web",
+                        "forwarding_rules": [{
+                            "frontend_protocol": "http",
+                            "frontend_port": 80,
+                            "backend_protocol": "http",
+                            "backend_port": 8080
+                        }],
+                        "instances": [{ "$ref": "compute-1" }]
+                    }
+                },
+                {
+                    "command_name": "CreateDnsDomain",
+                    "temp_id": "domain-1",
+                    "position": { "x": 0, "y": 450 },
+                    "data": { "domain": "example.com" }
+                },
+                {
+                    "command_name": "CreateDnsRecord",
+                    "temp_id": "record-1",
+                    "position": { "x": 200, "y": 450 },
+                    "data": {
+                        "domain": { "$ref": "domain-1" },
+                        "name": "www",
+                        "type": "A",
+                        "instance_id": { "$ref": "compute-1" }
+                    }
+                },
+                {
+                    "command_name": "CreateObjectStorage",
+                    "temp_id": "storage-1",
+                    "position": { "x": 800, "y": 0 },
+                    "data": { "cluster_id": 2, "tier_id": 1, "label": "assets" }
+                },
+                {
+                    "command_name": "SetComputeBackupSchedule",
+                    "temp_id": "compute-1",
+                    "position": { "x": 200, "y": 0 },
+                    "data": { "id": { "$ref": "compute-1" }, "type": "daily", "hour": 4 }
+                },
+                {
+                    "command_name": "CreateComputeSnapshot",
+                    "temp_id": "snapshot-1",
+                    "position": { "x": 200, "y": 600 },
+                    "data": {
+                        "instance_id": { "$ref": "compute-1" },
+                        "description": "before upgrade"
+                    }
+                },
+                {
+                    "command_name": "CreateDatabaseUser",
+                    "temp_id": "user-1",
+                    "position": { "x": 600, "y": 150 },
+                    "data": { "database_id": { "$ref": "database-1" }, "username": "app" }
+                },
+                {
+                    "command_name": "CreateLogicalDatabase",
+                    "temp_id": "db-1",
+                    "position": { "x": 700, "y": 150 },
+                    "data": { "database_id": { "$ref": "database-1" }, "name": "orders" }
+                },
+                {
+                    "command_name": "CreateConnectionPool",
+                    "temp_id": "pool-1",
+                    "position": { "x": 800, "y": 150 },
+                    "data": {
+                        "database_id": { "$ref": "database-1" },
+                        "name": "orders-pool",
+                        "database": "orders",
+                        "username": "app",
+                        "mode": "transaction",
+                        "size": 10
+                    }
+                },
+                {
+                    "command_name": "RestoreCompute",
+                    "temp_id": "compute-1",
+                    "position": { "x": 200, "y": 0 },
+                    "data": {
+                        "id": { "$ref": "compute-1" },
+                        "snapshot_id": { "$ref": "snapshot-1" }
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        execute_deploy(cmd, &mut vultr_execution_context)
+            .await
+            .unwrap();
+        wait_until_ready(
+            project.id,
+            &vultr_execution_context,
+            Duration::from_secs(5),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        // THEN
+        let firewall_groups = fake_vultr.resources("firewalls");
+        assert_eq!(firewall_groups.len(), 1);
+        let firewall_group_id = firewall_groups[0]["id"].as_str().unwrap();
+        assert_eq!(fake_vultr.firewall_rules(firewall_group_id).len(), 1);
+        let computes = fake_vultr.resources("instances");
+        assert_eq!(computes.len(), 1);
+        let blocks = fake_vultr.resources("blocks");
+        assert_eq!(blocks[0]["attached_to_instance"], computes[0]["id"]);
+        assert_eq!(fake_vultr.resources("databases").len(), 1);
+        assert_eq!(fake_vultr.resources("object-storage").len(), 1);
+        let vpc_id = fake_vultr.resources("vpcs")[0]["id"].clone();
+        assert_eq!(
+            fake_vultr.vpc_attachments(computes[0]["id"].as_str().unwrap()),
+            vec![vpc_id.as_str().unwrap()]
+        );
+
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        assert_eq!(snapshot.firewall_group[0].description, "web");
+        assert_eq!(snapshot.firewall_rule.len(), 1);
+        assert_eq!(snapshot.compute[0].status, "active");
+        assert_eq!(snapshot.compute[0].main_ip, "192.0.2.10");
+        assert_eq!(snapshot.compute[0].x, 200);
+        assert_eq!(
+            snapshot.block_storage[0].attached_to_instance,
+            snapshot.compute[0].id
+        );
+        assert_eq!(snapshot.managed_database[0].status, "Running");
+        assert_eq!(snapshot.object_storage[0].label, "assets");
+        assert_eq!(snapshot.vpc[0].v4_subnet, "10.1.96.0");
+        assert_eq!(snapshot.vpc[0].y, 300);
+        assert_eq!(
+            snapshot.compute[0].vpc_id,
+            Some(snapshot.vpc[0].id.to_string())
+        );
+        assert_eq!(
+            snapshot.managed_database[0].vpc_id,
+            snapshot.vpc[0].id.to_string()
+        );
+        let load_balancer = &snapshot.load_balancer[0];
+        assert_eq!(load_balancer.status, "active");
+        assert_eq!(load_balancer.ipv4, "192.0.2.20");
+        assert_eq!(load_balancer.instances, vec![snapshot.compute[0].id]);
+        assert!(load_balancer.forwarding_rules[0].id.is_some());
+        assert!(!load_balancer.has_ssl);
+        // The record took the address of the compute once it was active
+        assert_eq!(
+            fake_vultr.dns_records("example.com")[0]["data"],
+            "192.0.2.10"
+        );
+        assert_eq!(snapshot.dns_domain[0].id, "example.com");
+        let dns_record = &snapshot.dns_record[0];
+        assert_eq!(dns_record.domain, "example.com");
+        assert_eq!(dns_record.data, "192.0.2.10");
+        assert_eq!(dns_record.target_id, Some(snapshot.compute[0].id));
+        // Attaching kept the backups Vultr won't report
+        assert!(matches!(
+            snapshot.compute[0].auto_backups,
+            Some(BackupStatus::Disabled)
+        ));
+        let compute_id = computes[0]["id"].as_str().unwrap();
+        assert_eq!(
+            fake_vultr.backup_schedule(compute_id),
+            Some(json!({ "type": "daily", "hour": 4 }))
+        );
+        let backup_schedule = snapshot.compute[0].backup_schedule.as_ref().unwrap();
+        assert_eq!(backup_schedule.hour, 4);
+        let compute_snapshot = &snapshot.compute_snapshot[0];
+        assert_eq!(compute_snapshot.instance_id, Some(snapshot.compute[0].id));
+        assert_eq!(compute_snapshot.status, "complete");
+        assert_eq!(
+            fake_vultr.restores(compute_id),
+            vec![compute_snapshot.id.to_string()]
+        );
+        let database_id = snapshot.managed_database[0].id;
+        assert_eq!(snapshot.database_user[0].id, "app");
+        assert_eq!(snapshot.database_user[0].database_id, database_id);
+        assert_eq!(snapshot.logical_database[0].id, "orders");
+        assert_eq!(snapshot.connection_pool[0].size, 10);
+        assert_eq!(
+            (snapshot.connection_pool[0].x, snapshot.connection_pool[0].y),
+            (800, 150)
+        );
+        // Vultr generated a password, which stays out of the published diagram
+        let users = fake_vultr.database_children(&database_id.to_string(), "users");
+        assert!(users[0]["password"].is_string());
+        let diagram = get_rocks_db()
+            .await
+            .get(get_diagram_key(project.id).as_bytes())
+            .await
+            .unwrap();
+        assert!(!String::from_utf8(diagram).unwrap().contains("password"));
+    }
+
+    #[tokio::test]
+    async fn test_update_firewall_rule_against_fake_vultr() {
+        // GIVEN
+        let (_, project, _) = create_project_helper().await;
+        let fake_vultr = FakeVultrServer::start().await;
+        let mut vultr_execution_context =
+            VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id);
+        let rule = json!({
+            "firewall_group_id": { "$ref": "firewall-1" },
+            "ip_type": "v4",
+            "protocol": "tcp",
+            "port": "443",
+            "subnet": "0.0.0.0",
+            "subnet_size": 0,
+            "notes": "https"
+        });
+        let mut update = rule.clone();
+        update["firewall_rule_id"] = json!({ "$ref": "rule-1" });
+        update["port"] = json!("8443");
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "CreateFirewallGroup",
+                    "temp_id": "firewall-1",
+                    "position": { "x": 0, "y": 0 },
+                    "data": { "description": "web" }
+                },
+                {
+                    "command_name": "CreateFirewallRule",
+                    "temp_id": "rule-1",
+                    "position": { "x": 0, "y": 100 },
+                    "data": rule
+                },
+                {
+                    "command_name": "UpdateFirewallRule",
+                    "temp_id": "rule-1",
+                    "position": { "x": 40, "y": 100 },
+                    "data": update
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        execute_deploy(cmd, &mut vultr_execution_context)
+            .await
+            .unwrap();
+
+        // THEN
+        let firewall_group_id = fake_vultr.resources("firewalls")[0]["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let firewall_rules = fake_vultr.firewall_rules(&firewall_group_id);
+        assert_eq!(firewall_rules.len(), 1);
+        assert_eq!(firewall_rules[0]["port"], "8443");
+
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        assert_eq!(snapshot.firewall_rule.len(), 1);
+        let stored_rule = &snapshot.firewall_rule[0];
+        assert_eq!(stored_rule.id, firewall_rules[0]["id"].as_i64().unwrap());
+        assert_eq!(stored_rule.port, "8443");
+        assert_eq!(stored_rule.firewall_group_id.to_string(), firewall_group_id);
+        assert_eq!((stored_rule.x, stored_rule.y), (40, 100));
+    }
+
+    #[tokio::test]
+    async fn test_update_stored_firewall_rule_against_fake_vultr() {
+        // GIVEN
+        let (_, project, _) = create_project_helper().await;
+        let fake_vultr = FakeVultrServer::start().await;
+        let deploy = |command_list: Value| -> DeployProject {
+            serde_json::from_value(json!({
+                "project_id": project.id,
+                "command_list": command_list
+            }))
+            .unwrap()
+        };
+        let rule = json!({
+            "firewall_group_id": { "$ref": "firewall-1" },
+            "ip_type": "v4",
+            "protocol": "tcp",
+            "port": "443",
+            "subnet": "0.0.0.0",
+            "subnet_size": 0,
+            "notes": "https"
+        });
+        let first_deploy = deploy(json!([
+            {
+                "command_name": "CreateFirewallGroup",
+                "temp_id": "firewall-1",
+                "position": { "x": 0, "y": 0 },
+                "data": { "description": "web" }
+            },
+            {
+                "command_name": "CreateFirewallRule",
+                "temp_id": "rule-1",
+                "position": { "x": 0, "y": 100 },
+                "data": rule
+            }
+        ]));
+        execute_deploy(
+            first_deploy,
+            &mut VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id),
+        )
+        .await
+        .unwrap();
+        let stored = load_diagram_snapshot(project.id).await.unwrap();
+        let stored_rule = &stored.firewall_rule[0];
+
+        // A later deploy names the stored rule by its id
+        let mut update = rule.clone();
+        update["firewall_group_id"] = json!(stored_rule.firewall_group_id);
+        update["firewall_rule_id"] = json!(stored_rule.id);
+        update["port"] = json!("8443");
+        let second_deploy = deploy(json!([
+            {
+                "command_name": "UpdateFirewallRule",
+                "temp_id": "rule-1",
+                "position": { "x": 40, "y": 100 },
+                "data": update
+            }
+        ]));
+        let plan = second_deploy.plan(&stored);
+
+        // WHEN
+        execute_deploy(
+            second_deploy,
+            &mut VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id),
+        )
+        .await
+        .unwrap();
+
+        // THEN
+        assert!(plan.unresolved_references.is_empty());
+        assert_eq!(plan.update[0].resource_id, Some(stored_rule.id.to_string()));
+
+        let firewall_group_id = stored_rule.firewall_group_id.to_string();
+        let firewall_rules = fake_vultr.firewall_rules(&firewall_group_id);
+        assert_eq!(firewall_rules.len(), 1);
+        assert_eq!(firewall_rules[0]["port"], "8443");
+
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        assert_eq!(snapshot.firewall_rule.len(), 1);
+        let updated_rule = &snapshot.firewall_rule[0];
+        assert_eq!(updated_rule.id, firewall_rules[0]["id"].as_i64().unwrap());
+        assert_ne!(updated_rule.id, stored_rule.id);
+        assert_eq!(updated_rule.port, "8443");
+        assert_eq!((updated_rule.x, updated_rule.y), (40, 100));
+    }
+
+    #[tokio::test]
+    async fn test_detect_drift_of_firewall_rules_against_fake_vultr() {
+        // GIVEN
+        let (_, project, _) = create_project_helper().await;
+        let fake_vultr = FakeVultrServer::start().await;
+        let vultr_execution_context =
+            VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id);
+        let rule = |port: &str| {
+            json!({
+                "firewall_group_id": { "$ref": "firewall-1" },
+                "ip_type": "v4",
+                "protocol": "tcp",
+                "port": port,
+                "subnet": "0.0.0.0",
+                "subnet_size": 0,
+                "notes": "web"
+            })
+        };
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "CreateFirewallGroup",
+                    "temp_id": "firewall-1",
+                    "position": { "x": 0, "y": 0 },
+                    "data": { "description": "web" }
+                },
+                {
+                    "command_name": "CreateFirewallRule",
+                    "temp_id": "rule-1",
+                    "position": { "x": 0, "y": 100 },
+                    "data": rule("80")
+                },
+                {
+                    "command_name": "CreateFirewallRule",
+                    "temp_id": "rule-2",
+                    "position": { "x": 100, "y": 100 },
+                    "data": rule("443")
+                }
+            ]
+        }))
+        .unwrap();
+        execute_deploy(
+            cmd,
+            &mut VultrExecutionContext::new(Arc::new(fake_vultr.client()), project.id),
+        )
+        .await
+        .unwrap();
+        let snapshot = load_diagram_snapshot(project.id).await.unwrap();
+        let removed = snapshot
+            .firewall_rule
+            .iter()
+            .find(|rule| rule.port == "443")
+            .unwrap();
+        // Removed at Vultr outside of this server
+        DeleteFirewallRule {
+            firewall_group_id: removed.firewall_group_id,
+            firewall_rule_id: Some(removed.id),
+        }
+        .execute(&fake_vultr.client())
+        .await
+        .unwrap();
+
+        // WHEN
+        let report = DriftReport::detect(&snapshot, &vultr_execution_context)
+            .await
+            .unwrap();
+
+        // THEN
+        let status = |port: &str| {
+            let rule = snapshot
+                .firewall_rule
+                .iter()
+                .find(|rule| rule.port == port)
+                .unwrap();
+            report
+                .resources
+                .iter()
+                .find(|drift| {
+                    drift.resource_type == ResourceType::FirewallRule
+                        && drift.resource_id == rule.id.to_string()
+                })
+                .unwrap()
+                .status
+        };
+        assert_eq!(status("80"), DriftStatus::InSync);
+        assert_eq!(status("443"), DriftStatus::Missing);
+    }
+
+    #[tokio::test]
+    async fn test_detect_drift_of_empty_project() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        upsert_vult_api_key(
+            &VultApiKeyEntity::new(project.id, "api_key".to_string()),
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+
+        // WHEN
+        let report = handle_detect_drift(project.id, current_user.clone())
+            .await
+            .unwrap();
+
+        // THEN
+        assert!(report.resources.is_empty());
+        assert!(!report.has_drift());
+        let report = handle_reconcile_drift(project.id, current_user)
+            .await
+            .unwrap();
+        assert!(report.resources.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_project_while_another_is_running() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        upsert_vult_api_key(
+            &VultApiKeyEntity::new(project.id, "api_key".to_string()),
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let running = DeployJob::new(project.id, current_user.email.clone(), &[], vec![]);
+        get_rocks_db()
+            .await
+            .save_deploy_job(&running)
+            .await
+            .unwrap();
+        let cmd = || DeployProject {
+            project_id: project.id,
+            command_list: vec![],
+            wait_for_ready: false,
+        };
+
+        // WHEN
+        let result = handle_deploy_project(cmd(), current_user.clone()).await;
+
+        // THEN
+        assert!(matches!(result, Err(ServiceError::DeployInProgress(id)) if id == running.id));
+        let mut finished = running;
+        finished.succeed();
+        get_rocks_db()
+            .await
+            .save_deploy_job(&finished)
+            .await
+            .unwrap();
+        assert!(handle_deploy_project(cmd(), current_user).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_project_after_a_stale_one() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        upsert_vult_api_key(
+            &VultApiKeyEntity::new(project.id, "api_key".to_string()),
+            ext.write().await.transaction(),
+        )
+        .await
+        .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let mut stale = DeployJob::new(project.id, current_user.email.clone(), &[], vec![]);
+        stale.update_dt -= get_config().deploy_job_stale_after + Duration::from_secs(1);
+        get_rocks_db().await.save_deploy_job(&stale).await.unwrap();
+        let cmd = DeployProject {
+            project_id: project.id,
+            command_list: vec![],
+            wait_for_ready: false,
+        };
+
+        // WHEN
+        let result = handle_deploy_project(cmd, current_user).await;
+
+        // THEN
+        assert!(result.is_ok());
+        let stale = get_rocks_db()
+            .await
+            .get_deploy_job(project.id, stale.id)
+            .await
+            .unwrap();
+        assert_eq!(stale.status, DeployStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_fail_interrupted_deploy_jobs() {
+        // GIVEN
+        let project_id = Uuid::new_v4();
+        let running = DeployJob::new(project_id, "test@test.com".to_string(), &[], vec![]);
+        get_rocks_db()
+            .await
+            .save_deploy_job(&running)
+            .await
+            .unwrap();
+
+        // WHEN
+        fail_interrupted_deploy_jobs().await.unwrap();
+
+        // THEN
+        let job = get_rocks_db()
+            .await
+            .get_latest_deploy_job(project_id)
+            .await
+            .unwrap();
+        assert_eq!(job.status, DeployStatus::Failed);
+        assert!(job.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_project_with_unknown_command() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "CreateComputeUpdate",
+                    "temp_id": "compute-1",
+                    "position": { "x": 0, "y": 0 },
+                    "data": {}
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        let result = handle_deploy_project(cmd, current_user).await;
+
+        // THEN
+        assert!(matches!(result, Err(ServiceError::InvalidCommand(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_project_with_ssh_key_of_another_project() {
+        // GIVEN
+        let (_, project, current_user) = create_project_helper().await;
+        let (_, other_project, _) = create_project_helper().await;
+        let ssh_key = SshKey::new(
+            other_project.id,
+            Uuid::new_v4(),
+            "deploy".to_string(),
+            "ssh-ed25519 AAAA".to_string(),
+        );
+        let ext = SqlExecutor::new();
+        ext.write().await.begin().await.unwrap();
+        insert_ssh_key(&ssh_key, ext.write().await.transaction())
+            .await
+            .unwrap();
+        ext.write().await.commit().await.unwrap();
+        ext.write().await.close().await;
+        let cmd: DeployProject = serde_json::from_value(json!({
+            "project_id": project.id,
+            "command_list": [
+                {
+                    "command_name": "CreateCompute",
+                    "temp_id": "compute-1",
+                    "position": { "x": 0, "y": 0 },
+                    "data": {
+                        "region": "ewr",
+                        "plan": "vc2-1c-1gb",
+                        "label": "web",
+                        "os_id": 2284,
+                        "backups": "disabled",
+                        "hostname": "web",
+                        "sshkey_id": [ssh_key.id]
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+
+        // WHEN
+        let result = handle_deploy_project(cmd, current_user).await;
+
+        // THEN
+        assert!(matches!(result, Err(ServiceError::InvalidCommand(_))));
+    }
+
     #[tokio::test]
     async fn test_get_public_key() {
         // GIVEN
@@ -463,7 +2174,7 @@ mod tests {
         assert!(!public_key_1.is_empty());
 
         // WHEN
-        let public_key = PublicKey::from_pem(&public_key_1.as_bytes()).unwrap();
+        let public_key = PublicKey::from_pem(public_key_1.as_bytes()).unwrap();
         let private_key = crate::domain::auth::private_key::PrivateKey::from_pem(
             &rocks_db
                 .get(crate::adapter::kv_store::rocks_db::RocksDB::PRIVATE_KEY_NAME)